@@ -0,0 +1,105 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::Size,
+    widget::{
+        accessibility::{
+            testing::MemoryAccessibility, AccessAction, AccessActionRequest, AccessTree,
+        },
+        button::TextButton,
+        center::Center,
+        flex::Flex,
+        sized_box::SizedBox,
+        slider::Slider,
+        switch::Switch,
+        Widget,
+    },
+};
+
+#[derive(Default)]
+struct State {
+    count: i32,
+    volume: f32,
+    muted: bool,
+}
+
+#[derive(Clone)]
+enum Message {
+    Increment,
+    SetVolume(f32),
+    SetMuted(bool),
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, msg: Self::Message, _: &mut MessageCtx<Self>) {
+        match msg {
+            Message::Increment => self.count += 1,
+            Message::SetVolume(volume) => self.volume = volume,
+            Message::SetMuted(muted) => self.muted = muted,
+        }
+    }
+}
+
+fn build(state: &State) -> Box<dyn Widget<State>> {
+    Box::new(Center::new(
+        Flex::column()
+            .with_spacing(10.0)
+            .push(TextButton::new(
+                &format!("Clicked {} times", state.count),
+                20.0,
+            ))
+            .push(TextButton::new("Increment", 20.0).on_click(Message::Increment))
+            .push(SizedBox::new(
+                Size::new(200.0, 20.0),
+                Slider::new(0.0, 10.0, 0.0, true)
+                    .bind(|s: &State| &s.volume, |v| Message::SetVolume(*v)),
+            ))
+            .push(SizedBox::new(
+                Size::new(40.0, 20.0),
+                Switch::new().bind(|s: &State| &s.muted, |m| Message::SetMuted(*m)),
+            )),
+    ))
+}
+
+fn print_tree(tree: &AccessTree) {
+    let mut nodes: Vec<_> = tree.iter().collect();
+    nodes.sort_by_key(|(id, _)| *id);
+    for (id, node) in nodes {
+        println!(
+            "{:>20} {:?} {:?} {:?}",
+            id, node.role, node.name, node.value
+        );
+    }
+    println!();
+}
+
+fn main() {
+    let app = App::new();
+
+    // Stands in for a screen reader, it prints what it would announce and presses a button once
+    let reader = MemoryAccessibility::new();
+    let request =
+        WindowRequest::new("Accessibility", 400, 300, build).with_accessibility(reader.clone());
+
+    let mut updates = 0;
+    let mut pressed = false;
+    let delegate = UIAppDelegate::new(request).on_update(move |_, _| {
+        if reader.update_count() == updates {
+            return;
+        }
+
+        updates = reader.update_count();
+        let tree = reader.tree();
+        print_tree(&tree);
+
+        if !pressed {
+            if let Some((id, _)) = tree.by_name("Increment") {
+                reader.perform(AccessActionRequest::new(id, AccessAction::Press));
+                pressed = true
+            }
+        }
+    });
+
+    app.run(delegate, State::default());
+}
@@ -0,0 +1,63 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    widget::{button::TextButton, center::Center, container::Container, flex::Flex},
+};
+use std::{thread, time::Duration};
+
+#[derive(Default)]
+struct State {
+    price: f32,
+    updates: usize,
+}
+
+#[derive(Clone)]
+enum Message {
+    PriceChanged(f32),
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, msg: Self::Message, _: &mut MessageCtx<Self>) {
+        match msg {
+            Message::PriceChanged(price) => {
+                self.price = price;
+                self.updates += 1
+            }
+        }
+    }
+}
+
+fn main() {
+    let app = App::new();
+
+    // A backend thread publishing prices, each message wakes the ui even when the mouse doesn't move
+    let handle = app.handle();
+    thread::spawn(move || {
+        let mut price = 100.0f32;
+        for tick in 0..120 {
+            thread::sleep(Duration::from_millis(500));
+            price += (tick as f32 * 1.7).sin();
+            if handle.send(Message::PriceChanged(price)).is_err() {
+                return;
+            }
+        }
+
+        let _ = handle.quit();
+    });
+
+    let request = WindowRequest::new("Backend", 400, 200, |state: &State| {
+        Box::new(Center::new(
+            Flex::column()
+                .with_spacing(10.0)
+                .push(TextButton::new(&format!("{:.2}", state.price), 24.0))
+                .push(
+                    Container::new(TextButton::new(&format!("{} updates", state.updates), 16.0))
+                        .with_padding(10.0),
+                ),
+        ))
+    });
+    let delegate = UIAppDelegate::new(request);
+
+    app.run(delegate, State::default());
+}
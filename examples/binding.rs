@@ -0,0 +1,62 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::Size,
+    widget::{
+        center::Center, flex::Flex, sized_box::SizedBox, slider::Slider, switch::Switch,
+        text_editor::TextBox,
+    },
+};
+
+#[derive(Default)]
+struct State {
+    volume: f32,
+    muted: bool,
+    name: String,
+}
+
+#[derive(Clone)]
+enum Message {
+    SetVolume(f32),
+    SetMuted(bool),
+    SetName(String),
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, msg: Self::Message, _: &mut MessageCtx<Self>) {
+        match msg {
+            Message::SetVolume(volume) => self.volume = volume,
+            Message::SetMuted(muted) => self.muted = muted,
+            Message::SetName(name) => self.name = name,
+        }
+    }
+}
+
+fn main() {
+    let app = App::new();
+
+    let request = WindowRequest::new("Binding Example", 400, 300, |_state| {
+        Box::new(Center::new(
+            Flex::column()
+                .with_spacing(20.0)
+                .push(SizedBox::new(
+                    Size::new(300.0, 30.0),
+                    Slider::new(0.0, 1.0, 0.0, false)
+                        .bind(|s: &State| &s.volume, |v| Message::SetVolume(*v)),
+                ))
+                .push(SizedBox::new(
+                    Size::new(60.0, 30.0),
+                    Switch::new().bind(|s: &State| &s.muted, |m| Message::SetMuted(*m)),
+                ))
+                .push(SizedBox::new(
+                    Size::new(300.0, 30.0),
+                    TextBox::new("Name")
+                        .bind(|s: &State| &s.name, |n| Message::SetName(n.to_string())),
+                )),
+        ))
+    });
+    let delegate = UIAppDelegate::new(request);
+
+    app.run(delegate, State::default());
+}
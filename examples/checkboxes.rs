@@ -0,0 +1,123 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::Size,
+    widget::{
+        center::Center,
+        checkbox::{CheckState, Checkbox},
+        flex::Flex,
+        label::Label,
+        radio_group::RadioGroup,
+        sized_box::SizedBox,
+        Widget,
+    },
+};
+
+const TOPPINGS: [&str; 3] = ["Cheese", "Mushrooms", "Olives"];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum PizzaSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum Crust {
+    #[default]
+    Thin,
+    Thick,
+}
+
+#[derive(Default)]
+struct State {
+    toppings: [bool; 3],
+    // Checked, unchecked or indeterminate depending on the toppings
+    all_toppings: CheckState,
+    size: PizzaSize,
+    crust: Crust,
+    extra_napkins: bool,
+}
+
+impl State {
+    fn update_all_toppings(&mut self) {
+        self.all_toppings = match self.toppings.iter().filter(|topping| **topping).count() {
+            0 => CheckState::Unchecked,
+            count if count == self.toppings.len() => CheckState::Checked,
+            _ => CheckState::Indeterminate,
+        }
+    }
+}
+
+#[derive(Clone)]
+enum Message {
+    SetTopping(usize, bool),
+    SetAllToppings(CheckState),
+    SetSize(PizzaSize),
+    SetCrust(Crust),
+    SetExtraNapkins(bool),
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, message: Self::Message, _: &mut MessageCtx<Self>) {
+        match message {
+            Message::SetTopping(index, checked) => self.toppings[index] = checked,
+            Message::SetAllToppings(all) => self.toppings = [all == CheckState::Checked; 3],
+            Message::SetSize(size) => self.size = size,
+            Message::SetCrust(crust) => self.crust = crust,
+            Message::SetExtraNapkins(extra) => self.extra_napkins = extra,
+        }
+        self.update_all_toppings();
+    }
+}
+
+fn build(_: &State) -> Box<dyn Widget<State>> {
+    let mut toppings = Flex::column().with_spacing(6.0).push(
+        Checkbox::new().with_label("All toppings").bind_tri_state(
+            |s: &State| &s.all_toppings,
+            |all| Message::SetAllToppings(*all),
+        ),
+    );
+    for (index, topping) in TOPPINGS.iter().enumerate() {
+        toppings = toppings.push(Checkbox::new().with_label(topping).bind(
+            move |s: &State| &s.toppings[index],
+            move |checked| Message::SetTopping(index, *checked),
+        ));
+    }
+
+    let size = RadioGroup::column()
+        .with_option(PizzaSize::Small, "Small")
+        .with_option(PizzaSize::Medium, "Medium")
+        .with_option(PizzaSize::Large, "Large")
+        .bind(|s: &State| &s.size, |size| Message::SetSize(*size));
+
+    let crust = RadioGroup::row()
+        .with_spacing(16.0)
+        .with_option(Crust::Thin, "Thin crust")
+        .with_option(Crust::Thick, "Thick crust")
+        .bind(|s: &State| &s.crust, |crust| Message::SetCrust(*crust));
+
+    Box::new(Center::new(
+        Flex::column()
+            .with_spacing(16.0)
+            .push(SizedBox::new(
+                Size::new(400.0, 20.0),
+                Label::new("Click a control, then use space and the arrow keys"),
+            ))
+            .push(toppings)
+            .push(size)
+            .push(crust)
+            .push(Checkbox::new().with_label("Extra napkins").bind(
+                |s: &State| &s.extra_napkins,
+                |extra| Message::SetExtraNapkins(*extra),
+            )),
+    ))
+}
+
+fn main() {
+    let app = App::new();
+    let request = WindowRequest::new("Checkboxes", 500, 500, build);
+    app.run(UIAppDelegate::new(request), State::default());
+}
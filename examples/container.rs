@@ -0,0 +1,49 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::{Color, Point},
+    edge_insets::EdgeInsets,
+    widget::{
+        button::TextButton,
+        center::Center,
+        container::Container,
+        decoration::{Border, BorderSide, BoxShadow, LinearGradient},
+    },
+};
+
+struct State;
+
+impl AppState for State {
+    type Message = ();
+
+    fn handle_message(&mut self, _: Self::Message, _: &mut MessageCtx<Self>) {}
+}
+
+fn main() {
+    let app = App::new();
+
+    let request = WindowRequest::new("Container Example", 600, 400, |_state| {
+        Box::new(Center::new(
+            Container::new(TextButton::new("Decorated", 24.0))
+                .with_size(300.0, 150.0)
+                .with_padding(EdgeInsets::symmetric(40.0, 20.0))
+                .with_margin(10.0)
+                .with_background(LinearGradient::vertical(vec![
+                    Color::new(0xFFFFFFFF),
+                    Color::new(0xFFDDE3F0),
+                ]))
+                .with_border(
+                    Border::all(2.0, Color::new(0xFF766AC8))
+                        .with_bottom(BorderSide::new(4.0, Color::new(0xFF73C8A6))),
+                )
+                .with_corner_radius(12.0)
+                .with_shadow(BoxShadow::new(
+                    Color::new(0x40000000),
+                    Point::new(0.0, 4.0),
+                    12.0,
+                )),
+        ))
+    });
+    let delegate = UIAppDelegate::new(request);
+
+    app.run(delegate, State);
+}
@@ -0,0 +1,54 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::Size,
+    widget::{
+        button::TextButton, center::Center, container::Container, flex::Flex, sized_box::SizedBox,
+        text_editor::TextBox, WidgetExt,
+    },
+};
+
+#[derive(Default)]
+struct State {
+    count: usize,
+}
+
+#[derive(Clone)]
+enum Message {
+    Increment,
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, msg: Self::Message, _: &mut MessageCtx<Self>) {
+        match msg {
+            Message::Increment => self.count += 1,
+        }
+    }
+}
+
+fn main() {
+    let app = App::new();
+
+    // The builder runs again after every message. The text box keeps what was typed because it is matched by key.
+    let request = WindowRequest::new("Counter", 400, 200, |state: &State| {
+        Box::new(Center::new(
+            Flex::column()
+                .with_spacing(10.0)
+                .push(
+                    Container::new(
+                        TextButton::new(&format!("Clicked {} times", state.count), 24.0)
+                            .on_click(Message::Increment),
+                    )
+                    .with_padding(10.0),
+                )
+                .push(
+                    SizedBox::new(Size::new(300.0, 30.0), TextBox::new("Type something..."))
+                        .with_key("notes"),
+                ),
+        ))
+    });
+    let delegate = UIAppDelegate::new(request);
+
+    app.run(delegate, State::default());
+}
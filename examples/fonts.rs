@@ -0,0 +1,77 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::Size,
+    widget::{
+        button::TextButton,
+        center::Center,
+        flex::Flex,
+        label::{Label, RichText, Span},
+        sized_box::SizedBox,
+        Widget,
+    },
+};
+
+#[derive(Default)]
+struct State {
+    // The family of the font given on the command line
+    custom: Option<String>,
+}
+
+#[derive(Clone)]
+enum Message {}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, _: Self::Message, _: &mut MessageCtx<Self>) {}
+}
+
+fn row(child: impl Widget<State> + 'static) -> SizedBox<State> {
+    SizedBox::new(Size::new(500.0, 30.0), child)
+}
+
+fn build(state: &State) -> Box<dyn Widget<State>> {
+    let custom = match &state.custom {
+        Some(family) => Label::new(&format!("Registered from a file: {}", family))
+            .with_family(family)
+            .with_font_size(20.0),
+        None => Label::new("Pass the path of a .ttf or .otf file to see it here"),
+    };
+
+    Box::new(Center::new(
+        Flex::column()
+            .with_spacing(10.0)
+            .push(row(
+                Label::new("The theme's font, Arial or its fallbacks").with_font_size(20.0)
+            ))
+            .push(row(Label::new("The theme's serif family")
+                .with_family("serif")
+                .with_font_size(20.0)))
+            .push(row(Label::new("The theme's monospace family")
+                .with_family("monospace")
+                .with_font_size(20.0)))
+            .push(row(RichText::new(vec![
+                Span::new("Families mix in rich text, like "),
+                Span::new("Vec<String>").with_family("monospace"),
+                Span::new(" in a sentence."),
+            ])))
+            .push(row(custom))
+            .push(TextButton::new("Buttons use monospace too", 16.0)),
+    ))
+}
+
+fn main() {
+    let app = App::new();
+
+    // A bundled font would be registered from bytes, with include_bytes! and Fonts::register_font
+    let mut state = State::default();
+    if let Some(path) = std::env::args().nth(1) {
+        match app.fonts().register_font_file(&path) {
+            Ok(family) => state.custom = Some(family),
+            Err(error) => eprintln!("{}", error),
+        }
+    }
+
+    let request = WindowRequest::new("Fonts", 600, 400, build);
+    app.run(UIAppDelegate::new(request), state);
+}
@@ -0,0 +1,102 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::{Color, Size},
+    widget::{
+        button::{ButtonStyle, IconButton},
+        center::Center,
+        flex::Flex,
+        icon::Icon,
+        label::Label,
+        sized_box::SizedBox,
+        Widget,
+    },
+};
+
+// Drawn on a 24 unit grid, currentColor is the theme's icon color
+const HEART: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="24" height="24">
+  <path d="M12 21l-1.5-1.3C5.4 15.1 2 12.1 2 8.5 2 5.4 4.4 3 7.5 3c1.7 0 3.4.8 4.5 2.1C13.1 3.8 14.8 3 16.5 3 19.6 3 22 5.4 22 8.5c0 3.6-3.4 6.6-8.5 11.2L12 21z"/>
+</svg>"#;
+
+const SAVE: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24">
+  <path d="M17 3H5a2 2 0 0 0-2 2v14a2 2 0 0 0 2 2h14a2 2 0 0 0 2-2V7l-4-4zm-5 16a3 3 0 1 1 0-6 3 3 0 0 1 0 6zm3-10H5V5h10v4z"/>
+</svg>"#;
+
+const SETTINGS: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 24 24" fill="none">
+  <circle cx="12" cy="12" r="3" stroke="currentColor" stroke-width="2"/>
+  <circle cx="12" cy="12" r="8" stroke="currentColor" stroke-width="2" stroke-dasharray="4 2.3"/>
+</svg>"#;
+
+#[derive(Default)]
+struct State {
+    likes: usize,
+}
+
+#[derive(Clone)]
+enum Message {
+    Like,
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, message: Self::Message, _: &mut MessageCtx<Self>) {
+        match message {
+            Message::Like => self.likes += 1,
+        }
+    }
+}
+
+fn build(state: &State) -> Box<dyn Widget<State>> {
+    let sizes = Flex::row()
+        .with_spacing(16.0)
+        .push(Icon::new("heart").with_size(16.0))
+        .push(Icon::new("heart").with_size(32.0))
+        .push(Icon::new("heart").with_size(64.0))
+        .push(
+            Icon::new("heart")
+                .with_size(128.0)
+                .with_color(Color::new(0xFFD0427A)),
+        );
+
+    let toolbar = Flex::row()
+        .with_spacing(8.0)
+        .push(
+            IconButton::new("heart")
+                .with_description("Like")
+                .on_click(Message::Like),
+        )
+        .push(IconButton::new("save").with_text("Save", 14.0))
+        .push(
+            IconButton::new("settings")
+                .with_text("Settings", 14.0)
+                .style(ButtonStyle::Outline),
+        )
+        .push(
+            IconButton::new("save")
+                .with_icon_size(24.0)
+                .style(ButtonStyle::Fill),
+        );
+
+    Box::new(Center::new(
+        Flex::column()
+            .with_spacing(20.0)
+            .push(sizes)
+            .push(toolbar)
+            .push(SizedBox::new(
+                Size::new(200.0, 20.0),
+                Label::new(&format!("Liked {} times", state.likes)),
+            )),
+    ))
+}
+
+fn main() {
+    let mut app = App::new();
+    for (name, svg) in [("heart", HEART), ("save", SAVE), ("settings", SETTINGS)] {
+        if let Err(error) = app.style_ctx_mut().register_icon(name, svg) {
+            eprintln!("{}", error);
+        }
+    }
+
+    let request = WindowRequest::new("Icons", 600, 400, build);
+    app.run(UIAppDelegate::new(request), State::default());
+}
@@ -0,0 +1,111 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::{Color, Image as Picture, Paint, Point, Rect, Size},
+    widget::{
+        center::Center,
+        flex::Flex,
+        image::{Alignment, BoxFit, Image},
+        label::Label,
+        sized_box::SizedBox,
+        Widget,
+    },
+};
+use skia_safe::Surface;
+
+struct State {
+    // An image file given on the command line
+    path: Option<String>,
+}
+
+impl AppState for State {
+    type Message = ();
+
+    fn handle_message(&mut self, _: Self::Message, _: &mut MessageCtx<Self>) {}
+}
+
+// A wide picture, so the fit modes look different from each other
+fn picture() -> Picture {
+    let mut surface = Surface::new_raster_n32_premul((240, 120)).unwrap();
+    let canvas = surface.canvas();
+    canvas.clear(Color::new(0xFF73C8A6));
+
+    let mut paint = Paint::default();
+    paint.set_anti_alias(true);
+    paint.set_color(Color::new(0xFF766AC8));
+    canvas.draw_rect(Rect::from_xywh(0.0, 0.0, 120.0, 120.0), &paint);
+    paint.set_color(Color::new(0xFFFFFFFF));
+    canvas.draw_circle(Point::new(180.0, 60.0), 40.0, &paint);
+
+    surface.image_snapshot()
+}
+
+fn tile(caption: &str, image: Image) -> Flex<State> {
+    Flex::column()
+        .with_spacing(4.0)
+        .push(SizedBox::new(Size::new(120.0, 120.0), image))
+        .push(SizedBox::new(Size::new(120.0, 20.0), Label::new(caption)))
+}
+
+fn build(state: &State) -> Box<dyn Widget<State>> {
+    let fits = Flex::row()
+        .with_spacing(10.0)
+        .push(tile("Contain", Image::from_key("picture")))
+        .push(tile(
+            "Cover",
+            Image::from_key("picture").with_fit(BoxFit::Cover),
+        ))
+        .push(tile(
+            "Fill",
+            Image::from_key("picture").with_fit(BoxFit::Fill),
+        ))
+        .push(tile(
+            "None, left",
+            Image::from_key("picture")
+                .with_fit(BoxFit::None)
+                .with_alignment(Alignment::CENTER_LEFT),
+        ))
+        .push(tile(
+            "Scale down",
+            Image::from_key("picture").with_fit(BoxFit::ScaleDown),
+        ));
+
+    let styled = Flex::row()
+        .with_spacing(10.0)
+        .push(tile(
+            "Tinted",
+            Image::from_key("picture").with_tint(Color::new(0xFF1E38A1)),
+        ))
+        .push(tile(
+            "Rounded",
+            Image::from_key("picture")
+                .with_fit(BoxFit::Cover)
+                .with_corner_radius(60.0),
+        ));
+
+    // Big files show the placeholder until a worker has decoded them
+    let styled = match &state.path {
+        Some(path) => styled.push(tile(
+            "From the file",
+            Image::new(path.as_str())
+                .with_fit(BoxFit::Cover)
+                .with_corner_radius(12.0)
+                .with_description(path),
+        )),
+        None => styled.push(tile("Missing file", Image::new("missing.png"))),
+    };
+
+    Box::new(Center::new(
+        Flex::column().with_spacing(20.0).push(fits).push(styled),
+    ))
+}
+
+fn main() {
+    let app = App::new();
+    app.images().insert_image("picture", picture());
+
+    let state = State {
+        path: std::env::args().nth(1),
+    };
+    let request = WindowRequest::new("Images", 700, 400, build);
+    app.run(UIAppDelegate::new(request), state);
+}
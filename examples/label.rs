@@ -0,0 +1,95 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::{textlayout::TextAlign, Color, Size},
+    widget::{
+        center::Center,
+        flex::Flex,
+        label::{Label, Overflow, RichText, Span},
+        sized_box::SizedBox,
+        Widget,
+    },
+};
+
+const LOREM: &str =
+    "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor \
+incididunt ut labore et dolore magna aliqua. Ut enim ad minim veniam, quis nostrud exercitation \
+ullamco laboris nisi ut aliquip ex ea commodo consequat.";
+
+#[derive(Default)]
+struct State {
+    selected: String,
+    clicks: usize,
+}
+
+#[derive(Clone)]
+enum Message {
+    Selected(String),
+    LinkClicked,
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, msg: Self::Message, _: &mut MessageCtx<Self>) {
+        match msg {
+            Message::Selected(text) => self.selected = text,
+            Message::LinkClicked => self.clicks += 1,
+        }
+    }
+}
+
+// Flex doesn't constrain its children, text only wraps when it's given a width
+fn row(height: f32, child: impl Widget<State> + 'static) -> SizedBox<State> {
+    SizedBox::new(Size::new(400.0, height), child)
+}
+
+fn build(state: &State) -> Box<dyn Widget<State>> {
+    Box::new(Center::new(
+        Flex::column()
+            .with_spacing(12.0)
+            .push(row(
+                30.0,
+                Label::new("Centered title")
+                    .with_font_size(24.0)
+                    .with_align(TextAlign::Center),
+            ))
+            .push(row(
+                40.0,
+                Label::new(LOREM)
+                    .with_max_lines(2)
+                    .with_overflow(Overflow::Ellipsis),
+            ))
+            .push(row(
+                80.0,
+                Label::new(LOREM).on_selection(|text| Message::Selected(text.to_string())),
+            ))
+            .push(row(
+                50.0,
+                RichText::new(vec![
+                    Span::new("Rich text mixes "),
+                    Span::new("bold").bold(),
+                    Span::new(", "),
+                    Span::new("italic").italic(),
+                    Span::new(", "),
+                    Span::new("colored").with_color(Color::new(0xFFC84B31)),
+                    Span::new(" and "),
+                    Span::new("bigger").with_size(20.0),
+                    Span::new(" text with a "),
+                    Span::new("link").with_link(Message::LinkClicked),
+                    Span::new(&format!(" (clicked {} times)", state.clicks)),
+                ]),
+            ))
+            .push(row(
+                20.0,
+                Label::new(&format!("Selected: {}", state.selected))
+                    .with_wrap(false)
+                    .with_overflow(Overflow::Ellipsis),
+            )),
+    ))
+}
+
+fn main() {
+    let app = App::new();
+    let request = WindowRequest::new("Labels", 500, 420, build);
+    app.run(UIAppDelegate::new(request), State::default());
+}
@@ -0,0 +1,123 @@
+use rui::{
+    app::{
+        App, AppRequest, AppState, KeyChord, MessageCtx, Shortcuts, UIAppDelegate, WindowRequest,
+    },
+    widget::{button::TextButton, center::Center, menu_bar::MenuBar, popup::PopupMenu, Widget},
+};
+use winit::event::VirtualKeyCode;
+
+const NEW: usize = 1;
+const OPEN: usize = 2;
+const RECENT_1: usize = 3;
+const RECENT_2: usize = 4;
+const SAVE: usize = 5;
+const QUIT: usize = 6;
+const UNDO: usize = 10;
+const REDO: usize = 11;
+const WORD_WRAP: usize = 20;
+const STATUS_BAR: usize = 21;
+
+#[derive(Default)]
+struct State {
+    last: String,
+    word_wrap: bool,
+    status_bar: bool,
+    changes: usize,
+}
+
+#[derive(Clone)]
+enum Message {
+    Menu(usize),
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, msg: Self::Message, ctx: &mut MessageCtx<Self>) {
+        let Message::Menu(id) = msg;
+        match id {
+            QUIT => ctx.request(AppRequest::Quit),
+            SAVE => self.changes = 0,
+            UNDO => self.changes = self.changes.saturating_sub(1),
+            WORD_WRAP => self.word_wrap = !self.word_wrap,
+            STATUS_BAR => self.status_bar = !self.status_bar,
+            _ => self.changes += 1,
+        }
+
+        self.last = menus(self)
+            .iter()
+            .find_map(|menu| menu.find(id))
+            .map_or(String::new(), |item| item.label().to_string());
+
+        // Save and Undo may have been enabled or disabled
+        register_shortcuts(self, ctx.shortcuts_mut());
+    }
+}
+
+// The accelerators work while the menus are closed
+fn register_shortcuts(state: &State, shortcuts: &mut Shortcuts<Message>) {
+    for menu in menus(state) {
+        if let Err(error) = menu.register_shortcuts(shortcuts, Message::Menu) {
+            eprintln!("{}", error)
+        }
+    }
+}
+
+fn menus(state: &State) -> Vec<PopupMenu> {
+    vec![
+        PopupMenu::new(100, "&File")
+            .push(
+                PopupMenu::new(NEW, "&New")
+                    .with_shortcut(KeyChord::new(VirtualKeyCode::N).with_primary()),
+            )
+            .push(
+                PopupMenu::new(OPEN, "&Open...")
+                    .with_shortcut(KeyChord::new(VirtualKeyCode::O).with_primary()),
+            )
+            .with_sub_menu(
+                PopupMenu::new(200, "Open &Recent")
+                    .with_item(RECENT_1, "notes.txt")
+                    .with_item(RECENT_2, "todo.txt"),
+            )
+            .push(
+                PopupMenu::new(SAVE, "&Save")
+                    .with_shortcut(KeyChord::new(VirtualKeyCode::S).with_primary())
+                    .with_enabled(state.changes > 0),
+            )
+            .with_separator()
+            .with_item(QUIT, "&Quit"),
+        PopupMenu::new(101, "&Edit")
+            .push(
+                PopupMenu::new(UNDO, "&Undo")
+                    .with_shortcut(KeyChord::new(VirtualKeyCode::Z).with_primary())
+                    .with_enabled(state.changes > 0),
+            )
+            .push(PopupMenu::new(REDO, "&Redo").with_enabled(false)),
+        PopupMenu::new(102, "&View")
+            .push(PopupMenu::new(WORD_WRAP, "&Word Wrap").with_checked(state.word_wrap))
+            .push(PopupMenu::new(STATUS_BAR, "&Status Bar").with_checked(state.status_bar)),
+    ]
+}
+
+fn build(state: &State) -> Box<dyn Widget<State>> {
+    let text = if state.last.is_empty() {
+        "Pick something from the menus, or press Alt+F".to_string()
+    } else {
+        format!("{} ({} changes)", state.last, state.changes)
+    };
+
+    Box::new(
+        MenuBar::new(menus(state), Center::new(TextButton::new(&text, 16.0)))
+            .on_select(Message::Menu),
+    )
+}
+
+fn main() {
+    let state = State::default();
+    let mut shortcuts = Shortcuts::new();
+    register_shortcuts(&state, &mut shortcuts);
+
+    let app = App::new().with_shortcuts(shortcuts);
+    let request = WindowRequest::new("Menu bar", 600, 400, build);
+    app.run(UIAppDelegate::new(request), state);
+}
@@ -1,33 +1,89 @@
 use rui::{
-    app::{App, AppRequest, AppState, MessageCtx, UIAppDelegate, WindowRequest},
-    widget::{button::TextButton, container::Container, Widget},
+    app::{
+        App, AppRequest, AppState, ChangeWindowRequest, MessageCtx, UIAppDelegate, WindowChange,
+        WindowRequest,
+    },
+    widget::{button::TextButton, container::Container, flex::Flex, Widget},
+    window::WindowId,
 };
 
-struct State;
+#[derive(Default)]
+struct State {
+    second_window: Option<WindowId>,
+    dialog: Option<WindowId>,
+}
+
+#[derive(Clone)]
+enum Message {
+    OpenWindow,
+    WindowOpened(WindowId),
+    OpenDialog,
+    DialogOpened(WindowId),
+    CloseDialog,
+    Maximize,
+}
 
 impl AppState for State {
-    type Message = OpenWindow;
-
-    fn handle_message(&mut self, _: Self::Message, ctx: &mut MessageCtx<Self>) {
-        ctx.request(AppRequest::OpenWindow(WindowRequest::new(
-            "Second window",
-            600,
-            400,
-            |_| build_second_window(),
-        )))
+    type Message = Message;
+
+    fn handle_message(&mut self, msg: Self::Message, ctx: &mut MessageCtx<Self>) {
+        match msg {
+            Message::OpenWindow => ctx.request(AppRequest::OpenWindow(
+                WindowRequest::new("Second window", 600, 400, |_| build_second_window())
+                    .with_min_size(300, 200)
+                    .on_open(Message::WindowOpened),
+            )),
+            Message::WindowOpened(window_id) => self.second_window = Some(window_id),
+            Message::OpenDialog => {
+                if let Some(parent) = self.second_window {
+                    ctx.request(AppRequest::OpenWindow(
+                        WindowRequest::new("Dialog", 300, 150, |_| build_dialog())
+                            .with_resizable(false)
+                            .with_modal_parent(parent)
+                            .on_open(Message::DialogOpened),
+                    ))
+                }
+            }
+            Message::DialogOpened(window_id) => self.dialog = Some(window_id),
+            Message::CloseDialog => {
+                if let Some(dialog) = self.dialog.take() {
+                    ctx.request(AppRequest::CloseWindow(dialog))
+                }
+            }
+            Message::Maximize => {
+                if let Some(window_id) = self.second_window {
+                    ctx.request(AppRequest::ChangeWindow(ChangeWindowRequest::new(
+                        window_id,
+                        WindowChange::Maximized(true),
+                    )))
+                }
+            }
+        }
     }
 }
 
-#[derive(Clone)]
-struct OpenWindow;
-
 fn build_first_window() -> Box<dyn Widget<State>> {
-    let button = TextButton::new("Open new window", 24.0).on_click(OpenWindow);
+    let button = TextButton::new("Open new window", 24.0).on_click(Message::OpenWindow);
     Box::new(Container::new(button).with_padding(50.0))
 }
 
 fn build_second_window() -> Box<dyn Widget<State>> {
-    Box::new(Container::new(TextButton::new("You opened a second window", 24.0)).with_padding(50.0))
+    Box::new(
+        Container::new(
+            Flex::column()
+                .with_spacing(10.0)
+                .push(TextButton::new("Open a modal dialog", 24.0).on_click(Message::OpenDialog))
+                .push(TextButton::new("Maximize", 24.0).on_click(Message::Maximize)),
+        )
+        .with_padding(50.0),
+    )
+}
+
+fn build_dialog() -> Box<dyn Widget<State>> {
+    Box::new(
+        Container::new(TextButton::new("Close", 24.0).on_click(Message::CloseDialog))
+            .with_padding(30.0),
+    )
 }
 
 fn main() {
@@ -36,5 +92,5 @@ fn main() {
     let request = WindowRequest::new("Window 1", 600, 400, |_| build_first_window());
     let delegate = UIAppDelegate::new(request);
 
-    app.run(delegate, State);
+    app.run(delegate, State::default());
 }
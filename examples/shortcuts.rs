@@ -0,0 +1,118 @@
+use rui::{
+    app::{
+        App, AppState, KeyChord, MessageCtx, Shortcut, ShortcutCommand, Shortcuts, UIAppDelegate,
+        WindowRequest,
+    },
+    canvas::Size,
+    widget::{
+        button::TextButton,
+        center::Center,
+        flex::Flex,
+        sized_box::SizedBox,
+        text_editor::{TextBox, TextBoxCommand},
+        Widget, WidgetExt,
+    },
+};
+use winit::event::VirtualKeyCode;
+
+#[derive(Default)]
+struct State {
+    count: i32,
+}
+
+#[derive(Clone)]
+enum Message {
+    Increment,
+    Decrement,
+    Reset,
+    ClearNotes,
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, msg: Self::Message, ctx: &mut MessageCtx<Self>) {
+        match msg {
+            Message::Increment => self.count += 1,
+            Message::Decrement => self.count -= 1,
+            Message::Reset => self.count = 0,
+            Message::ClearNotes => ctx
+                .window("main")
+                .send_command("notes", TextBoxCommand::Clear),
+        }
+    }
+}
+
+fn build(state: &State) -> Box<dyn Widget<State>> {
+    Box::new(Center::new(
+        Flex::column()
+            .with_spacing(10.0)
+            .push(TextButton::new(&format!("Count {}", state.count), 24.0))
+            .push(TextButton::new(
+                "Ctrl/Cmd+Up and Ctrl/Cmd+Down count, Ctrl/Cmd+K R resets",
+                14.0,
+            ))
+            .push(TextButton::new(
+                "Escape clears the notes while typing",
+                14.0,
+            ))
+            .push(SizedBox::new(
+                Size::new(300.0, 30.0),
+                TextBox::new("Notes").with_key("notes"),
+            )),
+    ))
+}
+
+fn shortcuts() -> Shortcuts<Message> {
+    let mut shortcuts = Shortcuts::new();
+    let commands = [
+        ShortcutCommand::new(
+            "increment",
+            KeyChord::new(VirtualKeyCode::Up).with_primary(),
+            Message::Increment,
+        ),
+        ShortcutCommand::new(
+            "decrement",
+            KeyChord::new(VirtualKeyCode::Down).with_primary(),
+            Message::Decrement,
+        ),
+        // A sequence, only in the main window
+        ShortcutCommand::new(
+            "reset",
+            "Primary+K R".parse::<Shortcut>().unwrap(),
+            Message::Reset,
+        )
+        .in_window("main"),
+        // Only while the notes have focus
+        ShortcutCommand::new(
+            "clear-notes",
+            KeyChord::new(VirtualKeyCode::Escape),
+            Message::ClearNotes,
+        )
+        .in_widget("notes"),
+        // Can't be told apart from reset
+        ShortcutCommand::new(
+            "reset-all",
+            "Primary+K".parse::<Shortcut>().unwrap(),
+            Message::Reset,
+        )
+        .in_window("main"),
+    ];
+
+    for command in commands {
+        if let Err(error) = shortcuts.register(command) {
+            eprintln!("{}", error)
+        }
+    }
+
+    shortcuts
+}
+
+fn main() {
+    let app = App::new().with_shortcuts(shortcuts());
+
+    let request = WindowRequest::new("Shortcuts", 500, 300, build).with_name("main");
+    let delegate = UIAppDelegate::new(request);
+
+    app.run(delegate, State::default());
+}
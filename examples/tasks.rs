@@ -0,0 +1,130 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    widget::{button::TextButton, center::Center, container::Container, flex::Flex},
+};
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+    time::Duration,
+};
+
+#[derive(Default)]
+struct State {
+    status: String,
+    running: usize,
+}
+
+#[derive(Clone)]
+enum Message {
+    CountPrimes,
+    Download,
+    Finished(String),
+}
+
+fn count_primes(below: u64) -> usize {
+    (2..below)
+        .filter(|n| (2..).take_while(|d| d * d <= *n).all(|d| n % d != 0))
+        .count()
+}
+
+// Stands in for an io reactor: the waiting happens elsewhere and wakes the task when it's over,
+// so the task doesn't hold one of the app's workers while it waits
+#[derive(Default)]
+struct Timeout {
+    elapsed: bool,
+    waker: Option<Waker>,
+}
+
+struct Delay(Arc<Mutex<Timeout>>);
+
+impl Delay {
+    fn new(duration: Duration) -> Self {
+        let timeout = Arc::new(Mutex::new(Timeout::default()));
+        let timer = timeout.clone();
+        thread::spawn(move || {
+            thread::sleep(duration);
+            let mut timeout = timer.lock().unwrap();
+            timeout.elapsed = true;
+            if let Some(waker) = timeout.waker.take() {
+                waker.wake()
+            }
+        });
+        Self(timeout)
+    }
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        let mut timeout = self.0.lock().unwrap();
+        if timeout.elapsed {
+            Poll::Ready(())
+        } else {
+            timeout.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+async fn download() -> String {
+    Delay::new(Duration::from_secs(2)).await;
+    "Downloaded 42 bytes".to_string()
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, msg: Self::Message, ctx: &mut MessageCtx<Self>) {
+        match msg {
+            Message::CountPrimes => {
+                self.running += 1;
+                ctx.spawn_blocking(|| {
+                    Message::Finished(format!("{} primes below 5000000", count_primes(5_000_000)))
+                })
+            }
+            Message::Download => {
+                self.running += 1;
+                ctx.spawn(async { Message::Finished(download().await) })
+            }
+            Message::Finished(status) => {
+                self.running -= 1;
+                self.status = status
+            }
+        }
+    }
+}
+
+fn main() {
+    let app = App::new();
+
+    let request = WindowRequest::new("Tasks", 400, 250, |state: &State| {
+        let status = if state.running > 0 {
+            format!("{} task(s) running", state.running)
+        } else {
+            state.status.clone()
+        };
+
+        Box::new(Center::new(
+            Flex::column()
+                .with_spacing(10.0)
+                .push(TextButton::new(&status, 24.0))
+                .push(
+                    Container::new(
+                        TextButton::new("Count primes", 24.0).on_click(Message::CountPrimes),
+                    )
+                    .with_padding(10.0),
+                )
+                .push(
+                    Container::new(TextButton::new("Download", 24.0).on_click(Message::Download))
+                        .with_padding(10.0),
+                ),
+        ))
+    });
+    let delegate = UIAppDelegate::new(request);
+
+    app.run(delegate, State::default());
+}
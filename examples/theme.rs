@@ -0,0 +1,80 @@
+use rui::{
+    app::{App, AppError, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::Size,
+    widget::{
+        button::TextButton,
+        center::Center,
+        flex::Flex,
+        sized_box::SizedBox,
+        slider::Slider,
+        style::{StyleClass, SwitchStyle, TextButtonStyle},
+        switch::Switch,
+        text_editor::TextBox,
+        WidgetExt,
+    },
+};
+
+struct State;
+
+impl AppState for State {
+    type Message = ();
+
+    fn handle_message(&mut self, _: Self::Message, _: &mut MessageCtx<Self>) {}
+
+    // A typo in the watched file ends up here, the theme stays as it was until the file is fixed
+    fn app_error(&self, error: &AppError) -> Option<Self::Message> {
+        eprintln!("{}", error);
+        None
+    }
+}
+
+fn main() {
+    let app = App::new();
+
+    let request = WindowRequest::new("Theme Example", 400, 300, |_state| {
+        Box::new(Center::new(
+            Flex::column()
+                .with_spacing(20.0)
+                .push(TextButton::new("Themed", 18.0))
+                .push(TextButton::new("Accent", 18.0).with_class("accent"))
+                .push(
+                    TextButton::new("Square accent", 18.0)
+                        .with_class("accent")
+                        .with_style(|_, style: &mut TextButtonStyle| style.rounding = 0.0),
+                )
+                .push(SizedBox::new(
+                    Size::new(300.0, 30.0),
+                    Slider::new(0.0, 1.0, 0.5, false),
+                ))
+                .push(SizedBox::new(
+                    Size::new(60.0, 30.0),
+                    Switch::new().with_class("accent"),
+                ))
+                .push(SizedBox::new(
+                    Size::new(300.0, 30.0),
+                    TextBox::new("Type here"),
+                )),
+        ))
+    })
+    .with_theme("ocean");
+
+    let delegate = UIAppDelegate::new(request).on_start(|app| {
+        // Watching the file reloads the theme as soon as it is saved
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/examples/themes/ocean.toml");
+        if let Err(error) = app.style_ctx_mut().watch("ocean", path) {
+            eprintln!("{}", error)
+        }
+
+        // Classes build on whatever theme is active, this one swaps in the secondary color
+        app.style_ctx_mut().register_class(
+            "accent",
+            StyleClass::new()
+                .with(|theme, style: &mut TextButtonStyle| {
+                    *style = TextButtonStyle::from_primary(theme.secondary, theme.text)
+                })
+                .with(|theme, style: &mut SwitchStyle| style.fill = theme.secondary),
+        );
+    });
+
+    app.run(delegate, State);
+}
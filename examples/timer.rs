@@ -0,0 +1,64 @@
+use rui::{
+    app::{App, AppState, MessageCtx, TimerId, UIAppDelegate, WindowRequest},
+    widget::{button::TextButton, center::Center, container::Container, flex::Flex},
+};
+use std::time::Duration;
+
+#[derive(Default)]
+struct State {
+    seconds: usize,
+    ticker: Option<TimerId>,
+}
+
+#[derive(Clone)]
+enum Message {
+    Toggle,
+    Tick,
+    Reset,
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, msg: Self::Message, ctx: &mut MessageCtx<Self>) {
+        match msg {
+            Message::Toggle => match self.ticker.take() {
+                Some(ticker) => ctx.cancel_timer(ticker),
+                None => {
+                    self.ticker = Some(ctx.schedule_interval(Duration::from_secs(1), Message::Tick))
+                }
+            },
+            Message::Tick => self.seconds += 1,
+            Message::Reset => self.seconds = 0,
+        }
+    }
+}
+
+fn main() {
+    let app = App::new();
+
+    let request = WindowRequest::new("Timer", 400, 200, |state: &State| {
+        let toggle = if state.ticker.is_some() {
+            "Stop"
+        } else {
+            "Start"
+        };
+
+        Box::new(Center::new(
+            Flex::column()
+                .with_spacing(10.0)
+                .push(TextButton::new(&format!("{} seconds", state.seconds), 24.0))
+                .push(
+                    Container::new(TextButton::new(toggle, 24.0).on_click(Message::Toggle))
+                        .with_padding(10.0),
+                )
+                .push(
+                    Container::new(TextButton::new("Reset", 24.0).on_click(Message::Reset))
+                        .with_padding(10.0),
+                ),
+        ))
+    });
+    let delegate = UIAppDelegate::new(request);
+
+    app.run(delegate, State::default());
+}
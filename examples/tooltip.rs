@@ -0,0 +1,55 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::Size,
+    widget::{
+        button::{ButtonStyle, TextButton},
+        center::Center,
+        flex::Flex,
+        sized_box::SizedBox,
+        tooltip::Tooltip,
+        Widget, WidgetExt,
+    },
+};
+use std::time::Duration;
+
+struct State;
+
+impl AppState for State {
+    type Message = ();
+
+    fn handle_message(&mut self, _: Self::Message, _: &mut MessageCtx<Self>) {}
+}
+
+fn tool(icon: &str) -> SizedBox<State> {
+    SizedBox::new(
+        Size::new(36.0, 36.0),
+        TextButton::new(icon, 18.0).style(ButtonStyle::Outline),
+    )
+}
+
+fn build(_: &State) -> Box<dyn Widget<State>> {
+    Box::new(Center::new(
+        Flex::row()
+            .with_spacing(4.0)
+            .push(tool("B").with_tooltip("Bold (Ctrl+B)"))
+            .push(tool("I").with_tooltip("Italic (Ctrl+I)"))
+            .push(tool("U").with_tooltip("Underline (Ctrl+U)"))
+            // Any widget can be the tooltip
+            .push(
+                Tooltip::with_widget(
+                    tool("?"),
+                    SizedBox::new(
+                        Size::new(160.0, 24.0),
+                        TextButton::new("Shows up right away", 12.0).style(ButtonStyle::Text),
+                    ),
+                )
+                .with_delay(Duration::ZERO),
+            ),
+    ))
+}
+
+fn main() {
+    let app = App::new();
+    let request = WindowRequest::new("Tooltips", 400, 200, build);
+    app.run(UIAppDelegate::new(request), State);
+}
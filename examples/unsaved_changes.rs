@@ -0,0 +1,131 @@
+use rui::{
+    app::{App, AppRequest, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::Size,
+    widget::{
+        button::TextButton, center::Center, flex::Flex, sized_box::SizedBox, text_editor::TextBox,
+    },
+    window::{WindowEvent, WindowId, WindowStateStore},
+};
+
+#[derive(Default)]
+struct State {
+    text: String,
+    unsaved: bool,
+    dialog: Option<WindowId>,
+    focused: bool,
+}
+
+#[derive(Clone)]
+enum Message {
+    SetText(String),
+    Save,
+    AskToClose(WindowId),
+    DialogOpened(WindowId),
+    Discard(WindowId),
+    Cancel,
+    FocusChanged(bool),
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, msg: Self::Message, ctx: &mut MessageCtx<Self>) {
+        match msg {
+            Message::SetText(text) => {
+                self.text = text;
+                self.unsaved = true
+            }
+            Message::Save => self.unsaved = false,
+            Message::AskToClose(window_id) => {
+                if self.unsaved && self.dialog.is_none() {
+                    ctx.request(AppRequest::OpenWindow(
+                        WindowRequest::new("Unsaved changes", 360, 120, move |_| {
+                            Box::new(Center::new(
+                                Flex::row()
+                                    .with_spacing(10.0)
+                                    .push(
+                                        TextButton::new("Discard", 20.0)
+                                            .on_click(Message::Discard(window_id)),
+                                    )
+                                    .push(
+                                        TextButton::new("Cancel", 20.0).on_click(Message::Cancel),
+                                    ),
+                            ))
+                        })
+                        .with_resizable(false)
+                        .with_modal_parent(window_id)
+                        .on_open(Message::DialogOpened),
+                    ))
+                }
+            }
+            Message::DialogOpened(dialog) => self.dialog = Some(dialog),
+            Message::Discard(window_id) => {
+                self.unsaved = false;
+                self.dialog = None;
+                // Closing the editor closes the dialog with it
+                ctx.request(AppRequest::CloseWindow(window_id))
+            }
+            Message::Cancel => {
+                if let Some(dialog) = self.dialog.take() {
+                    ctx.request(AppRequest::CloseWindow(dialog))
+                }
+            }
+            Message::FocusChanged(focused) => self.focused = focused,
+        }
+    }
+
+    fn can_close_window(&self, window_id: WindowId) -> bool {
+        !self.unsaved || Some(window_id) == self.dialog
+    }
+
+    fn window_event(&self, window_id: WindowId, event: &WindowEvent) -> Option<Self::Message> {
+        if Some(window_id) == self.dialog {
+            // Closing the dialog itself is the same as cancelling
+            return match event {
+                WindowEvent::CloseRequested => Some(Message::Cancel),
+                _ => None,
+            };
+        }
+
+        match event {
+            WindowEvent::CloseRequested => Some(Message::AskToClose(window_id)),
+            WindowEvent::Focused => Some(Message::FocusChanged(true)),
+            WindowEvent::Blurred => Some(Message::FocusChanged(false)),
+            _ => None,
+        }
+    }
+}
+
+fn main() {
+    // The editor reopens where it was left
+    let app = match WindowStateStore::in_config_dir("rui-unsaved-changes") {
+        Ok(store) => App::new().with_window_state_store(store),
+        Err(error) => {
+            eprintln!("Window state is not remembered: {}", error);
+            App::new()
+        }
+    };
+
+    let request = WindowRequest::new("Editor", 400, 200, |state: &State| {
+        let status = match (state.unsaved, state.focused) {
+            (true, _) => "Save (unsaved changes)",
+            (false, true) => "Saved",
+            (false, false) => "Saved, window inactive",
+        };
+
+        Box::new(Center::new(
+            Flex::column()
+                .with_spacing(20.0)
+                .push(SizedBox::new(
+                    Size::new(300.0, 30.0),
+                    TextBox::new("Write something")
+                        .bind(|s: &State| &s.text, |t| Message::SetText(t.to_string())),
+                ))
+                .push(TextButton::new(status, 20.0).on_click(Message::Save)),
+        ))
+    })
+    .with_name("editor");
+    let delegate = UIAppDelegate::new(request);
+
+    app.run(delegate, State::default());
+}
@@ -0,0 +1,104 @@
+use rui::{
+    app::{App, AppState, MessageCtx, UIAppDelegate, WindowRequest},
+    canvas::{Point, Size},
+    widget::{
+        button::TextButton,
+        center::Center,
+        flex::Flex,
+        sized_box::SizedBox,
+        text_editor::{TextBox, TextBoxCommand},
+        Widget, WidgetExt,
+    },
+};
+
+#[derive(Default)]
+struct State {
+    query: String,
+    window_size: Option<Size>,
+    search_position: Option<Point>,
+    compact: bool,
+}
+
+#[derive(Clone)]
+enum Message {
+    SetQuery(String),
+    ClearSearch,
+    Measure,
+    ToggleCompact,
+}
+
+impl AppState for State {
+    type Message = Message;
+
+    fn handle_message(&mut self, msg: Self::Message, ctx: &mut MessageCtx<Self>) {
+        match msg {
+            Message::SetQuery(query) => self.query = query,
+            // The text box publishes SetQuery with the new text
+            Message::ClearSearch => ctx
+                .window("main")
+                .send_command("search", TextBoxCommand::Clear),
+            Message::Measure => {
+                let window = ctx.window("main");
+                self.window_size = window.size();
+                self.search_position = window.find("search").map(|search| search.position);
+            }
+            Message::ToggleCompact => {
+                self.compact = !self.compact;
+                let compact = self.compact;
+                ctx.window("main").set_root(move |state: &State| {
+                    if compact {
+                        build_compact(state)
+                    } else {
+                        build(state)
+                    }
+                })
+            }
+        }
+    }
+}
+
+fn search_box() -> impl Widget<State> {
+    SizedBox::new(
+        Size::new(300.0, 30.0),
+        TextBox::new("Search")
+            .bind(|s: &State| &s.query, |q| Message::SetQuery(q.to_string()))
+            .with_key("search"),
+    )
+}
+
+fn build(state: &State) -> Box<dyn Widget<State>> {
+    let size = match (state.window_size, state.search_position) {
+        (Some(size), Some(search)) => format!(
+            "{} x {}, search at {}, {}",
+            size.width, size.height, search.x, search.y
+        ),
+        _ => "Measure window".to_string(),
+    };
+
+    Box::new(Center::new(
+        Flex::column()
+            .with_spacing(10.0)
+            .push(search_box())
+            .push(TextButton::new("Clear search", 20.0).on_click(Message::ClearSearch))
+            .push(TextButton::new(&size, 20.0).on_click(Message::Measure))
+            .push(TextButton::new("Compact view", 20.0).on_click(Message::ToggleCompact)),
+    ))
+}
+
+fn build_compact(_: &State) -> Box<dyn Widget<State>> {
+    Box::new(Center::new(
+        Flex::column()
+            .with_spacing(10.0)
+            .push(search_box())
+            .push(TextButton::new("Full view", 20.0).on_click(Message::ToggleCompact)),
+    ))
+}
+
+fn main() {
+    let app = App::new();
+
+    let request = WindowRequest::new("Window access", 400, 300, build).with_name("main");
+    let delegate = UIAppDelegate::new(request);
+
+    app.run(delegate, State::default());
+}
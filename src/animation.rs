@@ -0,0 +1,551 @@
+use crate::canvas::{Color, Point, Size};
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+// Source of the time animations are evaluated at. Times are relative to an arbitrary start,
+// only the difference between two times matters.
+pub trait Clock {
+    fn now(&self) -> Duration;
+}
+
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+// A clock that only moves when told to, so animations can be stepped deterministically.
+// Clones share the same time, keep one to advance the clock after handing another to the app.
+#[derive(Clone, Default)]
+pub struct ManualClock {
+    time: Rc<Cell<Duration>>,
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.time.set(self.time.get() + by)
+    }
+
+    pub fn set(&self, time: Duration) {
+        self.time.set(time)
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Duration {
+        self.time.get()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    // Control points of a CSS style cubic bezier, the curve runs from (0, 0) to (1, 1)
+    CubicBezier(f32, f32, f32, f32),
+}
+
+impl Easing {
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match *self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier(x1, y1, x2, y2, t),
+        }
+    }
+}
+
+// Finds the curve parameter for x with a few Newton steps, falling back to bisection when the slope is flat
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, x: f32) -> f32 {
+    let bezier = |a: f32, b: f32, t: f32| {
+        let u = 1.0 - t;
+        3.0 * u * u * t * a + 3.0 * u * t * t * b + t * t * t
+    };
+    let slope = |a: f32, b: f32, t: f32| {
+        let u = 1.0 - t;
+        3.0 * u * u * a + 6.0 * u * t * (b - a) + 3.0 * t * t * (1.0 - b)
+    };
+
+    let mut t = x;
+    for _ in 0..8 {
+        let error = bezier(x1, x2, t) - x;
+        if error.abs() < 1e-5 {
+            return bezier(y1, y2, t);
+        }
+
+        let d = slope(x1, x2, t);
+        if d.abs() < 1e-6 {
+            break;
+        }
+
+        t -= error / d;
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    t = x;
+    for _ in 0..32 {
+        let value = bezier(x1, x2, t);
+        if (value - x).abs() < 1e-5 {
+            break;
+        }
+
+        if value < x {
+            low = t
+        } else {
+            high = t
+        }
+
+        t = (low + high) / 2.0;
+    }
+
+    bezier(y1, y2, t)
+}
+
+// Values that can be interpolated. t is usually in 0..1 but springs overshoot.
+pub trait Lerp {
+    fn lerp(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for Point {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Point::new(self.x.lerp(&to.x, t), self.y.lerp(&to.y, t))
+    }
+}
+
+impl Lerp for Size {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        Size::new(
+            self.width.lerp(&to.width, t),
+            self.height.lerp(&to.height, t),
+        )
+    }
+}
+
+impl Lerp for Color {
+    fn lerp(&self, to: &Self, t: f32) -> Self {
+        let channel = |from: u8, to: u8| {
+            (from as f32)
+                .lerp(&(to as f32), t)
+                .round()
+                .clamp(0.0, 255.0) as u8
+        };
+        Color::from_argb(
+            channel(self.a(), to.a()),
+            channel(self.r(), to.r()),
+            channel(self.g(), to.g()),
+            channel(self.b(), to.b()),
+        )
+    }
+}
+
+pub struct Tween<T> {
+    pub from: T,
+    pub to: T,
+    pub start: Duration,
+    pub duration: Duration,
+    pub easing: Easing,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(from: T, to: T, start: Duration, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            start,
+            duration,
+            easing: Easing::EaseInOut,
+        }
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    // Linear progress in 0..1, before easing
+    pub fn progress(&self, now: Duration) -> f32 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+
+        let elapsed = now.saturating_sub(self.start);
+        (elapsed.as_secs_f32() / self.duration.as_secs_f32()).min(1.0)
+    }
+
+    pub fn value(&self, now: Duration) -> T {
+        self.from
+            .lerp(&self.to, self.easing.apply(self.progress(now)))
+    }
+
+    pub fn is_finished(&self, now: Duration) -> bool {
+        self.progress(now) >= 1.0
+    }
+}
+
+// A damped spring, evaluated in closed form so the value only depends on the time and not on the frame rate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Spring {
+    pub stiffness: f32,
+    pub damping: f32,
+    pub mass: f32,
+}
+
+impl Spring {
+    pub fn new(stiffness: f32, damping: f32, mass: f32) -> Self {
+        Self {
+            stiffness,
+            damping,
+            mass,
+        }
+    }
+
+    // Settles without overshooting
+    pub fn smooth() -> Self {
+        Self::new(170.0, 26.0, 1.0)
+    }
+
+    pub fn bouncy() -> Self {
+        Self::new(300.0, 12.0, 1.0)
+    }
+
+    // Distance to the target at time t for a spring that starts at distance 1 and at rest
+    fn displacement(&self, t: f32) -> f32 {
+        let omega = (self.stiffness / self.mass).sqrt();
+        let zeta = self.damping / (2.0 * (self.stiffness * self.mass).sqrt());
+        let x0 = 1.0;
+
+        if zeta < 1.0 {
+            let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+            (-zeta * omega * t).exp()
+                * (x0 * (omega_d * t).cos() + (zeta * omega * x0) / omega_d * (omega_d * t).sin())
+        } else if zeta == 1.0 {
+            (-omega * t).exp() * (x0 + omega * x0 * t)
+        } else {
+            let root = (zeta * zeta - 1.0).sqrt();
+            let r1 = -omega * (zeta - root);
+            let r2 = -omega * (zeta + root);
+            let c1 = -r2 * x0 / (r1 - r2);
+            let c2 = x0 - c1;
+            c1 * (r1 * t).exp() + c2 * (r2 * t).exp()
+        }
+    }
+
+    // Progress from the start to the target, overshoots 1 for underdamped springs
+    pub fn progress(&self, elapsed: Duration) -> f32 {
+        1.0 - self.displacement(elapsed.as_secs_f32())
+    }
+
+    pub fn is_settled(&self, elapsed: Duration) -> bool {
+        const REST: f32 = 0.001;
+        let t = elapsed.as_secs_f32();
+        // Checking a bit ahead as well catches the moment an oscillation passes through the target
+        self.displacement(t).abs() < REST && self.displacement(t + 1.0 / 60.0).abs() < REST
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Transition {
+    Tween(Duration, Easing),
+    Spring(Spring),
+}
+
+impl Transition {
+    pub fn tween(duration: Duration) -> Self {
+        Transition::Tween(duration, Easing::EaseInOut)
+    }
+
+    pub fn spring(spring: Spring) -> Self {
+        Transition::Spring(spring)
+    }
+
+    fn progress(&self, elapsed: Duration) -> f32 {
+        match self {
+            Transition::Tween(duration, easing) => {
+                if duration.is_zero() {
+                    1.0
+                } else {
+                    easing.apply(elapsed.as_secs_f32() / duration.as_secs_f32())
+                }
+            }
+            Transition::Spring(spring) => spring.progress(elapsed),
+        }
+    }
+
+    fn is_finished(&self, elapsed: Duration) -> bool {
+        match self {
+            Transition::Tween(duration, _) => elapsed >= *duration,
+            Transition::Spring(spring) => spring.is_settled(elapsed),
+        }
+    }
+}
+
+impl Default for Transition {
+    fn default() -> Self {
+        Self::tween(Duration::from_millis(150))
+    }
+}
+
+// A value that transitions implicitly: setting a new target animates towards it from wherever the value is now.
+// Works through a shared reference so widgets can retarget from paint, where the target is usually known.
+pub struct Animated<T> {
+    from: Cell<T>,
+    target: Cell<T>,
+    start: Cell<Option<Duration>>,
+    transition: Transition,
+    jump_to_first_target: Cell<bool>,
+}
+
+impl<T: Lerp + Copy + PartialEq> Animated<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            from: Cell::new(value),
+            target: Cell::new(value),
+            start: Cell::new(None),
+            transition: Transition::default(),
+            jump_to_first_target: Cell::new(false),
+        }
+    }
+
+    // The first target is taken over without animating, for widgets that only learn
+    // their initial value when they are first painted.
+    pub fn with_initial_jump(self) -> Self {
+        self.jump_to_first_target.set(true);
+        self
+    }
+
+    pub fn with_transition(mut self, transition: Transition) -> Self {
+        self.transition = transition;
+        self
+    }
+
+    pub fn target(&self) -> T {
+        self.target.get()
+    }
+
+    pub fn value(&self, now: Duration) -> T {
+        match self.start.get() {
+            Some(start) => {
+                let elapsed = now.saturating_sub(start);
+                if self.transition.is_finished(elapsed) {
+                    self.target.get()
+                } else {
+                    self.from
+                        .get()
+                        .lerp(&self.target.get(), self.transition.progress(elapsed))
+                }
+            }
+            None => self.target.get(),
+        }
+    }
+
+    pub fn is_animating(&self, now: Duration) -> bool {
+        match self.start.get() {
+            Some(start) => !self.transition.is_finished(now.saturating_sub(start)),
+            None => false,
+        }
+    }
+
+    pub fn set(&self, target: T, now: Duration) {
+        if self.jump_to_first_target.replace(false) {
+            self.reset(target);
+            return;
+        }
+
+        if target == self.target.get() {
+            return;
+        }
+
+        self.from.set(self.value(now));
+        self.target.set(target);
+        self.start.set(Some(now));
+    }
+
+    // Jumps to the value without animating
+    pub fn reset(&self, value: T) {
+        self.from.set(value);
+        self.target.set(value);
+        self.start.set(None);
+    }
+
+    // Sets the target and returns the value at this time, the usual way to use it from paint
+    pub fn animate_to(&self, target: T, now: Duration) -> T {
+        self.set(target, now);
+        self.value(now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: f32, expected: f32) {
+        assert!(
+            (value - expected).abs() < 1e-4,
+            "{} is not close to {}",
+            value,
+            expected
+        )
+    }
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn tween_follows_the_clock() {
+        let clock = ManualClock::new();
+        let tween = Tween::new(0.0, 10.0, clock.now(), millis(100)).with_easing(Easing::Linear);
+        assert_close(tween.value(clock.now()), 0.0);
+        assert!(!tween.is_finished(clock.now()));
+
+        clock.advance(millis(50));
+        assert_close(tween.value(clock.now()), 5.0);
+
+        clock.advance(millis(50));
+        assert_close(tween.value(clock.now()), 10.0);
+        assert!(tween.is_finished(clock.now()));
+
+        // Stays at the end instead of extrapolating
+        clock.advance(millis(500));
+        assert_close(tween.value(clock.now()), 10.0);
+    }
+
+    #[test]
+    fn tween_waits_for_its_start_and_eases() {
+        let clock = ManualClock::new();
+        let tween = Tween::new(0.0, 10.0, millis(100), millis(100)).with_easing(Easing::EaseIn);
+        assert_close(tween.value(clock.now()), 0.0);
+
+        clock.set(millis(150));
+        assert_close(tween.progress(clock.now()), 0.5);
+        assert_close(tween.value(clock.now()), 1.25);
+    }
+
+    #[test]
+    fn tween_without_duration_is_finished() {
+        let tween = Tween::new(0.0, 10.0, Duration::ZERO, Duration::ZERO);
+        assert!(tween.is_finished(Duration::ZERO));
+        assert_close(tween.value(Duration::ZERO), 10.0);
+    }
+
+    #[test]
+    fn smooth_spring_settles_without_overshooting() {
+        let spring = Spring::smooth();
+        let clock = ManualClock::new();
+        assert_close(spring.progress(clock.now()), 0.0);
+        assert!(!spring.is_settled(clock.now()));
+
+        while clock.now() < millis(2000) {
+            clock.advance(millis(10));
+            assert!(spring.progress(clock.now()) < 1.0 + 1e-4);
+        }
+        assert!(spring.is_settled(clock.now()));
+        assert_close(spring.progress(clock.now()), 1.0);
+    }
+
+    #[test]
+    fn bouncy_spring_overshoots_and_settles() {
+        let spring = Spring::bouncy();
+        let clock = ManualClock::new();
+        let mut peak: f32 = 0.0;
+        while clock.now() < millis(3000) {
+            clock.advance(millis(10));
+            peak = peak.max(spring.progress(clock.now()));
+        }
+
+        assert!(peak > 1.1, "peaked at {}", peak);
+        assert!(spring.is_settled(clock.now()));
+    }
+
+    #[test]
+    fn animated_retargets_from_its_current_value() {
+        let clock = ManualClock::new();
+        let value =
+            Animated::new(0.0).with_transition(Transition::Tween(millis(100), Easing::Linear));
+        assert!(!value.is_animating(clock.now()));
+
+        value.set(10.0, clock.now());
+        assert!(value.is_animating(clock.now()));
+        assert_close(value.value(clock.now()), 0.0);
+
+        clock.advance(millis(50));
+        assert_close(value.value(clock.now()), 5.0);
+
+        // Heads back from halfway instead of jumping to the old target first
+        assert_close(value.animate_to(0.0, clock.now()), 5.0);
+        clock.advance(millis(50));
+        assert_close(value.value(clock.now()), 2.5);
+
+        clock.advance(millis(50));
+        assert_close(value.value(clock.now()), 0.0);
+        assert!(!value.is_animating(clock.now()));
+    }
+
+    #[test]
+    fn animated_takes_over_the_first_target_with_an_initial_jump() {
+        let clock = ManualClock::new();
+        let value = Animated::new(0.0).with_initial_jump();
+        assert_close(value.animate_to(1.0, clock.now()), 1.0);
+        assert!(!value.is_animating(clock.now()));
+
+        value.set(0.0, clock.now());
+        assert!(value.is_animating(clock.now()));
+    }
+
+    #[test]
+    fn animated_spring_ends_on_the_target() {
+        let clock = ManualClock::new();
+        let value = Animated::new(Point::new(0.0, 0.0))
+            .with_transition(Transition::spring(Spring::bouncy()));
+        value.set(Point::new(100.0, 50.0), clock.now());
+
+        clock.advance(millis(16));
+        assert!(value.is_animating(clock.now()));
+
+        clock.advance(millis(3000));
+        assert!(!value.is_animating(clock.now()));
+        assert_eq!(value.value(clock.now()), Point::new(100.0, 50.0));
+    }
+}
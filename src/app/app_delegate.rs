@@ -1,4 +1,4 @@
-use super::{App, AppState, WindowRequest};
+use super::{App, AppEvent, AppState, WindowRequest};
 use crate::window::{WindowId, WindowRegistry};
 use std::path::Path;
 use winit::event_loop::{ControlFlow, EventLoopWindowTarget};
@@ -9,7 +9,7 @@ pub trait AppDelegate<State: AppState> {
         app: &mut App<State>,
         state: &State,
         window_registry: &mut WindowRegistry<State>,
-        event_loop: &EventLoopWindowTarget<()>,
+        event_loop: &EventLoopWindowTarget<AppEvent>,
     ) {
         let _ = app;
         let _ = state;
@@ -17,7 +17,11 @@ pub trait AppDelegate<State: AppState> {
         let _ = event_loop;
     }
 
-    fn app_will_quit(&mut self, app: &mut App<State>, event_loop: &EventLoopWindowTarget<()>) {
+    fn app_will_quit(
+        &mut self,
+        app: &mut App<State>,
+        event_loop: &EventLoopWindowTarget<AppEvent>,
+    ) {
         let _ = app;
         let _ = event_loop;
     }
@@ -27,7 +31,7 @@ pub trait AppDelegate<State: AppState> {
         app: &App<State>,
         state: &State,
         window_registry: &mut WindowRegistry<State>,
-        event_loop: &EventLoopWindowTarget<()>,
+        event_loop: &EventLoopWindowTarget<AppEvent>,
     ) {
         let _ = app;
         let _ = state;
@@ -35,19 +39,21 @@ pub trait AppDelegate<State: AppState> {
         let _ = event_loop;
     }
 
+    // Returns the id of the window that was opened for the request
     fn window_requested(
         &mut self,
         app: &App<State>,
         state: &State,
         window_registry: &mut WindowRegistry<State>,
-        event_loop: &EventLoopWindowTarget<()>,
+        event_loop: &EventLoopWindowTarget<AppEvent>,
         request: WindowRequest<State>,
-    ) {
+    ) -> Option<WindowId> {
         let _ = app;
         let _ = state;
         let _ = window_registry;
         let _ = event_loop;
         let _ = request;
+        None
     }
 
     fn window_moved(
@@ -0,0 +1,21 @@
+use crate::{widget::style::ThemeError, window::WindowStateError};
+use std::path::PathBuf;
+use thiserror::Error;
+
+// Failures of work the app does on its own, outside of any call the application made.
+// They are handed to AppState::app_error.
+#[derive(Debug, Error)]
+pub enum AppError {
+    // A theme file requested with AppRequest::LoadTheme couldn't be loaded
+    #[error("failed to load theme \"{name}\": {error}")]
+    LoadTheme { name: String, error: ThemeError },
+    // A watched theme file changed but couldn't be read, the theme keeps its previous version
+    #[error("failed to reload theme: {0}")]
+    ReloadTheme(ThemeError),
+    // The positions and sizes of named windows couldn't be written to the store's file
+    #[error("failed to save window state to {}: {}", .path.display(), .error)]
+    SaveWindowState {
+        path: PathBuf,
+        error: WindowStateError,
+    },
+}
@@ -0,0 +1,78 @@
+use super::{
+    AppRequest, AppState, ChangeThemeRequest, ChangeWindowRequest, CursorIconRequest,
+    ThemeFileRequest,
+};
+use std::sync::mpsc;
+use thiserror::Error;
+use winit::{event_loop::EventLoopProxy, window::WindowId};
+
+// The user event of the app's event loop
+pub enum AppEvent {
+    // Messages were posted and are waiting to be handled
+    Wake,
+    Command(AppCommand),
+}
+
+// The requests that can be made from any thread
+pub enum AppCommand {
+    ChangeCursor(CursorIconRequest),
+    LoadTheme(ThemeFileRequest),
+    ChangeTheme(ChangeThemeRequest),
+    CloseWindow(WindowId),
+    ChangeWindow(ChangeWindowRequest),
+    Quit,
+}
+
+impl<State: AppState> From<AppCommand> for AppRequest<State> {
+    fn from(command: AppCommand) -> Self {
+        match command {
+            AppCommand::ChangeCursor(request) => AppRequest::ChangeCursorRequest(request),
+            AppCommand::LoadTheme(request) => AppRequest::LoadTheme(request),
+            AppCommand::ChangeTheme(request) => AppRequest::ChangeTheme(request),
+            AppCommand::CloseWindow(window_id) => AppRequest::CloseWindow(window_id),
+            AppCommand::ChangeWindow(request) => AppRequest::ChangeWindow(request),
+            AppCommand::Quit => AppRequest::Quit,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+#[error("The app is no longer running")]
+pub struct AppClosed;
+
+// Lets other threads drive the app. Everything sent wakes the event loop so it's handled right away.
+pub struct AppHandle<Message> {
+    message_tx: mpsc::Sender<Message>,
+    proxy: EventLoopProxy<AppEvent>,
+}
+
+impl<Message> AppHandle<Message> {
+    pub(crate) fn new(message_tx: mpsc::Sender<Message>, proxy: EventLoopProxy<AppEvent>) -> Self {
+        Self { message_tx, proxy }
+    }
+
+    pub fn send(&self, message: Message) -> Result<(), AppClosed> {
+        self.message_tx.send(message).map_err(|_| AppClosed)?;
+        self.proxy.send_event(AppEvent::Wake).map_err(|_| AppClosed)
+    }
+
+    pub fn command(&self, command: AppCommand) -> Result<(), AppClosed> {
+        self.proxy
+            .send_event(AppEvent::Command(command))
+            .map_err(|_| AppClosed)
+    }
+
+    pub fn quit(&self) -> Result<(), AppClosed> {
+        self.command(AppCommand::Quit)
+    }
+}
+
+// Derived Clone would require the message to be Clone as well
+impl<Message> Clone for AppHandle<Message> {
+    fn clone(&self) -> Self {
+        Self {
+            message_tx: self.message_tx.clone(),
+            proxy: self.proxy.clone(),
+        }
+    }
+}
@@ -1,9 +1,34 @@
-use super::{App, AppRequest};
+use super::{
+    App, AppError, AppHandle, AppRequest, Shortcuts, TimerId, WindowCtx, WindowInfo, WindowTarget,
+};
+use crate::window::{WindowEvent, WindowId};
+use std::{future::Future, time::Duration};
 
 pub trait AppState: Sized {
     type Message: Clone;
 
     fn handle_message(&mut self, msg: Self::Message, ctx: &mut MessageCtx<Self>);
+
+    // Returning false keeps the window open when its close button is pressed, for example while
+    // there are unsaved changes. Map WindowEvent::CloseRequested to a message to ask the user.
+    fn can_close_window(&self, window_id: WindowId) -> bool {
+        let _ = window_id;
+        true
+    }
+
+    // Lets the state respond to window events, the returned message is handled like any other
+    fn window_event(&self, window_id: WindowId, event: &WindowEvent) -> Option<Self::Message> {
+        let _ = window_id;
+        let _ = event;
+        None
+    }
+
+    // Called when something the app does on its own fails, like reloading a watched theme file.
+    // The returned message is handled like any other, the default ignores the error.
+    fn app_error(&self, error: &AppError) -> Option<Self::Message> {
+        let _ = error;
+        None
+    }
 }
 
 pub struct MessageCtx<'a, State: AppState> {
@@ -20,4 +45,49 @@ impl<'a, State: AppState + 'static> MessageCtx<'a, State> {
     pub fn request(&mut self, request: AppRequest<State>) {
         self.app.request(request)
     }
+
+    pub fn schedule(&mut self, delay: Duration, message: State::Message) -> TimerId {
+        self.app.schedule(delay, message)
+    }
+
+    pub fn schedule_interval(&mut self, interval: Duration, message: State::Message) -> TimerId {
+        self.app.schedule_interval(interval, message)
+    }
+
+    pub fn cancel_timer(&mut self, id: TimerId) {
+        self.app.cancel_timer(id)
+    }
+
+    pub fn window(&mut self, target: impl Into<WindowTarget>) -> WindowCtx<State> {
+        self.app.window(target)
+    }
+
+    pub fn windows(&self) -> impl Iterator<Item = &WindowInfo> {
+        self.app.windows()
+    }
+
+    // Commands can be registered and removed while the app runs, for example when a document opens
+    pub fn shortcuts_mut(&mut self) -> &mut Shortcuts<State::Message> {
+        self.app.shortcuts_mut()
+    }
+
+    pub fn handle(&self) -> AppHandle<State::Message> {
+        self.app.handle()
+    }
+
+    pub fn spawn<F>(&mut self, future: F)
+    where
+        F: Future<Output = State::Message> + Send + 'static,
+        State::Message: Send,
+    {
+        self.app.spawn(future)
+    }
+
+    pub fn spawn_blocking<F>(&mut self, f: F)
+    where
+        F: FnOnce() -> State::Message + Send + 'static,
+        State::Message: Send,
+    {
+        self.app.spawn_blocking(f)
+    }
 }
@@ -0,0 +1,144 @@
+use crate::{
+    canvas::{
+        textlayout::{FontCollection, TypefaceFontProvider},
+        Font, FontStyle, Typeface,
+    },
+    widget::style,
+};
+use skia_safe::FontMgr;
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum FontError {
+    #[error("failed to read font file {0:?}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("not a font file skia can read")]
+    InvalidData,
+}
+
+struct FontCache {
+    collection: FontCollection,
+    // Holds the fonts the app registered, the collection looks in it before the system fonts
+    provider: TypefaceFontProvider,
+}
+
+// The fonts of the app. Clones are cheap and share the registered fonts and the font collection, so the
+// collection's caches survive between layouts and are shared by all text widgets of all windows.
+#[derive(Clone)]
+pub struct Fonts {
+    cache: Rc<RefCell<FontCache>>,
+    // The families of the theme the window uses
+    style: Rc<style::FontStyle>,
+}
+
+impl Default for Fonts {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Fonts {
+    pub fn new() -> Self {
+        let provider = TypefaceFontProvider::new();
+        let asset_manager: FontMgr = provider.clone().into();
+        let mut collection = FontCollection::new();
+        collection.set_asset_font_manager(asset_manager);
+        collection.set_default_font_manager(FontMgr::new(), None);
+
+        Self {
+            cache: Rc::new(RefCell::new(FontCache {
+                collection,
+                provider,
+            })),
+            style: Rc::new(style::FontStyle::default()),
+        }
+    }
+
+    // Registers a font under the family name stored in it, which is returned
+    pub fn register_font(&self, bytes: &[u8]) -> Result<String, FontError> {
+        let typeface = FontMgr::new()
+            .new_from_data(bytes, None)
+            .ok_or(FontError::InvalidData)?;
+        let family = typeface.family_name();
+        self.register_typeface(typeface, None);
+        Ok(family)
+    }
+
+    // Registers a font under a family name of the app's choosing
+    pub fn register_font_as(&self, bytes: &[u8], family: &str) -> Result<(), FontError> {
+        let typeface = FontMgr::new()
+            .new_from_data(bytes, None)
+            .ok_or(FontError::InvalidData)?;
+        self.register_typeface(typeface, Some(family));
+        Ok(())
+    }
+
+    pub fn register_font_file(&self, path: impl AsRef<Path>) -> Result<String, FontError> {
+        let path = path.as_ref();
+        let bytes =
+            std::fs::read(path).map_err(|error| FontError::Io(path.to_path_buf(), error))?;
+        self.register_font(&bytes)
+    }
+
+    fn register_typeface(&self, typeface: Typeface, alias: Option<&str>) {
+        let mut cache = self.cache.borrow_mut();
+        cache.provider.register_typeface(typeface, alias);
+        // Text laid out before has to find the new font
+        cache.collection.clear_caches();
+    }
+
+    // The same fonts, with the families of another theme
+    pub(crate) fn with_style(&self, style: &style::FontStyle) -> Self {
+        Self {
+            cache: self.cache.clone(),
+            style: Rc::new(style.clone()),
+        }
+    }
+
+    pub fn style(&self) -> &style::FontStyle {
+        &self.style
+    }
+
+    // For paragraph builders, it knows the registered fonts as well as the system ones
+    pub fn collection(&self) -> FontCollection {
+        self.cache.borrow().collection.clone()
+    }
+
+    // The families to try for a family name, in order. A family the theme names, like "monospace", stands
+    // for its fallback chain. The theme's default font and its fallbacks come last.
+    pub fn families(&self, family: Option<&str>) -> Vec<String> {
+        let mut families = match family {
+            Some(family) => match self.style.families.get(family) {
+                Some(chain) => chain.clone(),
+                None => vec![family.to_string()],
+            },
+            None => Vec::new(),
+        };
+
+        families.push(self.style.family.clone());
+        families.extend(self.style.fallback.iter().cloned());
+        families
+    }
+
+    // The first typeface of the chain that's installed or registered. Falls back to the default
+    // typeface, so a missing font never fails.
+    pub fn typeface(&self, family: Option<&str>, style: FontStyle) -> Typeface {
+        let families = self.families(family);
+        self.cache
+            .borrow_mut()
+            .collection
+            .find_typefaces(&families, style)
+            .into_iter()
+            .next()
+            .unwrap_or_default()
+    }
+
+    pub fn font(&self, family: Option<&str>, size: f32) -> Font {
+        Font::new(self.typeface(family, FontStyle::normal()), size)
+    }
+}
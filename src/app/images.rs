@@ -0,0 +1,234 @@
+use super::{task_pool::SharedTaskPool, AppEvent};
+use crate::canvas::Image;
+use skia_safe::{Codec, Data};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+use thiserror::Error;
+use winit::event_loop::EventLoopProxy;
+
+// Smaller files are decoded right away, bigger ones on a worker thread
+const BACKGROUND_DECODE_SIZE: usize = 256 * 1024;
+
+#[derive(Clone, Debug, Error)]
+pub enum ImageError {
+    #[error("failed to read image file {0:?}: {1}")]
+    Io(PathBuf, String),
+    #[error("not an image skia can decode")]
+    InvalidData,
+    #[error("no image was inserted as \"{0}\"")]
+    UnknownKey(String),
+}
+
+// Where an image comes from
+#[derive(Clone, Debug)]
+pub enum ImageSource {
+    Path(PathBuf),
+    // Encoded image data, cached under the key
+    Bytes(String, Arc<[u8]>),
+    // An image put in the cache with Images::insert
+    Key(String),
+}
+
+impl ImageSource {
+    pub fn path(path: impl Into<PathBuf>) -> Self {
+        Self::Path(path.into())
+    }
+
+    pub fn bytes(key: &str, bytes: impl Into<Arc<[u8]>>) -> Self {
+        Self::Bytes(key.to_string(), bytes.into())
+    }
+
+    pub fn key(key: &str) -> Self {
+        Self::Key(key.to_string())
+    }
+
+    fn cache_key(&self) -> CacheKey {
+        match self {
+            Self::Path(path) => CacheKey::Path(path.clone()),
+            Self::Bytes(key, _) | Self::Key(key) => CacheKey::Key(key.clone()),
+        }
+    }
+}
+
+impl From<&str> for ImageSource {
+    fn from(path: &str) -> Self {
+        Self::path(path)
+    }
+}
+
+impl From<PathBuf> for ImageSource {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum CacheKey {
+    Path(PathBuf),
+    Key(String),
+}
+
+#[derive(Clone)]
+pub enum ImageStatus {
+    Loading,
+    Ready(Image),
+    Failed(ImageError),
+}
+
+type Entries = Arc<Mutex<HashMap<CacheKey, ImageStatus>>>;
+
+// The decoded images of the app, shared by all windows. Clones refer to the same cache.
+// Images stay cached until they're removed.
+#[derive(Clone)]
+pub struct Images {
+    entries: Entries,
+    // Counts the images that finished decoding on a worker, windows check their loading images when it changes
+    decoded: Arc<AtomicUsize>,
+    // The app's pool, a cache made outside of an app starts its own
+    pool: SharedTaskPool,
+    // Wakes the event loop when a worker is done
+    proxy: Option<EventLoopProxy<AppEvent>>,
+}
+
+impl Default for Images {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Images {
+    pub fn new() -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(HashMap::new())),
+            decoded: Arc::new(AtomicUsize::new(0)),
+            pool: SharedTaskPool::default(),
+            proxy: None,
+        }
+    }
+
+    pub(crate) fn with_proxy(mut self, proxy: EventLoopProxy<AppEvent>) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    pub(crate) fn with_task_pool(mut self, pool: SharedTaskPool) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    // Decodes the image and caches it under the key, for Image widgets made with ImageSource::Key
+    pub fn insert(&self, key: &str, bytes: &[u8]) -> Result<(), ImageError> {
+        let image = decode(bytes)?;
+        self.set(CacheKey::Key(key.to_string()), ImageStatus::Ready(image));
+        Ok(())
+    }
+
+    pub fn insert_image(&self, key: &str, image: Image) {
+        self.set(CacheKey::Key(key.to_string()), ImageStatus::Ready(image))
+    }
+
+    pub fn remove(&self, source: &ImageSource) {
+        self.entries.lock().unwrap().remove(&source.cache_key());
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear()
+    }
+
+    // The image if it's cached, otherwise it starts loading it
+    pub fn load(&self, source: &ImageSource) -> ImageStatus {
+        let key = source.cache_key();
+        if let Some(status) = self.entries.lock().unwrap().get(&key) {
+            return status.clone();
+        }
+
+        let status = match source {
+            ImageSource::Key(key) => ImageStatus::Failed(ImageError::UnknownKey(key.clone())),
+            ImageSource::Bytes(_, bytes) if bytes.len() < BACKGROUND_DECODE_SIZE => {
+                status_of(decode(bytes))
+            }
+            ImageSource::Bytes(_, bytes) => {
+                let bytes = bytes.clone();
+                return self.decode_in_background(key, move || decode(&bytes));
+            }
+            ImageSource::Path(path) => match std::fs::metadata(path) {
+                Ok(metadata) if metadata.len() >= BACKGROUND_DECODE_SIZE as u64 => {
+                    let path = path.clone();
+                    return self.decode_in_background(key, move || read_and_decode(&path));
+                }
+                _ => status_of(read_and_decode(path)),
+            },
+        };
+
+        self.set(key, status.clone());
+        status
+    }
+
+    // Whether a worker is still decoding the image
+    pub(crate) fn is_loading(&self, source: &ImageSource) -> bool {
+        matches!(
+            self.entries.lock().unwrap().get(&source.cache_key()),
+            Some(ImageStatus::Loading)
+        )
+    }
+
+    // Changes every time a worker finished an image
+    pub(crate) fn decoded_count(&self) -> usize {
+        self.decoded.load(Ordering::Acquire)
+    }
+
+    fn set(&self, key: CacheKey, status: ImageStatus) {
+        self.entries.lock().unwrap().insert(key, status);
+    }
+
+    fn decode_in_background<F>(&self, key: CacheKey, job: F) -> ImageStatus
+    where
+        F: FnOnce() -> Result<Image, ImageError> + Send + 'static,
+    {
+        // In the cache before the worker can finish
+        self.set(key.clone(), ImageStatus::Loading);
+        let entries = self.entries.clone();
+        let decoded = self.decoded.clone();
+        let proxy = self.proxy.clone();
+        self.pool.execute(move || {
+            let status = status_of(job());
+            // Removed while it was loading, it's not wanted anymore
+            if let Some(entry) = entries.lock().unwrap().get_mut(&key) {
+                *entry = status
+            }
+            decoded.fetch_add(1, Ordering::AcqRel);
+            if let Some(proxy) = proxy {
+                let _ = proxy.send_event(AppEvent::Wake);
+            }
+        });
+
+        ImageStatus::Loading
+    }
+}
+
+fn status_of(result: Result<Image, ImageError>) -> ImageStatus {
+    match result {
+        Ok(image) => ImageStatus::Ready(image),
+        Err(error) => ImageStatus::Failed(error),
+    }
+}
+
+// Decodes all the pixels now, skia would otherwise do it lazily when the image is first drawn
+fn decode(bytes: &[u8]) -> Result<Image, ImageError> {
+    let mut codec = Codec::from_data(Data::new_copy(bytes)).ok_or(ImageError::InvalidData)?;
+    codec
+        .get_image(None, None)
+        .map_err(|_| ImageError::InvalidData)
+}
+
+fn read_and_decode(path: &Path) -> Result<Image, ImageError> {
+    let bytes = std::fs::read(path)
+        .map_err(|error| ImageError::Io(path.to_path_buf(), error.to_string()))?;
+    decode(&bytes)
+}
@@ -1,20 +1,72 @@
 mod app_delegate;
+mod app_error;
+mod app_handle;
 mod app_state;
+mod fonts;
+mod images;
+mod shortcuts;
+mod task_pool;
+mod timer;
 mod ui_app_delegate;
+mod window_ctx;
 
 pub use app_delegate::AppDelegate;
+pub use app_error::AppError;
+pub use app_handle::{AppClosed, AppCommand, AppEvent, AppHandle};
 pub use app_state::{AppState, MessageCtx};
+pub use fonts::{FontError, Fonts};
+pub use images::{ImageError, ImageSource, ImageStatus, Images};
+pub use shortcuts::{KeyChord, Shortcut, ShortcutCommand, ShortcutError, ShortcutScope, Shortcuts};
+pub use timer::TimerId;
 pub use ui_app_delegate::UIAppDelegate;
-
-use crate::{widget::Widget, window::WindowRegistry, Queue};
+pub use window_ctx::{WindowCtx, WindowInfo, WindowTarget, WindowUpdate, WindowUpdateRequest};
+
+pub(crate) use timer::{TimerAction, TimerCommand};
+
+use crate::{
+    animation::{Clock, SystemClock},
+    canvas::{Point, Size},
+    widget::{
+        accessibility::AccessibilityConsumer,
+        style::{StyleContext, Theme},
+        Widget,
+    },
+    window::{self, WindowRegistry, WindowStateStore},
+    Queue,
+};
 use pollster::block_on;
-use std::{rc::Rc, sync::mpsc};
+use shortcuts::{KeyTarget, ShortcutMatch};
+use std::{
+    collections::HashMap,
+    future::Future,
+    path::PathBuf,
+    rc::Rc,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+use task_pool::SharedTaskPool;
+use timer::Timers;
 use winit::{
-    event::{Event, WindowEvent},
-    event_loop::{ControlFlow, EventLoop},
+    event::{ElementState, Event, KeyboardInput, ModifiersState, VirtualKeyCode, WindowEvent},
+    event_loop::{ControlFlow, EventLoop, EventLoopBuilder, EventLoopProxy},
     window::{CursorIcon, WindowId},
 };
 
+// Modifiers are part of the chord, pressing them alone doesn't break a sequence
+fn is_modifier(key: VirtualKeyCode) -> bool {
+    matches!(
+        key,
+        VirtualKeyCode::LShift
+            | VirtualKeyCode::RShift
+            | VirtualKeyCode::LControl
+            | VirtualKeyCode::RControl
+            | VirtualKeyCode::LAlt
+            | VirtualKeyCode::RAlt
+            | VirtualKeyCode::LWin
+            | VirtualKeyCode::RWin
+    )
+}
+
 pub struct CursorIconRequest {
     window_id: WindowId,
     cursor_icon: CursorIcon,
@@ -29,11 +81,93 @@ impl CursorIconRequest {
     }
 }
 
+pub struct ThemeFileRequest {
+    name: String,
+    path: PathBuf,
+    watch: bool,
+}
+
+impl ThemeFileRequest {
+    pub fn new(name: &str, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.into(),
+            watch: false,
+        }
+    }
+
+    // Reload the theme whenever the file changes
+    pub fn watched(mut self) -> Self {
+        self.watch = true;
+        self
+    }
+}
+
+pub struct ChangeThemeRequest {
+    window_id: WindowId,
+    theme: String,
+}
+
+impl ChangeThemeRequest {
+    pub fn new(window_id: WindowId, theme: &str) -> Self {
+        Self {
+            window_id,
+            theme: theme.to_string(),
+        }
+    }
+}
+
+// Changes to an open window
+pub enum WindowChange {
+    Title(String),
+    Size(u32, u32),
+    Position(i32, i32),
+    MinSize(Option<(u32, u32)>),
+    MaxSize(Option<(u32, u32)>),
+    Resizable(bool),
+    Fullscreen(bool),
+    Maximized(bool),
+    Minimized(bool),
+    Decorations(bool),
+    AlwaysOnTop(bool),
+    // Transparency can only be chosen when the window is created
+}
+
+pub struct ChangeWindowRequest {
+    window_id: WindowId,
+    change: WindowChange,
+}
+
+impl ChangeWindowRequest {
+    pub fn new(window_id: WindowId, change: WindowChange) -> Self {
+        Self { window_id, change }
+    }
+}
+
 pub struct WindowRequest<State: AppState> {
     pub builder: Box<dyn Fn(&State) -> Box<dyn Widget<State>>>,
     pub title: Option<String>,
     pub width: u32,
     pub height: u32,
+    pub theme: Option<String>,
+    // Stable name the window's position and size are remembered under
+    pub name: Option<String>,
+    // Sizes and positions are in logical pixels
+    pub position: Option<(i32, i32)>,
+    pub min_size: Option<(u32, u32)>,
+    pub max_size: Option<(u32, u32)>,
+    pub resizable: bool,
+    pub fullscreen: bool,
+    pub maximized: bool,
+    pub minimized: bool,
+    pub decorations: bool,
+    pub transparent: bool,
+    pub always_on_top: bool,
+    pub parent: Option<WindowId>,
+    pub modal: bool,
+    pub on_open: Option<Box<dyn FnOnce(WindowId) -> State::Message>>,
+    // Receives the window's accessibility tree, for example a platform adapter
+    pub accessibility: Option<Box<dyn AccessibilityConsumer>>,
 }
 
 impl<State: AppState> WindowRequest<State> {
@@ -48,13 +182,124 @@ impl<State: AppState> WindowRequest<State> {
             width,
             height,
             builder: Box::new(builder),
+            theme: None,
+            name: None,
+            position: None,
+            min_size: None,
+            max_size: None,
+            resizable: true,
+            fullscreen: false,
+            maximized: false,
+            minimized: false,
+            decorations: true,
+            transparent: false,
+            always_on_top: false,
+            parent: None,
+            modal: false,
+            on_open: None,
+            accessibility: None,
         }
     }
+
+    pub fn with_theme(mut self, theme: &str) -> Self {
+        self.theme = Some(theme.to_string());
+        self
+    }
+
+    // The window reopens where it was left when the app has a window state store
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn with_position(mut self, x: i32, y: i32) -> Self {
+        self.position = Some((x, y));
+        self
+    }
+
+    pub fn with_min_size(mut self, width: u32, height: u32) -> Self {
+        self.min_size = Some((width, height));
+        self
+    }
+
+    pub fn with_max_size(mut self, width: u32, height: u32) -> Self {
+        self.max_size = Some((width, height));
+        self
+    }
+
+    pub fn with_resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    pub fn with_fullscreen(mut self, fullscreen: bool) -> Self {
+        self.fullscreen = fullscreen;
+        self
+    }
+
+    pub fn with_maximized(mut self, maximized: bool) -> Self {
+        self.maximized = maximized;
+        self
+    }
+
+    pub fn with_minimized(mut self, minimized: bool) -> Self {
+        self.minimized = minimized;
+        self
+    }
+
+    pub fn with_decorations(mut self, decorations: bool) -> Self {
+        self.decorations = decorations;
+        self
+    }
+
+    pub fn with_transparent(mut self, transparent: bool) -> Self {
+        self.transparent = transparent;
+        self
+    }
+
+    pub fn with_always_on_top(mut self, always_on_top: bool) -> Self {
+        self.always_on_top = always_on_top;
+        self
+    }
+
+    // The window is centered on its parent and closes with it
+    pub fn with_parent(mut self, parent: WindowId) -> Self {
+        self.parent = Some(parent);
+        self
+    }
+
+    // Like a child window, but the parent ignores input while it's open
+    pub fn with_modal_parent(mut self, parent: WindowId) -> Self {
+        self.parent = Some(parent);
+        self.modal = true;
+        self
+    }
+
+    // The message is handled with the id of the window once it's open
+    pub fn on_open<F>(mut self, f: F) -> Self
+    where
+        F: FnOnce(WindowId) -> State::Message + 'static,
+    {
+        self.on_open = Some(Box::new(f));
+        self
+    }
+
+    pub fn with_accessibility(mut self, consumer: impl AccessibilityConsumer + 'static) -> Self {
+        self.accessibility = Some(Box::new(consumer));
+        self
+    }
 }
 
 pub enum AppRequest<State: AppState> {
     OpenWindow(WindowRequest<State>),
     ChangeCursorRequest(CursorIconRequest),
+    RegisterTheme(String, Box<Theme>),
+    LoadTheme(ThemeFileRequest),
+    ChangeTheme(ChangeThemeRequest),
+    CloseWindow(WindowId),
+    ChangeWindow(ChangeWindowRequest),
+    UpdateWindow(WindowUpdateRequest<State>),
+    Quit,
 }
 
 pub struct GpuApi {
@@ -108,6 +353,21 @@ pub struct App<State: AppState> {
     pub message_tx: mpsc::Sender<State::Message>,
     message_tr: mpsc::Receiver<State::Message>,
     pending_requests: Queue<AppRequest<State>>,
+    style_ctx: StyleContext,
+    fonts: Fonts,
+    images: Images,
+    clock: Box<dyn Clock>,
+    frame_time: Duration,
+    timers: Timers<State::Message>,
+    timer_tx: mpsc::Sender<TimerCommand<State::Message>>,
+    timer_rx: mpsc::Receiver<TimerCommand<State::Message>>,
+    // Shared with the image cache
+    task_pool: SharedTaskPool,
+    event_loop: Option<EventLoop<AppEvent>>,
+    proxy: EventLoopProxy<AppEvent>,
+    window_state_store: Option<WindowStateStore>,
+    windows: HashMap<WindowId, WindowInfo>,
+    shortcuts: Shortcuts<State::Message>,
     _state: std::marker::PhantomData<State>,
 }
 
@@ -116,20 +376,246 @@ impl<State: AppState + 'static> App<State> {
         let gpu_api = block_on(GpuApi::new());
 
         let (message_tx, message_tr) = mpsc::channel();
+        let (timer_tx, timer_rx) = mpsc::channel();
+        // Created up front so tasks spawned before run can already wake the loop
+        let event_loop = EventLoopBuilder::with_user_event().build();
+        let proxy = event_loop.create_proxy();
+        let task_pool = SharedTaskPool::default();
 
         Self {
             message_tx,
             message_tr,
             pending_requests: Queue::new(),
+            style_ctx: StyleContext::new().with_proxy(proxy.clone()),
+            fonts: Fonts::new(),
+            images: Images::new()
+                .with_proxy(proxy.clone())
+                .with_task_pool(task_pool.clone()),
+            clock: Box::new(SystemClock::new()),
+            frame_time: Duration::ZERO,
+            timers: Timers::new(),
+            timer_tx,
+            timer_rx,
+            task_pool,
+            event_loop: Some(event_loop),
+            proxy,
+            window_state_store: None,
+            windows: HashMap::new(),
+            shortcuts: Shortcuts::new(),
             _state: std::marker::PhantomData::<State>::default(),
             gpu_api,
         }
     }
 
+    // Remembers where named windows were left, see WindowRequest::with_name
+    pub fn with_window_state_store(mut self, store: WindowStateStore) -> Self {
+        self.window_state_store = Some(store);
+        self
+    }
+
+    pub fn with_shortcuts(mut self, shortcuts: Shortcuts<State::Message>) -> Self {
+        self.shortcuts = shortcuts;
+        self
+    }
+
+    pub fn shortcuts(&self) -> &Shortcuts<State::Message> {
+        &self.shortcuts
+    }
+
+    pub fn shortcuts_mut(&mut self) -> &mut Shortcuts<State::Message> {
+        &mut self.shortcuts
+    }
+
+    // Publishes the message of the shortcut the key completes. Returns true when the key belongs to a
+    // shortcut, it's kept from the widgets then.
+    fn shortcut_key(
+        &mut self,
+        window_registry: &WindowRegistry<State>,
+        window_id: WindowId,
+        input: &KeyboardInput,
+        modifiers: ModifiersState,
+    ) -> bool {
+        let key = match input.virtual_keycode {
+            Some(key) if input.state == ElementState::Pressed && !is_modifier(key) => key,
+            _ => return false,
+        };
+        if window_registry.is_blocked(&window_id) {
+            return false;
+        }
+
+        let focus_keys = window_registry.focus_keys(&window_id);
+        let target = KeyTarget {
+            window_id,
+            window_name: window_registry.name(&window_id),
+            focus_keys: &focus_keys,
+        };
+        let chord = KeyChord { key, modifiers };
+        match self.shortcuts.key_pressed(&target, chord, self.clock.now()) {
+            ShortcutMatch::Triggered(message) => {
+                self.message_tx.send(message).unwrap();
+                true
+            }
+            ShortcutMatch::Pending => true,
+            ShortcutMatch::None => false,
+        }
+    }
+
     pub fn gpu_api(&self) -> &GpuApi {
         &self.gpu_api
     }
 
+    pub fn style_ctx(&self) -> &StyleContext {
+        &self.style_ctx
+    }
+
+    pub fn style_ctx_mut(&mut self) -> &mut StyleContext {
+        &mut self.style_ctx
+    }
+
+    // Shared by all windows, register the fonts the app bundles here
+    pub fn fonts(&self) -> &Fonts {
+        &self.fonts
+    }
+
+    // Decoded images, shared by all windows
+    pub fn images(&self) -> &Images {
+        &self.images
+    }
+
+    // Replaces the clock animations run on, a ManualClock makes them deterministic
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.frame_time = clock.now();
+        self.clock = Box::new(clock);
+    }
+
+    // The time every widget sees while handling the current event or painting the current frame
+    pub fn frame_time(&self) -> Duration {
+        self.frame_time
+    }
+
+    // Handles the message after the delay has passed
+    pub fn schedule(&mut self, delay: Duration, message: State::Message) -> TimerId {
+        let id = TimerId::next();
+        self.timers.schedule(
+            id,
+            self.frame_time,
+            delay,
+            None,
+            TimerAction::Message(message),
+        );
+        id
+    }
+
+    // Handles the message every interval until the timer is cancelled
+    pub fn schedule_interval(&mut self, interval: Duration, message: State::Message) -> TimerId {
+        let id = TimerId::next();
+        self.timers.schedule(
+            id,
+            self.frame_time,
+            interval,
+            Some(interval),
+            TimerAction::Message(message),
+        );
+        id
+    }
+
+    pub fn cancel_timer(&mut self, id: TimerId) {
+        self.timers.cancel(id)
+    }
+
+    pub(crate) fn timer_tx(&self) -> mpsc::Sender<TimerCommand<State::Message>> {
+        self.timer_tx.clone()
+    }
+
+    // Timers started by widgets arrive through a channel, like their messages
+    fn apply_timer_commands(&mut self) {
+        while let Ok(command) = self.timer_rx.try_recv() {
+            self.timers.apply(self.frame_time, command)
+        }
+    }
+
+    // Polls the future on the worker threads and handles its output as a message once it completes.
+    // A pending future doesn't occupy a worker, it's polled again when its waker is woken.
+    // There's no io reactor though, use spawn_blocking for work that blocks.
+    pub fn spawn<F>(&mut self, future: F)
+    where
+        F: Future<Output = State::Message> + Send + 'static,
+        State::Message: Send,
+    {
+        let handle = self.handle();
+        self.task_pool.spawn(async move {
+            // The loop may already be gone when the app is quitting
+            let _ = handle.send(future.await);
+        })
+    }
+
+    // Runs the closure on a worker thread, for blocking io and long computations
+    pub fn spawn_blocking<F>(&mut self, f: F)
+    where
+        F: FnOnce() -> State::Message + Send + 'static,
+        State::Message: Send,
+    {
+        let handle = self.handle();
+        self.task_pool.execute(move || {
+            // The loop may already be gone when the app is quitting
+            let _ = handle.send(f());
+        })
+    }
+
+    // A handle other threads can use to send messages and commands to the app
+    pub fn handle(&self) -> AppHandle<State::Message> {
+        AppHandle::new(self.message_tx.clone(), self.proxy.clone())
+    }
+
+    // Reaches a window's widget tree from outside, for example from AppState::handle_message
+    pub fn window(&mut self, target: impl Into<WindowTarget>) -> WindowCtx<State> {
+        WindowCtx::new(self, target.into())
+    }
+
+    // The windows that were open at the start of the frame
+    pub fn windows(&self) -> impl Iterator<Item = &WindowInfo> {
+        self.windows.values()
+    }
+
+    pub fn window_info(&self, window_id: WindowId) -> Option<&WindowInfo> {
+        self.windows.get(&window_id)
+    }
+
+    pub(crate) fn resolve_window(&self, target: &WindowTarget) -> Option<WindowId> {
+        match target {
+            WindowTarget::Id(window_id) => {
+                self.windows.contains_key(window_id).then_some(*window_id)
+            }
+            WindowTarget::Name(name) => self
+                .windows
+                .values()
+                .find(|info| info.name.as_ref() == Some(name))
+                .map(|info| info.id),
+        }
+    }
+
+    // Delivers the event to the widgets of the window and to the state
+    fn window_event(
+        &mut self,
+        window_registry: &mut WindowRegistry<State>,
+        state: &State,
+        window_id: WindowId,
+        event: window::WindowEvent,
+    ) {
+        window_registry.window_event(self, state, &window_id, &event);
+        if let Some(message) = state.window_event(window_id, &event) {
+            self.message_tx.send(message).unwrap()
+        }
+    }
+
+    // Lets the state turn the error into a message, see AppState::app_error
+    fn report_error(&self, state: &State, error: AppError) {
+        if let Some(message) = state.app_error(&error) {
+            self.message_tx.send(message).unwrap();
+            let _ = self.proxy.send_event(AppEvent::Wake);
+        }
+    }
+
     pub fn request(&mut self, request: AppRequest<State>) {
         self.pending_requests.push(request)
     }
@@ -138,24 +624,62 @@ impl<State: AppState + 'static> App<State> {
     where
         Delegate: AppDelegate<State> + 'static,
     {
-        let event_loop = EventLoop::new();
+        let event_loop = self
+            .event_loop
+            .take()
+            .expect("The event loop is created with the app");
         let mut d = delegate;
 
         let mut window_registry = WindowRegistry::new();
+        window_registry.set_timer_tx(self.timer_tx());
+        if let Some(store) = self.window_state_store.take() {
+            window_registry.set_state_store(store)
+        }
 
         d.app_will_start(&mut self, &state, &mut window_registry, &event_loop);
         let mut last_mouse_position = winit::dpi::PhysicalPosition::<f64>::new(0., 0.);
         let mut last_file_drop: Vec<std::path::PathBuf> = Vec::new();
         let mut mouse_is_down = false;
+        let mut modifiers = ModifiersState::empty();
+        // The character of a key that triggered a shortcut isn't typed
+        let mut shortcut_pressed = false;
         event_loop.run(move |e, event_loop, control_flow| {
+            self.frame_time = self.clock.now();
+
+            self.apply_timer_commands();
+            for (id, action) in self.timers.expire(self.frame_time) {
+                match action {
+                    TimerAction::Message(message) => self.message_tx.send(message).unwrap(),
+                    TimerAction::Window(window_id) => {
+                        window_registry.timer_fired(&mut self, &state, &window_id, id)
+                    }
+                }
+            }
+
+            let mut state_changed = false;
             while let Ok(message) = self.message_tr.try_recv() {
                 state.handle_message(message, &mut MessageCtx::new(&mut self));
+                state_changed = true;
+            }
+
+            if state_changed {
+                window_registry.state_changed(&state);
             }
 
             while let Some(request) = self.pending_requests.pop() {
                 match request {
-                    AppRequest::OpenWindow(request) => {
-                        d.window_requested(&self, &state, &mut window_registry, event_loop, request)
+                    AppRequest::OpenWindow(mut request) => {
+                        let on_open = request.on_open.take();
+                        let window_id = d.window_requested(
+                            &self,
+                            &state,
+                            &mut window_registry,
+                            event_loop,
+                            request,
+                        );
+                        if let (Some(window_id), Some(on_open)) = (window_id, on_open) {
+                            self.message_tx.send(on_open(window_id)).unwrap()
+                        }
                     }
 
                     AppRequest::ChangeCursorRequest(request) => {
@@ -163,15 +687,67 @@ impl<State: AppState + 'static> App<State> {
                             entry.window.set_cursor_icon(request.cursor_icon)
                         }
                     }
+
+                    AppRequest::RegisterTheme(name, theme) => {
+                        self.style_ctx.register(&name, *theme)
+                    }
+
+                    AppRequest::LoadTheme(request) => {
+                        let result = if request.watch {
+                            self.style_ctx.watch(&request.name, &request.path)
+                        } else {
+                            self.style_ctx.load(&request.name, &request.path)
+                        };
+
+                        if let Err(error) = result {
+                            let name = request.name.clone();
+                            self.report_error(&state, AppError::LoadTheme { name, error })
+                        }
+                    }
+
+                    AppRequest::ChangeTheme(request) => {
+                        window_registry.set_theme(&request.window_id, &request.theme)
+                    }
+
+                    AppRequest::CloseWindow(window_id) => {
+                        window_registry.close(&window_id);
+                        if window_registry.active_window_count() == 0 {
+                            *control_flow = ControlFlow::Exit;
+                        }
+                    }
+
+                    AppRequest::ChangeWindow(request) => {
+                        if let Some(entry) = window_registry.get_mut(request.window_id) {
+                            entry.apply(request.change)
+                        }
+                    }
+
+                    AppRequest::UpdateWindow(request) => window_registry.update_window(
+                        &self,
+                        &state,
+                        &request.window_id,
+                        request.update,
+                    ),
+
+                    AppRequest::Quit => *control_flow = ControlFlow::Exit,
                 }
             }
 
-            *control_flow = winit::event_loop::ControlFlow::Poll;
             match e {
+                // Posted messages were already handled above, commands are handled with the next requests
+                Event::UserEvent(AppEvent::Wake) => (),
+                Event::UserEvent(AppEvent::Command(command)) => self.request(command.into()),
+
                 Event::WindowEvent {
                     event: WindowEvent::CloseRequested,
                     window_id,
                 } => {
+                    self.window_event(
+                        &mut window_registry,
+                        &state,
+                        window_id,
+                        window::WindowEvent::CloseRequested,
+                    );
                     window_registry.close_button_pressed(&window_id, &state);
                     if window_registry.active_window_count() == 0 {
                         *control_flow = ControlFlow::Exit;
@@ -186,12 +762,64 @@ impl<State: AppState + 'static> App<State> {
                 Event::WindowEvent {
                     event: WindowEvent::Moved(physical_position),
                     window_id,
-                } => window_registry.window_moved(&window_id, &physical_position),
+                } => {
+                    window_registry.window_moved(&window_id, &physical_position);
+                    self.window_event(
+                        &mut window_registry,
+                        &state,
+                        window_id,
+                        window::WindowEvent::Moved(Point::new(
+                            physical_position.x as f32,
+                            physical_position.y as f32,
+                        )),
+                    )
+                }
 
                 Event::WindowEvent {
                     event: WindowEvent::Resized(physical_size),
                     window_id,
-                } => window_registry.window_resized(&self, &state, &window_id, &physical_size),
+                } => {
+                    window_registry.window_resized(&self, &state, &window_id, &physical_size);
+                    self.window_event(
+                        &mut window_registry,
+                        &state,
+                        window_id,
+                        window::WindowEvent::Resized(Size::new(
+                            physical_size.width as f32,
+                            physical_size.height as f32,
+                        )),
+                    )
+                }
+
+                Event::WindowEvent {
+                    event: WindowEvent::Occluded(occluded),
+                    window_id,
+                } => self.window_event(
+                    &mut window_registry,
+                    &state,
+                    window_id,
+                    window::WindowEvent::Occluded(occluded),
+                ),
+
+                Event::WindowEvent {
+                    event: WindowEvent::ThemeChanged(theme),
+                    window_id,
+                } => self.window_event(
+                    &mut window_registry,
+                    &state,
+                    window_id,
+                    window::WindowEvent::ThemeChanged(theme),
+                ),
+
+                Event::WindowEvent {
+                    event: WindowEvent::CursorLeft { .. },
+                    window_id,
+                } => self.window_event(
+                    &mut window_registry,
+                    &state,
+                    window_id,
+                    window::WindowEvent::CursorLeft,
+                ),
 
                 Event::WindowEvent {
                     event: WindowEvent::DroppedFile(path_buffer),
@@ -210,7 +838,15 @@ impl<State: AppState + 'static> App<State> {
                     event: WindowEvent::Focused(f),
                     window_id,
                 } => {
+                    let event = if f {
+                        window::WindowEvent::Focused
+                    } else {
+                        window::WindowEvent::Blurred
+                    };
+                    self.window_event(&mut window_registry, &state, window_id, event);
+
                     *control_flow = if f {
+                        window_registry.window_focused(&window_id);
                         d.window_got_focus(&window_id)
                     } else {
                         d.window_lost_focus(&window_id)
@@ -253,12 +889,32 @@ impl<State: AppState + 'static> App<State> {
                 Event::WindowEvent {
                     window_id,
                     event: WindowEvent::ReceivedCharacter(character),
-                } => window_registry.character_received(&window_id, &mut self, character, &state),
+                } => {
+                    if !shortcut_pressed {
+                        window_registry.character_received(&window_id, &mut self, character, &state)
+                    }
+                }
+
+                Event::WindowEvent {
+                    event: WindowEvent::ModifiersChanged(changed),
+                    ..
+                } => modifiers = changed,
 
                 Event::WindowEvent {
                     window_id,
                     event: WindowEvent::KeyboardInput { input, .. },
-                } => window_registry.keyboard_event(&window_id, &mut self, &input, &state),
+                } => {
+                    shortcut_pressed =
+                        self.shortcut_key(&window_registry, window_id, &input, modifiers);
+                    if !shortcut_pressed {
+                        window_registry.keyboard_event(&window_id, &mut self, &input, &state)
+                    }
+                }
+
+                Event::WindowEvent {
+                    window_id,
+                    event: WindowEvent::Ime(ime),
+                } => window_registry.ime_event(&window_id, &mut self, &ime, &state),
 
                 Event::WindowEvent {
                     event: WindowEvent::MouseInput { state: s, .. },
@@ -284,13 +940,38 @@ impl<State: AppState + 'static> App<State> {
                     }
                 },
                 Event::MainEventsCleared => {
+                    // A theme that fails to reload keeps its previous version, so just report it
+                    for result in self.style_ctx.reload_changed() {
+                        if let Err(error) = result {
+                            self.report_error(&state, AppError::ReloadTheme(error))
+                        }
+                    }
+
+                    self.windows = window_registry.window_infos();
                     d.app_will_update(&self, &state, &mut window_registry, event_loop);
                     window_registry.update(&state);
                     window_registry.draw(&self, &state);
+
+                    // Only keep painting while something animates, otherwise sleep until the next event or timer
+                    self.apply_timer_commands();
+                    if !matches!(*control_flow, ControlFlow::ExitWithCode(_)) {
+                        *control_flow = if window_registry.is_animating() {
+                            ControlFlow::Poll
+                        } else if let Some(deadline) = self.timers.next_deadline() {
+                            let delay = deadline.saturating_sub(self.clock.now());
+                            ControlFlow::WaitUntil(Instant::now() + delay)
+                        } else {
+                            ControlFlow::Wait
+                        }
+                    }
                 }
                 _ => (),
             }
 
+            for error in window_registry.take_errors() {
+                self.report_error(&state, error)
+            }
+
             if let ControlFlow::Exit = *control_flow {
                 d.app_will_quit(&mut self, event_loop)
             }
@@ -0,0 +1,655 @@
+use super::WindowTarget;
+use std::{borrow::Cow, collections::HashMap, fmt, str::FromStr, time::Duration};
+use thiserror::Error;
+use winit::{
+    event::{ModifiersState, VirtualKeyCode},
+    window::WindowId,
+};
+
+// How long the next key of a sequence is waited for
+const SEQUENCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ShortcutError {
+    #[error("\"{0}\" is already registered")]
+    DuplicateName(String),
+    #[error("the keys of \"{name}\" conflict with \"{existing}\" in the same scope")]
+    Conflict { name: String, existing: String },
+    #[error("invalid shortcut \"{0}\"")]
+    Parse(String),
+}
+
+// A key together with the modifiers held down with it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    pub key: VirtualKeyCode,
+    pub modifiers: ModifiersState,
+}
+
+impl KeyChord {
+    pub fn new(key: VirtualKeyCode) -> Self {
+        Self {
+            key,
+            modifiers: ModifiersState::empty(),
+        }
+    }
+
+    pub fn with_ctrl(mut self) -> Self {
+        self.modifiers |= ModifiersState::CTRL;
+        self
+    }
+
+    pub fn with_shift(mut self) -> Self {
+        self.modifiers |= ModifiersState::SHIFT;
+        self
+    }
+
+    pub fn with_alt(mut self) -> Self {
+        self.modifiers |= ModifiersState::ALT;
+        self
+    }
+
+    pub fn with_logo(mut self) -> Self {
+        self.modifiers |= ModifiersState::LOGO;
+        self
+    }
+
+    // Cmd on macOS and Ctrl everywhere else
+    pub fn with_primary(mut self) -> Self {
+        self.modifiers |= primary_modifier();
+        self
+    }
+}
+
+fn primary_modifier() -> ModifiersState {
+    if cfg!(target_os = "macos") {
+        ModifiersState::LOGO
+    } else {
+        ModifiersState::CTRL
+    }
+}
+
+// Parses chords like "Ctrl+Shift+S" or "Primary+Enter". Primary is Cmd on macOS and Ctrl elsewhere.
+impl FromStr for KeyChord {
+    type Err = ShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ShortcutError::Parse(s.to_string());
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let key = parts
+            .pop()
+            .filter(|key| !key.is_empty())
+            .ok_or_else(error)?;
+        let mut chord = KeyChord::new(parse_key(key).ok_or_else(error)?);
+        for modifier in parts {
+            chord = match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => chord.with_ctrl(),
+                "shift" => chord.with_shift(),
+                "alt" | "option" => chord.with_alt(),
+                "cmd" | "super" | "logo" | "meta" => chord.with_logo(),
+                "primary" | "cmdorctrl" => chord.with_primary(),
+                _ => return Err(error()),
+            }
+        }
+
+        Ok(chord)
+    }
+}
+
+fn parse_key(name: &str) -> Option<VirtualKeyCode> {
+    use VirtualKeyCode::*;
+
+    const LETTERS: [VirtualKeyCode; 26] = [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    ];
+    const DIGITS: [VirtualKeyCode; 10] =
+        [Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9];
+    const FUNCTION_KEYS: [VirtualKeyCode; 12] = [F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12];
+
+    let lower = name.to_ascii_lowercase();
+    let mut chars = lower.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c @ 'a'..='z'), None) => return Some(LETTERS[c as usize - 'a' as usize]),
+        (Some(c @ '0'..='9'), None) => return Some(DIGITS[c as usize - '0' as usize]),
+        _ => (),
+    }
+
+    if let Some(number) = lower
+        .strip_prefix('f')
+        .and_then(|n| n.parse::<usize>().ok())
+    {
+        return FUNCTION_KEYS.get(number.checked_sub(1)?).copied();
+    }
+
+    Some(match lower.as_str() {
+        "enter" | "return" => Return,
+        "escape" | "esc" => Escape,
+        "tab" => Tab,
+        "space" => Space,
+        "backspace" => Back,
+        "delete" | "del" => Delete,
+        "insert" => Insert,
+        "home" => Home,
+        "end" => End,
+        "pageup" => PageUp,
+        "pagedown" => PageDown,
+        "up" => Up,
+        "down" => Down,
+        "left" => Left,
+        "right" => Right,
+        "plus" => Plus,
+        "-" | "minus" => Minus,
+        "=" | "equals" => Equals,
+        "," | "comma" => Comma,
+        "." | "period" => Period,
+        "/" | "slash" => Slash,
+        _ => return None,
+    })
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, name) in [
+            (ModifiersState::CTRL, "Ctrl+"),
+            (ModifiersState::ALT, "Alt+"),
+            (ModifiersState::SHIFT, "Shift+"),
+            (ModifiersState::LOGO, "Cmd+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(name)?
+            }
+        }
+
+        f.write_str(&key_name(self.key))
+    }
+}
+
+// How the key is shown in menus and hints, parse_key accepts every name it returns
+fn key_name(key: VirtualKeyCode) -> Cow<'static, str> {
+    use VirtualKeyCode::*;
+
+    Cow::Borrowed(match key {
+        Key0 => "0",
+        Key1 => "1",
+        Key2 => "2",
+        Key3 => "3",
+        Key4 => "4",
+        Key5 => "5",
+        Key6 => "6",
+        Key7 => "7",
+        Key8 => "8",
+        Key9 => "9",
+        Return => "Enter",
+        Escape => "Esc",
+        Back => "Backspace",
+        Minus => "-",
+        Equals => "=",
+        Comma => ",",
+        Period => ".",
+        Slash => "/",
+        // Letters, function keys and the rest are named like the variant
+        _ => return Cow::Owned(format!("{:?}", key)),
+    })
+}
+
+// One or more chords pressed one after the other, like "Ctrl+K Ctrl+C"
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    chords: Vec<KeyChord>,
+}
+
+impl Shortcut {
+    pub fn new(chord: KeyChord) -> Self {
+        Self {
+            chords: vec![chord],
+        }
+    }
+
+    pub fn then(mut self, chord: KeyChord) -> Self {
+        self.chords.push(chord);
+        self
+    }
+
+    pub fn chords(&self) -> &[KeyChord] {
+        &self.chords
+    }
+
+    // Equal shortcuts, or one starting with the other, can't be told apart while typing
+    fn overlaps(&self, other: &Shortcut) -> bool {
+        self.chords.starts_with(&other.chords) || other.chords.starts_with(&self.chords)
+    }
+}
+
+impl From<KeyChord> for Shortcut {
+    fn from(chord: KeyChord) -> Self {
+        Self::new(chord)
+    }
+}
+
+// Chords are separated by spaces
+impl FromStr for Shortcut {
+    type Err = ShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chords = s
+            .split_whitespace()
+            .map(KeyChord::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        if chords.is_empty() {
+            return Err(ShortcutError::Parse(s.to_string()));
+        }
+
+        Ok(Self { chords })
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, chord) in self.chords.iter().enumerate() {
+            if index > 0 {
+                f.write_str(" ")?
+            }
+            write!(f, "{}", chord)?
+        }
+
+        Ok(())
+    }
+}
+
+// Where a shortcut works. Narrower scopes win over wider ones when their keys overlap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShortcutScope {
+    App,
+    Window(WindowTarget),
+    // While the widget with the key, or anything inside it, has focus
+    Widget(String),
+}
+
+pub struct ShortcutCommand<Message> {
+    name: String,
+    shortcut: Shortcut,
+    scope: ShortcutScope,
+    message: Message,
+}
+
+impl<Message> ShortcutCommand<Message> {
+    pub fn new(name: &str, shortcut: impl Into<Shortcut>, message: Message) -> Self {
+        Self {
+            name: name.to_string(),
+            shortcut: shortcut.into(),
+            scope: ShortcutScope::App,
+            message,
+        }
+    }
+
+    pub fn in_window(mut self, window: impl Into<WindowTarget>) -> Self {
+        self.scope = ShortcutScope::Window(window.into());
+        self
+    }
+
+    pub fn in_widget(mut self, key: &str) -> Self {
+        self.scope = ShortcutScope::Widget(key.to_string());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn shortcut(&self) -> &Shortcut {
+        &self.shortcut
+    }
+
+    pub fn scope(&self) -> &ShortcutScope {
+        &self.scope
+    }
+}
+
+// The window a key was pressed in, and what had focus there
+pub(crate) struct KeyTarget<'a> {
+    pub(crate) window_id: WindowId,
+    pub(crate) window_name: Option<&'a str>,
+    // Keys of the focused widget and its ancestors, innermost first
+    pub(crate) focus_keys: &'a [String],
+}
+
+impl<'a> KeyTarget<'a> {
+    // Lower is narrower, None when the scope doesn't apply
+    fn rank(&self, scope: &ShortcutScope) -> Option<usize> {
+        match scope {
+            ShortcutScope::Widget(key) => self.focus_keys.iter().position(|k| k == key),
+            ShortcutScope::Window(WindowTarget::Id(id)) => {
+                (*id == self.window_id).then_some(self.focus_keys.len())
+            }
+            ShortcutScope::Window(WindowTarget::Name(name)) => {
+                (Some(name.as_str()) == self.window_name).then_some(self.focus_keys.len())
+            }
+            ShortcutScope::App => Some(self.focus_keys.len() + 1),
+        }
+    }
+}
+
+pub(crate) enum ShortcutMatch<Message> {
+    Triggered(Message),
+    // The key continues a sequence, it's swallowed until the sequence completes or breaks
+    Pending,
+    None,
+}
+
+// Named commands bound to shortcuts. Shortcuts are checked before key events reach the widgets.
+pub struct Shortcuts<Message> {
+    commands: Vec<ShortcutCommand<Message>>,
+    // Chords of an unfinished sequence per window, with the time of the last one
+    pending: HashMap<WindowId, (Vec<KeyChord>, Duration)>,
+}
+
+impl<Message: Clone> Shortcuts<Message> {
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, command: ShortcutCommand<Message>) -> Result<(), ShortcutError> {
+        if self.get(&command.name).is_some() {
+            return Err(ShortcutError::DuplicateName(command.name));
+        }
+
+        if let Some(existing) = self.conflicting(&command.shortcut, &command.scope) {
+            return Err(ShortcutError::Conflict {
+                name: command.name,
+                existing: existing.name.clone(),
+            });
+        }
+
+        self.commands.push(command);
+        Ok(())
+    }
+
+    pub fn unregister(&mut self, name: &str) -> Option<ShortcutCommand<Message>> {
+        let index = self.commands.iter().position(|c| c.name == name)?;
+        Some(self.commands.remove(index))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ShortcutCommand<Message>> {
+        self.commands.iter().find(|command| command.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &ShortcutCommand<Message>> {
+        self.commands.iter()
+    }
+
+    // The command in the same scope whose keys can't be told apart from the shortcut
+    pub fn conflicting(
+        &self,
+        shortcut: &Shortcut,
+        scope: &ShortcutScope,
+    ) -> Option<&ShortcutCommand<Message>> {
+        self.commands
+            .iter()
+            .find(|command| command.scope == *scope && command.shortcut.overlaps(shortcut))
+    }
+
+    pub(crate) fn key_pressed(
+        &mut self,
+        target: &KeyTarget,
+        chord: KeyChord,
+        now: Duration,
+    ) -> ShortcutMatch<Message> {
+        let mut chords = match self.pending.remove(&target.window_id) {
+            Some((chords, last)) if now.saturating_sub(last) < SEQUENCE_TIMEOUT => chords,
+            _ => Vec::new(),
+        };
+        chords.push(chord);
+
+        let result = self.find(target, &chords, now);
+        // A broken sequence starts over with the key that broke it
+        if chords.len() > 1 && matches!(result, ShortcutMatch::None) {
+            return self.find(target, &[chord], now);
+        }
+
+        result
+    }
+
+    fn find(
+        &mut self,
+        target: &KeyTarget,
+        chords: &[KeyChord],
+        now: Duration,
+    ) -> ShortcutMatch<Message> {
+        let active = self
+            .commands
+            .iter()
+            .filter_map(|command| target.rank(&command.scope).map(|rank| (rank, command)));
+
+        let mut triggered: Option<(usize, &ShortcutCommand<Message>)> = None;
+        let mut continues = false;
+        for (rank, command) in active {
+            let keys = command.shortcut.chords();
+            if keys == chords {
+                if triggered.map_or(true, |(best, _)| rank < best) {
+                    triggered = Some((rank, command))
+                }
+            } else if keys.starts_with(chords) {
+                continues = true
+            }
+        }
+
+        if let Some((_, command)) = triggered {
+            ShortcutMatch::Triggered(command.message.clone())
+        } else if continues {
+            self.pending
+                .insert(target.window_id, (chords.to_vec(), now));
+            ShortcutMatch::Pending
+        } else {
+            ShortcutMatch::None
+        }
+    }
+}
+
+impl<Message: Clone> Default for Shortcuts<Message> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use VirtualKeyCode::*;
+
+    fn chord(s: &str) -> KeyChord {
+        s.parse().unwrap()
+    }
+
+    fn command(name: &'static str, shortcut: &str) -> ShortcutCommand<&'static str> {
+        ShortcutCommand::new(name, shortcut.parse::<Shortcut>().unwrap(), name)
+    }
+
+    fn target(focus_keys: &[String]) -> KeyTarget {
+        KeyTarget {
+            // Only compared with itself
+            window_id: unsafe { WindowId::dummy() },
+            window_name: Some("main"),
+            focus_keys,
+        }
+    }
+
+    fn millis(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    fn triggered(result: ShortcutMatch<&'static str>) -> Option<&'static str> {
+        match result {
+            ShortcutMatch::Triggered(message) => Some(message),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn parses_chords() {
+        assert_eq!(
+            chord("Ctrl+Shift+S"),
+            KeyChord::new(S).with_ctrl().with_shift()
+        );
+        assert_eq!(
+            chord("shift+ctrl+s"),
+            KeyChord::new(S).with_ctrl().with_shift()
+        );
+        assert_eq!(chord(" Alt + F4 "), KeyChord::new(F4).with_alt());
+        assert_eq!(chord("Primary+Enter"), KeyChord::new(Return).with_primary());
+        assert_eq!(chord("Cmd+,"), KeyChord::new(Comma).with_logo());
+        assert_eq!(chord("Ctrl+Plus"), KeyChord::new(Plus).with_ctrl());
+        assert_eq!(chord("7"), KeyChord::new(Key7));
+    }
+
+    #[test]
+    fn rejects_invalid_chords() {
+        for invalid in ["", "Ctrl+", "Ctrl+Hyper+S", "F0", "F13", "Ctrl+SS", "Shift"] {
+            assert_eq!(
+                invalid.parse::<KeyChord>(),
+                Err(ShortcutError::Parse(invalid.to_string())),
+                "{:?}",
+                invalid
+            );
+        }
+
+        assert!("  ".parse::<Shortcut>().is_err());
+        assert!("Ctrl+K Hyper+C".parse::<Shortcut>().is_err());
+    }
+
+    #[test]
+    fn parses_sequences() {
+        let shortcut: Shortcut = "Ctrl+K  Ctrl+C".parse().unwrap();
+        assert_eq!(
+            shortcut,
+            Shortcut::new(KeyChord::new(K).with_ctrl()).then(KeyChord::new(C).with_ctrl())
+        );
+    }
+
+    #[test]
+    fn displays_key_names() {
+        assert_eq!(KeyChord::new(Key1).with_ctrl().to_string(), "Ctrl+1");
+        assert_eq!(
+            KeyChord::new(Return).with_shift().with_ctrl().to_string(),
+            "Ctrl+Shift+Enter"
+        );
+        assert_eq!(KeyChord::new(Escape).to_string(), "Esc");
+        assert_eq!(KeyChord::new(F5).with_alt().to_string(), "Alt+F5");
+
+        // What's shown can be parsed back
+        for shortcut in [
+            "Ctrl+K Ctrl+C",
+            "Alt+Shift+Backspace",
+            "Ctrl+-",
+            "Ctrl+Plus",
+            "PageUp",
+        ] {
+            let parsed: Shortcut = shortcut.parse().unwrap();
+            assert_eq!(parsed.to_string(), shortcut);
+            assert_eq!(parsed.to_string().parse::<Shortcut>().unwrap(), parsed);
+        }
+    }
+
+    #[test]
+    fn sequences_trigger_on_their_last_chord() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts
+            .register(command("comment", "Ctrl+K Ctrl+C"))
+            .unwrap();
+        shortcuts.register(command("save", "Ctrl+S")).unwrap();
+        let target = target(&[]);
+
+        let result = shortcuts.key_pressed(&target, chord("Ctrl+K"), millis(0));
+        assert!(matches!(result, ShortcutMatch::Pending));
+        let result = shortcuts.key_pressed(&target, chord("Ctrl+C"), millis(300));
+        assert_eq!(triggered(result), Some("comment"));
+
+        // The sequence is over, the second chord alone isn't bound
+        let result = shortcuts.key_pressed(&target, chord("Ctrl+C"), millis(400));
+        assert!(matches!(result, ShortcutMatch::None));
+    }
+
+    #[test]
+    fn sequences_break_on_timeout_and_other_keys() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts
+            .register(command("comment", "Ctrl+K Ctrl+C"))
+            .unwrap();
+        shortcuts.register(command("save", "Ctrl+S")).unwrap();
+        let target = target(&[]);
+
+        shortcuts.key_pressed(&target, chord("Ctrl+K"), millis(0));
+        let result = shortcuts.key_pressed(&target, chord("Ctrl+C"), millis(2000));
+        assert!(matches!(result, ShortcutMatch::None));
+
+        // The key that breaks a sequence counts on its own
+        shortcuts.key_pressed(&target, chord("Ctrl+K"), millis(3000));
+        let result = shortcuts.key_pressed(&target, chord("Ctrl+S"), millis(3100));
+        assert_eq!(triggered(result), Some("save"));
+    }
+
+    #[test]
+    fn narrower_scopes_win() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts.register(command("save all", "Ctrl+S")).unwrap();
+        shortcuts
+            .register(command("save window", "Ctrl+S").in_window("main"))
+            .unwrap();
+        shortcuts
+            .register(command("save file", "Ctrl+S").in_widget("editor"))
+            .unwrap();
+
+        let in_editor = ["editor".to_string(), "panel".to_string()];
+        let result = shortcuts.key_pressed(&target(&in_editor), chord("Ctrl+S"), millis(0));
+        assert_eq!(triggered(result), Some("save file"));
+
+        let in_panel = ["panel".to_string()];
+        let result = shortcuts.key_pressed(&target(&in_panel), chord("Ctrl+S"), millis(0));
+        assert_eq!(triggered(result), Some("save window"));
+
+        let other_window = KeyTarget {
+            window_name: None,
+            ..target(&[])
+        };
+        let result = shortcuts.key_pressed(&other_window, chord("Ctrl+S"), millis(0));
+        assert_eq!(triggered(result), Some("save all"));
+    }
+
+    #[test]
+    fn detects_conflicts_in_the_same_scope() {
+        let mut shortcuts = Shortcuts::new();
+        shortcuts
+            .register(command("comment", "Ctrl+K Ctrl+C"))
+            .unwrap();
+
+        assert_eq!(
+            shortcuts.register(command("comment", "Ctrl+J")),
+            Err(ShortcutError::DuplicateName("comment".to_string()))
+        );
+
+        // A prefix of a sequence can't be told apart from it while typing
+        for keys in ["Ctrl+K", "Ctrl+K Ctrl+C", "Ctrl+K Ctrl+C Ctrl+X"] {
+            assert_eq!(
+                shortcuts.register(command("other", keys)),
+                Err(ShortcutError::Conflict {
+                    name: "other".to_string(),
+                    existing: "comment".to_string()
+                })
+            );
+        }
+
+        shortcuts
+            .register(command("uncomment", "Ctrl+K Ctrl+U"))
+            .unwrap();
+        shortcuts
+            .register(command("kill", "Ctrl+K").in_widget("terminal"))
+            .unwrap();
+
+        shortcuts.unregister("comment").unwrap();
+        shortcuts
+            .register(command("other", "Ctrl+K Ctrl+C"))
+            .unwrap();
+    }
+}
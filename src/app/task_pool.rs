@@ -0,0 +1,125 @@
+use std::{
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    sync::{mpsc, Arc, Mutex},
+    task::{Context, Wake, Waker},
+    thread,
+};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+type BoxFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+// A future that is polled on the workers. It's only queued again when its waker is woken,
+// so a future that waits on io doesn't hold a worker in the meantime.
+struct Task {
+    // None once the future completed
+    future: Mutex<Option<BoxFuture>>,
+    jobs: mpsc::Sender<Job>,
+}
+
+impl Task {
+    fn poll(self: Arc<Self>) {
+        let mut slot = self.future.lock().unwrap();
+        // A wake that arrives while the future is being polled queues another poll,
+        // which waits for this one to put the future back
+        if let Some(mut future) = slot.take() {
+            let waker = Waker::from(self.clone());
+            if future
+                .as_mut()
+                .poll(&mut Context::from_waker(&waker))
+                .is_pending()
+            {
+                *slot = Some(future)
+            }
+        }
+    }
+}
+
+impl Wake for Task {
+    fn wake(self: Arc<Self>) {
+        let jobs = self.jobs.clone();
+        // The pool may be gone when the app is quitting
+        let _ = jobs.send(Box::new(move || self.poll()));
+    }
+}
+
+// A fixed set of worker threads that run jobs in the order they were submitted.
+// The workers stop once the pool is dropped and the queue has run dry.
+pub(crate) struct TaskPool {
+    jobs: mpsc::Sender<Job>,
+}
+
+impl TaskPool {
+    pub(crate) fn new(threads: usize) -> Self {
+        let (jobs, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for index in 0..threads.max(1) {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(format!("rui-worker-{}", index))
+                .spawn(move || loop {
+                    let job = receiver.lock().unwrap().recv();
+                    match job {
+                        // A panicking task shouldn't take the worker down with it
+                        Ok(job) => {
+                            let _ = panic::catch_unwind(AssertUnwindSafe(job));
+                        }
+                        Err(_) => break,
+                    }
+                })
+                .expect("Failed to spawn worker thread");
+        }
+
+        Self { jobs }
+    }
+
+    // One worker per core, minus one for the ui thread
+    pub(crate) fn with_default_threads() -> Self {
+        let threads = thread::available_parallelism()
+            .map(|count| count.get().saturating_sub(1))
+            .unwrap_or(1);
+        Self::new(threads)
+    }
+
+    pub(crate) fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        self.jobs
+            .send(Box::new(job))
+            .expect("All worker threads have stopped")
+    }
+
+    // Polls the future on the workers until it completes
+    pub(crate) fn spawn(&self, future: impl Future<Output = ()> + Send + 'static) {
+        let task = Arc::new(Task {
+            future: Mutex::new(Some(Box::pin(future))),
+            jobs: self.jobs.clone(),
+        });
+        self.execute(move || task.poll())
+    }
+}
+
+// The app's pool, created when the first task is submitted. Clones share the same workers,
+// so everything that runs work in the background competes for the same threads.
+#[derive(Clone, Default)]
+pub(crate) struct SharedTaskPool {
+    pool: Arc<Mutex<Option<TaskPool>>>,
+}
+
+impl SharedTaskPool {
+    pub(crate) fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        self.pool
+            .lock()
+            .unwrap()
+            .get_or_insert_with(TaskPool::with_default_threads)
+            .execute(job)
+    }
+
+    pub(crate) fn spawn(&self, future: impl Future<Output = ()> + Send + 'static) {
+        self.pool
+            .lock()
+            .unwrap()
+            .get_or_insert_with(TaskPool::with_default_threads)
+            .spawn(future)
+    }
+}
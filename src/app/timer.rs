@@ -0,0 +1,137 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+use winit::window::WindowId;
+
+// Handle to a scheduled timer, used to cancel it and to recognize its Event::Timer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TimerId(u64);
+
+impl TimerId {
+    // Ids are handed out globally so widgets can get one without access to the app
+    pub(crate) fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+pub(crate) enum TimerAction<Message> {
+    // Handled by AppState::handle_message
+    Message(Message),
+    // Delivered as Event::Timer to the widgets of a window
+    Window(WindowId),
+}
+
+pub(crate) enum TimerCommand<Message> {
+    Schedule {
+        id: TimerId,
+        delay: Duration,
+        interval: Option<Duration>,
+        action: TimerAction<Message>,
+    },
+    Cancel(TimerId),
+    // Cancels every timer of the window, sent when it closes
+    CancelWindow(WindowId),
+}
+
+struct Timer<Message> {
+    id: TimerId,
+    deadline: Duration,
+    interval: Option<Duration>,
+    action: TimerAction<Message>,
+}
+
+pub(crate) struct Timers<Message> {
+    timers: Vec<Timer<Message>>,
+}
+
+impl<Message: Clone> Timers<Message> {
+    pub(crate) fn new() -> Self {
+        Self { timers: Vec::new() }
+    }
+
+    pub(crate) fn schedule(
+        &mut self,
+        id: TimerId,
+        now: Duration,
+        delay: Duration,
+        interval: Option<Duration>,
+        action: TimerAction<Message>,
+    ) {
+        self.timers.push(Timer {
+            id,
+            deadline: now + delay,
+            interval,
+            action,
+        })
+    }
+
+    pub(crate) fn cancel(&mut self, id: TimerId) {
+        self.timers.retain(|timer| timer.id != id)
+    }
+
+    pub(crate) fn cancel_window(&mut self, window_id: WindowId) {
+        self.timers.retain(|timer| match timer.action {
+            TimerAction::Window(id) => id != window_id,
+            TimerAction::Message(_) => true,
+        })
+    }
+
+    pub(crate) fn apply(&mut self, now: Duration, command: TimerCommand<Message>) {
+        match command {
+            TimerCommand::Schedule {
+                id,
+                delay,
+                interval,
+                action,
+            } => self.schedule(id, now, delay, interval, action),
+            TimerCommand::Cancel(id) => self.cancel(id),
+            TimerCommand::CancelWindow(window_id) => self.cancel_window(window_id),
+        }
+    }
+
+    pub(crate) fn next_deadline(&self) -> Option<Duration> {
+        self.timers.iter().map(|timer| timer.deadline).min()
+    }
+
+    // Removes the timers that are due and returns what they should do, in the order they expired.
+    // Interval timers are rescheduled, skipping the ticks that were missed while the app was busy.
+    pub(crate) fn expire(&mut self, now: Duration) -> Vec<(TimerId, TimerAction<Message>)> {
+        let mut expired: Vec<(Duration, TimerId, TimerAction<Message>)> = Vec::new();
+        let mut index = 0;
+        while index < self.timers.len() {
+            if self.timers[index].deadline > now {
+                index += 1;
+                continue;
+            }
+
+            let timer = &mut self.timers[index];
+            match timer.interval {
+                Some(interval) if !interval.is_zero() => {
+                    let deadline = timer.deadline;
+                    let action = match &timer.action {
+                        TimerAction::Message(message) => TimerAction::Message(message.clone()),
+                        TimerAction::Window(window_id) => TimerAction::Window(*window_id),
+                    };
+                    while timer.deadline <= now {
+                        timer.deadline += interval;
+                    }
+
+                    expired.push((deadline, timer.id, action));
+                    index += 1;
+                }
+                _ => {
+                    let timer = self.timers.remove(index);
+                    expired.push((timer.deadline, timer.id, timer.action));
+                }
+            }
+        }
+
+        expired.sort_by_key(|(deadline, _, _)| *deadline);
+        expired
+            .into_iter()
+            .map(|(_, id, action)| (id, action))
+            .collect()
+    }
+}
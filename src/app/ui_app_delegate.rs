@@ -1,6 +1,6 @@
 use crate::{
-    app::{App, AppDelegate, AppRequest, AppState, WindowRequest},
-    window::{UiWindowDelegate, WindowDelegate, WindowRegistry},
+    app::{App, AppDelegate, AppEvent, AppRequest, AppState, WindowRequest},
+    window::{UiWindowDelegate, WindowDelegate, WindowId, WindowRegistry},
 };
 use winit::event_loop::EventLoopWindowTarget;
 
@@ -44,7 +44,7 @@ impl<State: AppState> AppDelegate<State> for UIAppDelegate<State> {
         app: &mut App<State>,
         _: &State,
         _: &mut WindowRegistry<State>,
-        _: &EventLoopWindowTarget<()>,
+        _: &EventLoopWindowTarget<AppEvent>,
     ) {
         // self.device = Some(device);
         if let Some(request) = self.initial_window_request.take() {
@@ -61,7 +61,7 @@ impl<State: AppState> AppDelegate<State> for UIAppDelegate<State> {
         app: &App<State>,
         state: &State,
         _: &mut WindowRegistry<State>,
-        _: &EventLoopWindowTarget<()>,
+        _: &EventLoopWindowTarget<AppEvent>,
     ) {
         if let Some(cb) = self.on_update.as_mut() {
             cb(app, state)
@@ -73,16 +73,11 @@ impl<State: AppState> AppDelegate<State> for UIAppDelegate<State> {
         app: &App<State>,
         state: &State,
         window_registry: &mut WindowRegistry<State>,
-        target: &EventLoopWindowTarget<()>,
+        target: &EventLoopWindowTarget<AppEvent>,
         request: WindowRequest<State>,
-    ) {
+    ) -> Option<WindowId> {
         let window = window_registry
-            .create_window(
-                target,
-                &request.title.unwrap_or_else(|| "Untitled".to_string()),
-                request.width,
-                request.height,
-            )
+            .create_window(target, &request)
             .expect("Window creation failed");
 
         let mut window_delegate = UiWindowDelegate::new(
@@ -90,6 +85,12 @@ impl<State: AppState> AppDelegate<State> for UIAppDelegate<State> {
             app.gpu_api().queue.clone(),
             request.builder,
         );
+        if let Some(theme) = &request.theme {
+            window_delegate = window_delegate.with_theme(theme);
+        }
+        if let Some(consumer) = request.accessibility {
+            window_delegate = window_delegate.with_accessibility(consumer);
+        }
         window_delegate.resized(
             &window,
             app,
@@ -98,6 +99,15 @@ impl<State: AppState> AppDelegate<State> for UIAppDelegate<State> {
             window.inner_size().width,
             window.inner_size().height,
         );
+        let window_id = window.id();
         window_registry.register_with_delegate(window, Box::new(window_delegate));
+        if let Some(parent) = request.parent {
+            window_registry.set_parent(window_id, parent, request.modal)
+        }
+        if let Some(name) = &request.name {
+            window_registry.set_name(window_id, name)
+        }
+
+        Some(window_id)
     }
 }
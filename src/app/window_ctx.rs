@@ -0,0 +1,131 @@
+use super::{App, AppRequest, AppState};
+use crate::{
+    canvas::Size,
+    widget::{
+        query::{WidgetInfo, WidgetTree},
+        Command, Widget,
+    },
+    window::WindowId,
+};
+use std::{any::Any, rc::Rc};
+
+// Addresses a window by its id or by the name it was opened with, see WindowRequest::with_name
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WindowTarget {
+    Id(WindowId),
+    Name(String),
+}
+
+impl From<WindowId> for WindowTarget {
+    fn from(window_id: WindowId) -> Self {
+        WindowTarget::Id(window_id)
+    }
+}
+
+impl From<&str> for WindowTarget {
+    fn from(name: &str) -> Self {
+        WindowTarget::Name(name.to_string())
+    }
+}
+
+// What the app knows about an open window. It's updated once per frame, so a window that was
+// requested while handling the current message isn't known yet.
+#[derive(Clone, Debug)]
+pub struct WindowInfo {
+    pub id: WindowId,
+    pub name: Option<String>,
+    // In physical pixels, like the layout of the window's widgets
+    pub size: Size,
+    pub scale_factor: f64,
+    // The window's widgets as of the last layout
+    pub tree: Rc<WidgetTree>,
+}
+
+pub enum WindowUpdate<State: AppState> {
+    // Replaces the builder of the window's widget tree and rebuilds it
+    SetRoot(Box<dyn Fn(&State) -> Box<dyn Widget<State>>>),
+    Rebuild,
+    // Lays out the whole tree again, ignoring cached layouts
+    Relayout,
+    Repaint,
+    Command(Command),
+}
+
+pub struct WindowUpdateRequest<State: AppState> {
+    pub(crate) window_id: WindowId,
+    pub(crate) update: WindowUpdate<State>,
+}
+
+impl<State: AppState> WindowUpdateRequest<State> {
+    pub fn new(window_id: WindowId, update: WindowUpdate<State>) -> Self {
+        Self { window_id, update }
+    }
+}
+
+// Access to one window from outside its widget tree. Updates are applied before the next frame,
+// they do nothing when the window isn't open.
+pub struct WindowCtx<'a, State: AppState> {
+    app: &'a mut App<State>,
+    window_id: Option<WindowId>,
+}
+
+impl<'a, State: AppState + 'static> WindowCtx<'a, State> {
+    pub(crate) fn new(app: &'a mut App<State>, target: WindowTarget) -> Self {
+        let window_id = app.resolve_window(&target);
+        Self { app, window_id }
+    }
+
+    pub fn id(&self) -> Option<WindowId> {
+        self.window_id
+    }
+
+    pub fn info(&self) -> Option<&WindowInfo> {
+        self.window_id.and_then(|id| self.app.window_info(id))
+    }
+
+    pub fn size(&self) -> Option<Size> {
+        self.info().map(|info| info.size)
+    }
+
+    pub fn tree(&self) -> Option<&WidgetTree> {
+        self.info().map(|info| info.tree.as_ref())
+    }
+
+    // Where the widget with the key is, for example to position a popup next to it
+    pub fn find(&self, key: &str) -> Option<&WidgetInfo> {
+        self.tree().and_then(|tree| tree.by_key(key))
+    }
+
+    pub fn set_root<F>(&mut self, builder: F)
+    where
+        F: Fn(&State) -> Box<dyn Widget<State>> + 'static,
+    {
+        self.update(WindowUpdate::SetRoot(Box::new(builder)))
+    }
+
+    pub fn rebuild(&mut self) {
+        self.update(WindowUpdate::Rebuild)
+    }
+
+    pub fn relayout(&mut self) {
+        self.update(WindowUpdate::Relayout)
+    }
+
+    pub fn repaint(&mut self) {
+        self.update(WindowUpdate::Repaint)
+    }
+
+    // Sends the payload to the widget with the key, see Event::Command
+    pub fn send_command(&mut self, key: &str, payload: impl Any) {
+        self.update(WindowUpdate::Command(Command::new(key, payload)))
+    }
+
+    fn update(&mut self, update: WindowUpdate<State>) {
+        if let Some(window_id) = self.window_id {
+            self.app
+                .request(AppRequest::UpdateWindow(WindowUpdateRequest::new(
+                    window_id, update,
+                )))
+        }
+    }
+}
@@ -1,8 +1,9 @@
 pub mod skia_cpu_canvas;
 
 pub use skia_safe::{
-    font, textlayout, textlayout::Paragraph, Color, Color4f, Font, FontStyle, Paint, Point, Rect,
-    Size, TextBlob, Typeface,
+    font, svg, textlayout, textlayout::Paragraph, BlurStyle, Color, Color4f, Font, FontStyle,
+    Image, MaskFilter, Paint, Path, Point, RRect, Rect, Shader, Size, TextBlob, TileMode, Typeface,
+    Vector,
 };
 
 pub trait Canvas2D {
@@ -12,12 +13,23 @@ pub trait Canvas2D {
     fn restore(&mut self);
     fn translate(&mut self, point: &Point);
 
+    fn clip_rect(&mut self, rect: &Rect);
+    fn clip_rrect(&mut self, rrect: &RRect);
+    fn clip_path(&mut self, path: &Path);
+
     fn draw_rect(&mut self, rect: &Rect, paint: &Paint);
     fn draw_rounded_rect(&mut self, rect: &Rect, rx: f32, ry: f32, paint: &Paint);
+    fn draw_rrect(&mut self, rrect: &RRect, paint: &Paint);
+    fn draw_drrect(&mut self, outer: &RRect, inner: &RRect, paint: &Paint);
+    fn draw_path(&mut self, path: &Path, paint: &Paint);
 
     fn draw_circle(&mut self, center: &Point, radius: f32, paint: &Paint);
 
+    fn draw_image_rect(&mut self, image: &Image, rect: &Rect, paint: &Paint);
+
     fn draw_string(&mut self, rect: &Rect, text: &str, font: &Font, paint: &Paint);
     fn draw_text_blob(&mut self, pos: &Point, blob: &TextBlob, paint: &Paint);
     fn draw_paragraph(&mut self, pos: &Point, paragraph: &Paragraph);
+    // The document is scaled to the rect, keeping its aspect ratio
+    fn draw_svg(&mut self, svg: &svg::Dom, rect: &Rect);
 }
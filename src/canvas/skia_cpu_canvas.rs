@@ -1,4 +1,7 @@
-use skia_safe::{Color, Font, ISize, Paint, Point, Rect, Surface};
+use skia_safe::{
+    Color, FilterMode, Font, ISize, Image, Paint, Path, Point, RRect, Rect, SamplingOptions,
+    Surface,
+};
 
 use super::Canvas2D;
 
@@ -60,6 +63,19 @@ impl Canvas2D for SkiaCanvas {
     fn translate(&mut self, point: &Point) {
         self.surface.canvas().translate(*point);
     }
+
+    fn clip_rect(&mut self, rect: &Rect) {
+        self.surface.canvas().clip_rect(rect, None, true);
+    }
+
+    fn clip_rrect(&mut self, rrect: &RRect) {
+        self.surface.canvas().clip_rrect(rrect, None, true);
+    }
+
+    fn clip_path(&mut self, path: &Path) {
+        self.surface.canvas().clip_path(path, None, true);
+    }
+
     fn draw_rect(&mut self, rect: &Rect, paint: &Paint) {
         self.surface.canvas().draw_rect(rect, paint);
     }
@@ -68,10 +84,33 @@ impl Canvas2D for SkiaCanvas {
         self.surface.canvas().draw_round_rect(rect, rx, ry, paint);
     }
 
+    fn draw_rrect(&mut self, rrect: &RRect, paint: &Paint) {
+        self.surface.canvas().draw_rrect(rrect, paint);
+    }
+
+    fn draw_drrect(&mut self, outer: &RRect, inner: &RRect, paint: &Paint) {
+        self.surface.canvas().draw_drrect(outer, inner, paint);
+    }
+
+    fn draw_path(&mut self, path: &Path, paint: &Paint) {
+        self.surface.canvas().draw_path(path, paint);
+    }
+
     fn draw_circle(&mut self, center: &Point, radius: f32, paint: &Paint) {
         self.surface.canvas().draw_circle(*center, radius, paint);
     }
 
+    fn draw_image_rect(&mut self, image: &Image, rect: &Rect, paint: &Paint) {
+        // Images are scaled more often than not, nearest neighbour sampling would make them blocky
+        self.surface.canvas().draw_image_rect_with_sampling_options(
+            image,
+            None,
+            rect,
+            SamplingOptions::from(FilterMode::Linear),
+            paint,
+        );
+    }
+
     fn draw_string(&mut self, rect: &Rect, text: &str, font: &Font, paint: &Paint) {
         let blob = skia_safe::TextBlob::from_str(text, font);
         if let Some(b) = blob {
@@ -88,4 +127,16 @@ impl Canvas2D for SkiaCanvas {
     fn draw_paragraph(&mut self, pos: &Point, paragraph: &skia_safe::textlayout::Paragraph) {
         paragraph.paint(self.surface.canvas(), *pos);
     }
+
+    fn draw_svg(&mut self, svg: &skia_safe::svg::Dom, rect: &Rect) {
+        // Shares the document, the container size is set again before every render
+        let mut svg = svg.clone();
+        svg.set_container_size(rect.size());
+
+        let canvas = self.surface.canvas();
+        canvas.save();
+        canvas.translate(Point::new(rect.left, rect.top));
+        svg.render(canvas);
+        canvas.restore();
+    }
 }
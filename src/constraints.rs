@@ -1,4 +1,4 @@
-#[derive(Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct BoxConstraints {
     min_width: Option<f32>,
     min_height: Option<f32>,
@@ -82,6 +82,18 @@ impl BoxConstraints {
         self.max_height
     }
 
+    // Clamps a width to the min and max width, where they are given
+    pub fn constrain_width(&self, width: f32) -> f32 {
+        let width = self.max_width.map_or(width, |max| width.min(max));
+        self.min_width.map_or(width, |min| width.max(min))
+    }
+
+    // Clamps a height to the min and max height, where they are given
+    pub fn constrain_height(&self, height: f32) -> f32 {
+        let height = self.max_height.map_or(height, |max| height.min(max));
+        self.min_height.map_or(height, |min| height.max(min))
+    }
+
     pub fn has_min(&self) -> bool {
         self.min_width.is_some() && self.min_height.is_some()
     }
@@ -0,0 +1,105 @@
+use crate::canvas::{Rect, Size};
+use std::ops::Add;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EdgeInsets {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl EdgeInsets {
+    pub fn new(left: f32, top: f32, right: f32, bottom: f32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::default()
+    }
+
+    pub fn all(value: f32) -> Self {
+        Self::new(value, value, value, value)
+    }
+
+    pub fn symmetric(horizontal: f32, vertical: f32) -> Self {
+        Self::new(horizontal, vertical, horizontal, vertical)
+    }
+
+    pub fn with_left(mut self, left: f32) -> Self {
+        self.left = left;
+        self
+    }
+
+    pub fn with_top(mut self, top: f32) -> Self {
+        self.top = top;
+        self
+    }
+
+    pub fn with_right(mut self, right: f32) -> Self {
+        self.right = right;
+        self
+    }
+
+    pub fn with_bottom(mut self, bottom: f32) -> Self {
+        self.bottom = bottom;
+        self
+    }
+
+    // Total inset in the horizontal direction, left + right
+    pub fn horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+
+    // Total inset in the vertical direction, top + bottom
+    pub fn vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
+
+    pub fn inflate_size(&self, size: &Size) -> Size {
+        Size::new(
+            size.width + self.horizontal(),
+            size.height + self.vertical(),
+        )
+    }
+
+    pub fn deflate_size(&self, size: &Size) -> Size {
+        Size::new(
+            (size.width - self.horizontal()).max(0.0),
+            (size.height - self.vertical()).max(0.0),
+        )
+    }
+
+    pub fn deflate_rect(&self, rect: &Rect) -> Rect {
+        Rect::new(
+            rect.left + self.left,
+            rect.top + self.top,
+            (rect.right - self.right).max(rect.left + self.left),
+            (rect.bottom - self.bottom).max(rect.top + self.top),
+        )
+    }
+}
+
+impl Add for EdgeInsets {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(
+            self.left + rhs.left,
+            self.top + rhs.top,
+            self.right + rhs.right,
+            self.bottom + rhs.bottom,
+        )
+    }
+}
+
+impl From<f32> for EdgeInsets {
+    fn from(value: f32) -> Self {
+        Self::all(value)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::app::AppState;
+
+// Focuses on a part of the application state.
+// Any closure from the state to a reference into it is a lens.
+pub trait Lens<State, T: ?Sized> {
+    fn get<'a>(&self, state: &'a State) -> &'a T;
+}
+
+impl<State, T: ?Sized, F> Lens<State, T> for F
+where
+    F: Fn(&State) -> &T,
+{
+    fn get<'a>(&self, state: &'a State) -> &'a T {
+        self(state)
+    }
+}
+
+// Ties a control to a part of the state. The control shows the value the lens points at
+// and publishes the message returned by on_change when the user changes it.
+pub struct Binding<State: AppState, T: ?Sized> {
+    lens: Box<dyn Lens<State, T>>,
+    on_change: Box<dyn Fn(&T) -> State::Message>,
+}
+
+impl<State: AppState, T: ?Sized> Binding<State, T> {
+    pub fn new<G, F>(get: G, on_change: F) -> Self
+    where
+        G: Fn(&State) -> &T + 'static,
+        F: Fn(&T) -> State::Message + 'static,
+    {
+        Self::from_lens(get, on_change)
+    }
+
+    pub fn from_lens<L, F>(lens: L, on_change: F) -> Self
+    where
+        L: Lens<State, T> + 'static,
+        F: Fn(&T) -> State::Message + 'static,
+    {
+        Self {
+            lens: Box::new(lens),
+            on_change: Box::new(on_change),
+        }
+    }
+
+    pub fn get<'a>(&self, state: &'a State) -> &'a T {
+        self.lens.get(state)
+    }
+
+    pub fn changed(&self, value: &T) -> State::Message {
+        (self.on_change)(value)
+    }
+}
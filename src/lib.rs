@@ -1,9 +1,12 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::too_many_arguments)]
 
+pub mod animation;
 pub mod app;
 pub mod canvas;
 pub mod constraints;
+pub mod edge_insets;
+pub mod lens;
 pub mod user_interface;
 pub mod widget;
 pub mod window;
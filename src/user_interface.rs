@@ -1,15 +1,26 @@
 use crate::{
-    app::{App, AppRequest, AppState, CursorIconRequest},
-    canvas::{Canvas2D, Point, Size},
+    app::{App, AppRequest, AppState, CursorIconRequest, Fonts, Images, TimerId},
+    canvas::{Canvas2D, Point, Rect, Size},
     constraints::BoxConstraints,
     widget::{
-        style::StyleContext, Action, ChildSlot, Event, EventCtx, KeyEvent, LayoutCtx, MouseEvent,
-        PaintCtx, Properties, Widget,
+        accessibility::{AccessActionRequest, AccessTree, AccessUpdate},
+        query::{WidgetInfo, WidgetTree},
+        style::StyleContext,
+        Action, ChildSlot, Command, Event, EventCtx, KeyEvent, LayoutCtx, MouseEvent, PaintCtx,
+        Properties, Widget,
     },
     window,
 };
-use std::path::Path;
-use winit::{event::KeyboardInput, window::WindowId};
+use std::{
+    cell::{Cell, RefCell},
+    path::Path,
+    rc::Rc,
+    time::Duration,
+};
+use winit::{
+    event::{Ime, KeyboardInput},
+    window::WindowId,
+};
 
 pub struct WindowContext {
     _origin: Point,
@@ -23,20 +34,78 @@ pub struct DragContext<State> {
 
 pub struct UserInterface<State: AppState> {
     pub root: ChildSlot<State>,
-    pub style_ctx: StyleContext,
     _actions: Vec<Action<State>>,
     theme: String,
     mouse_move_consumer: Option<usize>,
+    last_mouse_event: Option<window::MouseEvent>,
+    constraints: BoxConstraints,
+    animation_frame_requested: Cell<bool>,
+    // Built on demand after each layout
+    tree: RefCell<Option<Rc<WidgetTree>>>,
+    // The accessibility tree as it was last handed out, updates are relative to it
+    access_tree: Option<AccessTree>,
+    // Uid of the slot that has keyboard focus
+    focused: Option<usize>,
+    fonts: Fonts,
+    images: Images,
+    // How many images had been decoded in the background at the last check
+    images_decoded: usize,
 }
 
 impl<State: AppState + 'static> UserInterface<State> {
     pub fn new(root: Box<dyn Widget<State>>, theme: &str) -> Self {
         UserInterface {
             root: ChildSlot::new_with_box(root),
-            style_ctx: StyleContext::new(),
             _actions: Vec::new(),
             theme: theme.to_string(),
             mouse_move_consumer: None,
+            last_mouse_event: None,
+            constraints: BoxConstraints::new(),
+            animation_frame_requested: Cell::new(false),
+            tree: RefCell::new(None),
+            access_tree: None,
+            focused: None,
+            fonts: Fonts::new(),
+            images: Images::new(),
+            images_decoded: 0,
+        }
+    }
+
+    // Shares the fonts of the app instead of loading the system fonts again
+    pub fn with_fonts(mut self, fonts: Fonts) -> Self {
+        self.fonts = fonts;
+        self
+    }
+
+    pub fn fonts(&self) -> &Fonts {
+        &self.fonts
+    }
+
+    // Shares the image cache of the app
+    pub fn with_images(mut self, images: Images) -> Self {
+        self.images_decoded = images.decoded_count();
+        self.images = images;
+        self
+    }
+
+    // Images that were loading have their size now. Only the widgets that showed one of them, and their
+    // ancestors, are laid out again.
+    pub fn update_images(&mut self, state: &State) {
+        let decoded = self.images.decoded_count();
+        if decoded != self.images_decoded {
+            self.images_decoded = decoded;
+            if self.root.invalidate_loaded_images(&self.images) {
+                self.layout_if_needed(state)
+            }
+        }
+    }
+
+    // Picks up the font families of the theme. The text is laid out again when they changed.
+    pub fn update_fonts(&mut self, style_ctx: &StyleContext, state: &State) {
+        let font = &style_ctx.theme_or_default(&self.theme).font;
+        if self.fonts.style() != font {
+            self.fonts = self.fonts.with_style(font);
+            self.relayout(state)
         }
     }
 
@@ -48,8 +117,6 @@ impl<State: AppState + 'static> UserInterface<State> {
         self.layout(&constraints, state);
     }
 
-    pub fn resized(&self, _: &State, _: WindowId) {}
-
     pub fn mouse_down(
         &mut self,
         app: &mut App<State>,
@@ -61,12 +128,17 @@ impl<State: AppState + 'static> UserInterface<State> {
             size: *self.root.size(),
             ..Properties::default()
         };
-        let mut ctx = EventCtx::new(&properties, window_id, app.message_tx.clone());
+        let mut ctx = EventCtx::new(&properties, window_id, app);
         self.root.event(
             &Event::Mouse(MouseEvent::MouseDown(*event)),
             &mut ctx,
             state,
         );
+
+        // Clicking anything that doesn't take focus clears it
+        let focus = ctx.focus_change().flatten();
+        self.event_handled(&ctx, state);
+        self.set_focus(app, state, window_id, focus)
     }
 
     pub fn mouse_up(
@@ -80,9 +152,11 @@ impl<State: AppState + 'static> UserInterface<State> {
             size: *self.root.size(),
             ..Properties::default()
         };
-        let mut ctx = EventCtx::new(&properties, window_id, app.message_tx.clone());
+        let mut ctx = EventCtx::new(&properties, window_id, app);
         self.root
             .event(&Event::Mouse(MouseEvent::MouseUp(*event)), &mut ctx, state);
+
+        self.event_handled(&ctx, state)
     }
 
     pub fn double_click(&self, _: &State, _: &MouseEvent) {}
@@ -98,12 +172,14 @@ impl<State: AppState + 'static> UserInterface<State> {
             size: *self.root.size(),
             ..Properties::default()
         };
-        let mut ctx = EventCtx::new(&properties, window_id, app.message_tx.clone());
+        let mut ctx = EventCtx::new(&properties, window_id, app);
         self.root.event(
             &Event::Mouse(MouseEvent::MouseDrag(*event)),
             &mut ctx,
             state,
         );
+
+        self.event_handled(&ctx, state)
     }
 
     pub fn mouse_moved(
@@ -117,53 +193,72 @@ impl<State: AppState + 'static> UserInterface<State> {
             size: *self.root.size(),
             ..Properties::default()
         };
-        let mut ctx = EventCtx::new(&properties, window_id, app.message_tx.clone());
+        let mut ctx = EventCtx::new(&properties, window_id, app);
         self.root.event(
             &Event::Mouse(MouseEvent::MouseMove(*event)),
             &mut ctx,
             state,
         );
+        let mut layout_requested = ctx.layout_requested();
+        let mut animation_frame_requested = ctx.animation_frame_requested();
 
         if let Some(consumer) = ctx.consumer() {
             if self.mouse_move_consumer.is_none() {
-                let mut ctx = EventCtx::new(&properties, window_id, app.message_tx.clone());
+                let mut ctx = EventCtx::new(&properties, window_id, app);
                 ctx.set_target(consumer);
                 self.root.event(
                     &Event::Mouse(MouseEvent::MouseEnter(*event)),
                     &mut ctx,
                     state,
                 );
+                layout_requested |= ctx.layout_requested();
+                animation_frame_requested |= ctx.animation_frame_requested();
             } else {
                 let previous_consumer = self.mouse_move_consumer.unwrap();
                 if consumer != previous_consumer {
-                    let mut ctx = EventCtx::new(&properties, window_id, app.message_tx.clone());
+                    let mut ctx = EventCtx::new(&properties, window_id, app);
                     ctx.set_target(consumer);
                     self.root.event(
                         &Event::Mouse(MouseEvent::MouseEnter(*event)),
                         &mut ctx,
                         state,
                     );
+                    layout_requested |= ctx.layout_requested();
+                    animation_frame_requested |= ctx.animation_frame_requested();
 
-                    let mut ctx = EventCtx::new(&properties, window_id, app.message_tx.clone());
+                    let mut ctx = EventCtx::new(&properties, window_id, app);
                     ctx.set_target(previous_consumer);
                     self.root.event(
                         &Event::Mouse(MouseEvent::MouseLeave(*event)),
                         &mut ctx,
                         state,
                     );
+                    layout_requested |= ctx.layout_requested();
+                    animation_frame_requested |= ctx.animation_frame_requested();
                 }
             }
         } else if let Some(previous_consumer) = self.mouse_move_consumer {
-            let mut ctx = EventCtx::new(&properties, window_id, app.message_tx.clone());
+            let mut ctx = EventCtx::new(&properties, window_id, app);
             ctx.set_target(previous_consumer);
             self.root.event(
                 &Event::Mouse(MouseEvent::MouseLeave(*event)),
                 &mut ctx,
                 state,
             );
+            layout_requested |= ctx.layout_requested();
+            animation_frame_requested |= ctx.animation_frame_requested();
         }
 
         self.mouse_move_consumer = ctx.consumer();
+        self.last_mouse_event = Some(*event);
+
+        if animation_frame_requested {
+            self.animation_frame_requested.set(true)
+        }
+
+        if layout_requested {
+            self.layout_if_needed(state)
+        }
 
         app.request(AppRequest::ChangeCursorRequest(CursorIconRequest::new(
             window_id,
@@ -171,22 +266,73 @@ impl<State: AppState + 'static> UserInterface<State> {
         )))
     }
 
-    pub fn mouse_leave(&self, _: &State, _: &window::MouseEvent) {}
+    // The widget under the cursor is told the mouse left before the window gets WindowEvent::CursorLeft
+    pub fn mouse_leave(&mut self, app: &App<State>, state: &State, window_id: WindowId) {
+        let properties = Properties {
+            size: *self.root.size(),
+            ..Properties::default()
+        };
+        if let (Some(consumer), Some(event)) = (
+            self.mouse_move_consumer.take(),
+            self.last_mouse_event.take(),
+        ) {
+            let mut ctx = EventCtx::new(&properties, window_id, app);
+            ctx.set_target(consumer);
+            self.root.event(
+                &Event::Mouse(MouseEvent::MouseLeave(event)),
+                &mut ctx,
+                state,
+            );
+            self.event_handled(&ctx, state)
+        }
+    }
 
-    pub fn keyboard_event(
+    pub fn window_event(
+        &mut self,
+        app: &App<State>,
+        state: &State,
+        window_id: WindowId,
+        event: &window::WindowEvent,
+    ) {
+        if let window::WindowEvent::CursorLeft = event {
+            self.mouse_leave(app, state, window_id)
+        }
+
+        let properties = Properties {
+            size: *self.root.size(),
+            ..Properties::default()
+        };
+        let mut ctx = EventCtx::new(&properties, window_id, app);
+        self.root.event(&Event::Window(*event), &mut ctx, state);
+
+        self.event_handled(&ctx, state)
+    }
+
+    pub fn timer_fired(
         &mut self,
         app: &mut App<State>,
         state: &State,
         window_id: WindowId,
-        event: &KeyboardInput,
+        timer: TimerId,
     ) {
         let properties = Properties {
             size: *self.root.size(),
             ..Properties::default()
         };
-        let mut ctx = EventCtx::new(&properties, window_id, app.message_tx.clone());
-        self.root
-            .event(&Event::Key(KeyEvent::Input(*event)), &mut ctx, state);
+        let mut ctx = EventCtx::new(&properties, window_id, app);
+        self.root.event(&Event::Timer(timer), &mut ctx, state);
+
+        self.event_handled(&ctx, state)
+    }
+
+    pub fn keyboard_event(
+        &mut self,
+        app: &mut App<State>,
+        state: &State,
+        window_id: WindowId,
+        event: &KeyboardInput,
+    ) {
+        self.key_event(app, state, window_id, KeyEvent::Input(*event))
     }
 
     pub fn character_received(
@@ -195,35 +341,280 @@ impl<State: AppState + 'static> UserInterface<State> {
         state: &State,
         window_id: WindowId,
         character: char,
+    ) {
+        self.key_event(app, state, window_id, KeyEvent::Char(character))
+    }
+
+    pub fn ime_event(
+        &mut self,
+        app: &mut App<State>,
+        state: &State,
+        window_id: WindowId,
+        event: &Ime,
+    ) {
+        let event = match event {
+            Ime::Preedit(text, cursor) => KeyEvent::Preedit {
+                text: text.clone(),
+                cursor: *cursor,
+            },
+            Ime::Commit(text) => KeyEvent::Commit(text.clone()),
+            // Whatever was being composed is gone
+            Ime::Disabled => KeyEvent::Preedit {
+                text: String::new(),
+                cursor: None,
+            },
+            Ime::Enabled => return,
+        };
+
+        self.key_event(app, state, window_id, event)
+    }
+
+    // Key events go to the focused widget. Containers see them on the way, even when nothing has focus,
+    // so things like a menu bar can react to keys.
+    fn key_event(&mut self, app: &App<State>, state: &State, window_id: WindowId, event: KeyEvent) {
+        self.send_to(app, state, window_id, self.focused, &Event::Key(event))
+    }
+
+    fn send_to(
+        &mut self,
+        app: &App<State>,
+        state: &State,
+        window_id: WindowId,
+        target: Option<usize>,
+        event: &Event,
     ) {
         let properties = Properties {
             size: *self.root.size(),
             ..Properties::default()
         };
-        let mut ctx = EventCtx::new(&properties, window_id, app.message_tx.clone());
+        let mut ctx = EventCtx::new(&properties, window_id, app);
+        if let Some(target) = target {
+            ctx.set_target(target)
+        }
+        self.root.event(event, &mut ctx, state);
+
+        let focus = ctx.focus_change();
+        self.event_handled(&ctx, state);
+        if let Some(focus) = focus {
+            self.set_focus(app, state, window_id, focus)
+        }
+    }
+
+    // Moves keyboard focus to the slot with the uid, telling the widgets that lose and gain it
+    pub fn set_focus(
+        &mut self,
+        app: &App<State>,
+        state: &State,
+        window_id: WindowId,
+        focus: Option<usize>,
+    ) {
+        if focus == self.focused {
+            return;
+        }
+
+        if let Some(previous) = std::mem::replace(&mut self.focused, focus) {
+            self.send_to(app, state, window_id, Some(previous), &Event::Focus(false))
+        }
+
+        if let Some(focused) = focus {
+            self.send_to(app, state, window_id, Some(focused), &Event::Focus(true))
+        }
+    }
+
+    // Keys of the focused widget and its ancestors, innermost first
+    pub fn focus_keys(&self) -> Vec<String> {
+        let tree = self.tree();
+        match self.focused.and_then(|uid| tree.by_uid(uid)) {
+            Some(focused) => std::iter::once(focused)
+                .chain(tree.ancestors(focused))
+                .filter_map(|info| info.key.clone())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    // Where text typed into the focused widget ends up, relative to the window
+    pub fn caret_rect(&self, state: &State) -> Option<Rect> {
+        let focused = self.focused?;
+        let (slot, position) = find_slot(&self.root, focused, Point::new(0.0, 0.0))?;
+        slot.caret_rect(slot.size(), state)
+            .map(|rect| rect.with_offset(position))
+    }
+
+    // Delivers the command to the widget with its key
+    pub fn command(
+        &mut self,
+        app: &App<State>,
+        state: &State,
+        window_id: WindowId,
+        command: &Command,
+    ) {
+        let properties = Properties {
+            size: *self.root.size(),
+            ..Properties::default()
+        };
+        let mut ctx = EventCtx::new(&properties, window_id, app);
         self.root
-            .event(&Event::Key(KeyEvent::Char(character)), &mut ctx, state);
+            .event(&Event::Command(command.clone()), &mut ctx, state);
+
+        self.event_handled(&ctx, state)
+    }
+
+    // Performs an action assistive technology asked for on the widget it targets
+    pub fn access_action(
+        &mut self,
+        app: &App<State>,
+        state: &State,
+        window_id: WindowId,
+        request: &AccessActionRequest,
+    ) {
+        // The targeted slot asks for focus when the action is AccessAction::Focus
+        self.send_to(app, state, window_id, None, &Event::Access(request.clone()))
+    }
+
+    pub fn focused(&self) -> Option<usize> {
+        self.focused
+    }
+
+    pub fn accessibility_tree(&self, state: &State) -> AccessTree {
+        AccessTree::new(&self.root, state).with_focus(self.focused)
+    }
+
+    // What changed in the accessibility tree since the last call, None if nothing did
+    pub fn accessibility_update(&mut self, state: &State) -> Option<AccessUpdate> {
+        let tree = self.accessibility_tree(state);
+        let update = tree.update_from(self.access_tree.as_ref());
+        self.access_tree = Some(tree);
+        update
+    }
+
+    // Applies what the widgets asked for while handling an event
+    fn event_handled(&mut self, ctx: &EventCtx<State::Message>, state: &State) {
+        if ctx.animation_frame_requested() {
+            self.animation_frame_requested.set(true)
+        }
+
+        if ctx.layout_requested() {
+            self.layout_if_needed(state)
+        }
+    }
+
+    // Whether a widget asked for another frame, during the last paint or an event after it
+    pub fn is_animating(&self) -> bool {
+        self.animation_frame_requested.get()
     }
 
     pub fn layout(&mut self, constraints: &BoxConstraints, state: &State) {
-        let mut ctx = LayoutCtx::new();
+        self.constraints = *constraints;
+        let mut ctx = LayoutCtx::new(&self.fonts, &self.images);
         let size = self.root.layout(constraints, &mut ctx, state);
         self.root.set_size(&size);
+        self.tree.replace(None);
+    }
+
+    // The widget tree as of the last layout
+    pub fn tree(&self) -> Rc<WidgetTree> {
+        self.tree
+            .borrow_mut()
+            .get_or_insert_with(|| Rc::new(WidgetTree::new(&self.root)))
+            .clone()
+    }
+
+    pub fn find(&self, key: &str) -> Option<WidgetInfo> {
+        self.tree().by_key(key).cloned()
+    }
+
+    // Lays out the tree again with the last constraints, only visiting subtrees that were invalidated.
+    pub fn layout_if_needed(&mut self, state: &State) {
+        if self.root.needs_layout() {
+            let constraints = self.constraints;
+            self.layout(&constraints, state);
+        }
+    }
+
+    pub fn mark_needs_layout(&mut self) {
+        self.root.mark_needs_layout()
+    }
+
+    // Lays out the whole tree again, without reusing any cached layout
+    pub fn relayout(&mut self, state: &State) {
+        self.root.invalidate_layout();
+        let constraints = self.constraints;
+        self.layout(&constraints, state);
+    }
+
+    // Called after the application state was changed by a message.
+    pub fn state_changed(&mut self, state: &State) {
+        self.root.invalidate_state_dependents();
+        self.layout_if_needed(state);
+    }
+
+    // Replaces the widget tree with a freshly built one. Widgets that match the old tree by key and type
+    // take over its local state, so things like text box contents and hover state survive the rebuild.
+    // Slots whose widgets lay out like the old ones keep their cached layout, only the others and the
+    // ones depending on the state are laid out again.
+    pub fn rebuild(&mut self, root: Box<dyn Widget<State>>, state: &State) {
+        let mut root = ChildSlot::new_with_box(root);
+        if root.can_reconcile(&self.root) {
+            root.reconcile(&mut self.root);
+        }
+
+        self.root = root;
+        self.tree.replace(None);
+        self.state_changed(state);
     }
 
-    pub fn paint(&self, state: &State, canvas: &mut dyn Canvas2D) {
-        canvas.clear(&self.style_ctx.theme(&self.theme).unwrap().background);
+    pub fn theme(&self) -> &str {
+        &self.theme
+    }
+
+    pub fn set_theme(&mut self, theme: &str) {
+        self.theme = theme.to_string()
+    }
+
+    pub fn paint(
+        &self,
+        style_ctx: &StyleContext,
+        now: Duration,
+        state: &State,
+        canvas: &mut dyn Canvas2D,
+    ) {
+        // Widgets that are still animating ask again while painting
+        self.animation_frame_requested.set(false);
+        let theme = style_ctx.theme_or_default(&self.theme);
+        canvas.clear(&theme.background);
 
         let properties = Properties {
             size: *self.root.size(),
             ..Properties::default()
         };
 
-        self.root.paint(
-            self.style_ctx.theme(&self.theme).unwrap(),
-            &PaintCtx::new(&properties),
-            canvas,
-            state,
-        );
+        let ctx = PaintCtx::new(
+            &properties,
+            &self.fonts,
+            &self.images,
+            now,
+            &self.animation_frame_requested,
+        )
+        .with_classes(style_ctx.classes())
+        .with_icons(style_ctx.icons());
+        self.root.paint(theme, &ctx, canvas, state);
+        self.root.paint_overlay(theme, &ctx, canvas, state);
     }
 }
+
+// The slot with the uid and its position in the window
+fn find_slot<State: AppState>(
+    slot: &ChildSlot<State>,
+    uid: usize,
+    origin: Point,
+) -> Option<(&ChildSlot<State>, Point)> {
+    let position = Point::new(origin.x + slot.position().x, origin.y + slot.position().y);
+    if slot.uid() == uid {
+        return Some((slot, position));
+    }
+
+    slot.children()
+        .into_iter()
+        .find_map(|child| find_slot(child, uid, position))
+}
@@ -0,0 +1,522 @@
+use crate::{
+    app::AppState,
+    canvas::{Point, Rect},
+    widget::{ChildSlot, Widget},
+};
+use std::collections::HashMap;
+
+// The id of the node for the window itself, widgets use the uid of their slot
+pub const WINDOW_NODE: usize = usize::MAX;
+
+// What a widget is to assistive technology
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    Window,
+    Group,
+    Button,
+    Switch,
+    Checkbox,
+    RadioGroup,
+    Slider,
+    TextInput,
+    List,
+    Label,
+    Image,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessAction {
+    Press,
+    Focus,
+    // Comes with ActionData
+    SetValue,
+    Increment,
+    Decrement,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AccessValue {
+    Text(String),
+    Number { value: f32, min: f32, max: f32 },
+    Toggled(bool),
+    // A checkbox that is neither checked nor unchecked
+    Mixed,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ActionData {
+    Value(String),
+    NumericValue(f32),
+}
+
+// An action assistive technology wants performed on a node, see AccessibilityConsumer::take_actions
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessActionRequest {
+    pub target: usize,
+    pub action: AccessAction,
+    pub data: Option<ActionData>,
+}
+
+impl AccessActionRequest {
+    pub fn new(target: usize, action: AccessAction) -> Self {
+        Self {
+            target,
+            action,
+            data: None,
+        }
+    }
+
+    pub fn with_data(mut self, data: ActionData) -> Self {
+        self.data = Some(data);
+        self
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessNode {
+    pub role: Role,
+    pub name: Option<String>,
+    pub value: Option<AccessValue>,
+    pub actions: Vec<AccessAction>,
+    // Relative to the window, filled in when the tree is built
+    pub bounds: Rect,
+    pub children: Vec<usize>,
+}
+
+impl AccessNode {
+    pub fn new(role: Role) -> Self {
+        Self {
+            role,
+            name: None,
+            value: None,
+            actions: Vec::new(),
+            bounds: Rect::default(),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    pub fn with_value(mut self, value: AccessValue) -> Self {
+        self.value = Some(value);
+        self
+    }
+
+    pub fn with_action(mut self, action: AccessAction) -> Self {
+        self.actions.push(action);
+        self
+    }
+
+    pub fn supports(&self, action: AccessAction) -> bool {
+        self.actions.contains(&action)
+    }
+}
+
+// The changes between two trees. The first update of a window contains every node.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessUpdate {
+    // Nodes that were added or changed, sorted by id
+    pub nodes: Vec<(usize, AccessNode)>,
+    pub removed: Vec<usize>,
+    pub focus: Option<usize>,
+}
+
+// What a window looks like to assistive technology. Widgets without a node don't show up,
+// their children are attached to the closest ancestor that has one.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessTree {
+    nodes: HashMap<usize, AccessNode>,
+    focus: Option<usize>,
+}
+
+impl AccessTree {
+    pub fn new<State: AppState>(root: &ChildSlot<State>, state: &State) -> Self {
+        let mut tree = Self::default();
+        let size = root.size();
+        tree.nodes.insert(
+            WINDOW_NODE,
+            AccessNode {
+                bounds: Rect::from_wh(size.width, size.height),
+                ..AccessNode::new(Role::Window)
+            },
+        );
+        tree.push(root, Point::new(0.0, 0.0), WINDOW_NODE, state);
+        tree
+    }
+
+    fn push<State: AppState>(
+        &mut self,
+        slot: &ChildSlot<State>,
+        origin: Point,
+        parent: usize,
+        state: &State,
+    ) {
+        let position = Point::new(origin.x + slot.position().x, origin.y + slot.position().y);
+        let parent = match slot.accessibility(state) {
+            Some(mut node) => {
+                let size = slot.size();
+                node.bounds = Rect::from_xywh(position.x, position.y, size.width, size.height);
+                self.nodes.insert(slot.uid(), node);
+                if let Some(parent) = self.nodes.get_mut(&parent) {
+                    parent.children.push(slot.uid())
+                }
+                slot.uid()
+            }
+            None => parent,
+        };
+
+        for child in slot.children() {
+            self.push(child, position, parent, state)
+        }
+    }
+
+    // Focus is only kept when the node still exists
+    pub fn with_focus(mut self, focus: Option<usize>) -> Self {
+        self.focus = focus.filter(|id| self.nodes.contains_key(id));
+        self
+    }
+
+    pub fn root(&self) -> Option<&AccessNode> {
+        self.nodes.get(&WINDOW_NODE)
+    }
+
+    pub fn node(&self, id: usize) -> Option<&AccessNode> {
+        self.nodes.get(&id)
+    }
+
+    pub fn focus(&self) -> Option<usize> {
+        self.focus
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &AccessNode)> {
+        self.nodes.iter().map(|(id, node)| (*id, node))
+    }
+
+    pub fn find(&self, predicate: impl Fn(&AccessNode) -> bool) -> Option<(usize, &AccessNode)> {
+        self.iter().find(|(_, node)| predicate(node))
+    }
+
+    pub fn by_name(&self, name: &str) -> Option<(usize, &AccessNode)> {
+        self.find(|node| node.name.as_deref() == Some(name))
+    }
+
+    // What changed since the previous tree, None if nothing did
+    pub fn update_from(&self, previous: Option<&AccessTree>) -> Option<AccessUpdate> {
+        let mut nodes: Vec<(usize, AccessNode)> = self
+            .iter()
+            .filter(|(id, node)| previous.and_then(|previous| previous.node(*id)) != Some(*node))
+            .map(|(id, node)| (id, node.clone()))
+            .collect();
+        nodes.sort_by_key(|(id, _)| *id);
+
+        let mut removed: Vec<usize> = previous
+            .map(|previous| {
+                previous
+                    .nodes
+                    .keys()
+                    .filter(|id| !self.nodes.contains_key(id))
+                    .copied()
+                    .collect()
+            })
+            .unwrap_or_default();
+        removed.sort_unstable();
+
+        let focus_changed = previous.map_or(true, |previous| previous.focus != self.focus);
+        if nodes.is_empty() && removed.is_empty() && !focus_changed {
+            return None;
+        }
+
+        Some(AccessUpdate {
+            nodes,
+            removed,
+            focus: self.focus,
+        })
+    }
+
+    // Brings a copy of a tree up to date, this is what a consumer does with the updates it gets
+    pub fn apply(&mut self, update: &AccessUpdate) {
+        for id in &update.removed {
+            self.nodes.remove(id);
+        }
+
+        for (id, node) in &update.nodes {
+            self.nodes.insert(*id, node.clone());
+        }
+
+        self.focus = update.focus;
+    }
+}
+
+// Receives the accessibility tree of a window and the actions assistive technology asks for.
+// rui builds the tree and performs the actions but doesn't ship a platform adapter, screen readers only
+// see a window once the app hands it one, for example built on AccessKit, with WindowRequest::with_accessibility.
+pub trait AccessibilityConsumer {
+    fn update(&mut self, update: &AccessUpdate);
+
+    // Actions that came in since the last frame, they are performed before the frame is painted
+    fn take_actions(&mut self) -> Vec<AccessActionRequest> {
+        Vec::new()
+    }
+}
+
+// Consumers that don't need a platform, for tests and for checking what a window exposes
+pub mod testing;
+
+#[cfg(test)]
+mod tests {
+    use super::{testing::MemoryAccessibility, *};
+    use crate::{
+        app::MessageCtx,
+        canvas::Size,
+        constraints::BoxConstraints,
+        user_interface::UserInterface,
+        widget::{
+            flex::Flex, sized_box::SizedBox, slider::Slider, switch::Switch, Event, EventCtx,
+            Properties, WidgetExt,
+        },
+    };
+    use std::sync::mpsc;
+
+    #[derive(Default)]
+    struct State {
+        muted: bool,
+        volume: f32,
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Message {
+        SetMuted(bool),
+        SetVolume(f32),
+    }
+
+    impl State {
+        fn apply(&mut self, message: Message) {
+            match message {
+                Message::SetMuted(muted) => self.muted = muted,
+                Message::SetVolume(volume) => self.volume = volume,
+            }
+        }
+    }
+
+    impl AppState for State {
+        type Message = Message;
+
+        fn handle_message(&mut self, message: Self::Message, _: &mut MessageCtx<Self>) {
+            self.apply(message)
+        }
+    }
+
+    fn switch() -> SizedBox<State> {
+        SizedBox::new(
+            Size::new(60.0, 30.0),
+            Switch::new().bind(|state| &state.muted, |muted| Message::SetMuted(*muted)),
+        )
+    }
+
+    fn slider() -> SizedBox<State> {
+        SizedBox::new(
+            Size::new(200.0, 30.0),
+            Slider::new(0.0, 100.0, 0.0, false)
+                .bind(|state| &state.volume, |volume| Message::SetVolume(*volume)),
+        )
+    }
+
+    fn user_interface(root: Flex<State>, state: &State) -> UserInterface<State> {
+        let mut ui = UserInterface::new(Box::new(root), "light");
+        ui.layout(&BoxConstraints::new_with_max(400.0, 300.0), state);
+        ui
+    }
+
+    // A switch above a slider
+    fn mixer(state: &State) -> UserInterface<State> {
+        user_interface(
+            Flex::column()
+                .push(switch().with_key("mute"))
+                .push(slider().with_key("volume")),
+            state,
+        )
+    }
+
+    fn node_with_role(tree: &AccessTree, role: Role) -> usize {
+        tree.find(|node| node.role == role).unwrap().0
+    }
+
+    // Sends the request straight to the widgets, returns what they published and where focus should go
+    fn perform(
+        ui: &mut UserInterface<State>,
+        state: &State,
+        request: AccessActionRequest,
+    ) -> (Vec<Message>, Option<Option<usize>>) {
+        let (message_tx, messages) = mpsc::channel();
+        let properties = Properties {
+            size: *ui.root.size(),
+            ..Properties::default()
+        };
+        let mut ctx = EventCtx::headless(&properties, message_tx);
+        ui.root.event(&Event::Access(request), &mut ctx, state);
+        let focus = ctx.focus_change();
+        drop(ctx);
+        (messages.try_iter().collect(), focus)
+    }
+
+    #[test]
+    fn widgets_without_a_node_are_skipped() {
+        let state = State::default();
+        let tree = mixer(&state).accessibility_tree(&state);
+        assert_eq!(tree.len(), 4);
+
+        // The sized boxes have no node, the controls hang off the column
+        let window = tree.root().unwrap();
+        assert_eq!(window.role, Role::Window);
+        assert_eq!(window.children.len(), 1);
+        let group = tree.node(window.children[0]).unwrap();
+        assert_eq!(group.role, Role::Group);
+
+        let switch = node_with_role(&tree, Role::Switch);
+        let slider = node_with_role(&tree, Role::Slider);
+        assert_eq!(group.children, vec![switch, slider]);
+
+        let switch = tree.node(switch).unwrap();
+        assert_eq!(switch.bounds, Rect::from_xywh(0.0, 0.0, 60.0, 30.0));
+        assert_eq!(switch.value, Some(AccessValue::Toggled(false)));
+        assert!(switch.supports(AccessAction::Press));
+
+        let slider = tree.node(slider).unwrap();
+        assert_eq!(slider.bounds, Rect::from_xywh(0.0, 30.0, 200.0, 30.0));
+        assert_eq!(
+            slider.value,
+            Some(AccessValue::Number {
+                value: 0.0,
+                min: 0.0,
+                max: 100.0
+            })
+        );
+    }
+
+    #[test]
+    fn updates_only_contain_what_changed() {
+        let mut state = State::default();
+        let mut ui = mixer(&state);
+        let mut reader = MemoryAccessibility::new();
+
+        let first = ui.accessibility_update(&state).unwrap();
+        assert_eq!(first.nodes.len(), 4);
+        assert!(first.removed.is_empty());
+        reader.update(&first);
+        assert!(ui.accessibility_update(&state).is_none());
+
+        state.volume = 40.0;
+        ui.state_changed(&state);
+        let second = ui.accessibility_update(&state).unwrap();
+        let slider = node_with_role(&ui.accessibility_tree(&state), Role::Slider);
+        assert_eq!(second.nodes.len(), 1);
+        assert_eq!(second.nodes[0].0, slider);
+        reader.update(&second);
+
+        assert_eq!(reader.update_count(), 2);
+        assert_eq!(reader.tree(), ui.accessibility_tree(&state));
+
+        ui.rebuild(
+            Box::new(Flex::column().push(switch().with_key("mute"))),
+            &state,
+        );
+        let third = ui.accessibility_update(&state).unwrap();
+        assert!(third.removed.contains(&slider));
+        reader.update(&third);
+        assert!(reader.tree().node(slider).is_none());
+        assert_eq!(reader.tree(), ui.accessibility_tree(&state));
+    }
+
+    #[test]
+    fn focus_changes_are_sent_without_node_changes() {
+        let state = State::default();
+        let ui = mixer(&state);
+        let tree = ui.accessibility_tree(&state);
+        let slider = node_with_role(&tree, Role::Slider);
+
+        let focused = tree.clone().with_focus(Some(slider));
+        let update = focused.update_from(Some(&tree)).unwrap();
+        assert!(update.nodes.is_empty());
+        assert_eq!(update.focus, Some(slider));
+
+        // Focus on a node that doesn't exist is dropped
+        assert_eq!(tree.with_focus(Some(usize::MAX - 1)).focus(), None);
+    }
+
+    #[test]
+    fn press_toggles_the_switch() {
+        let mut state = State::default();
+        let mut ui = mixer(&state);
+        let switch = node_with_role(&ui.accessibility_tree(&state), Role::Switch);
+
+        let (messages, focus) = perform(
+            &mut ui,
+            &state,
+            AccessActionRequest::new(switch, AccessAction::Press),
+        );
+        assert_eq!(messages, vec![Message::SetMuted(true)]);
+        assert_eq!(focus, None);
+
+        state.apply(Message::SetMuted(true));
+        ui.state_changed(&state);
+        let tree = ui.accessibility_tree(&state);
+        assert_eq!(
+            tree.node(switch).unwrap().value,
+            Some(AccessValue::Toggled(true))
+        );
+    }
+
+    #[test]
+    fn set_value_and_steps_move_the_slider() {
+        let mut state = State::default();
+        let mut ui = mixer(&state);
+        let slider = node_with_role(&ui.accessibility_tree(&state), Role::Slider);
+
+        let request = AccessActionRequest::new(slider, AccessAction::SetValue)
+            .with_data(ActionData::NumericValue(75.0));
+        let (messages, _) = perform(&mut ui, &state, request);
+        assert_eq!(messages, vec![Message::SetVolume(75.0)]);
+        state.apply(Message::SetVolume(75.0));
+
+        // A tenth of the range
+        let request = AccessActionRequest::new(slider, AccessAction::Increment);
+        let (messages, _) = perform(&mut ui, &state, request);
+        assert_eq!(messages, vec![Message::SetVolume(85.0)]);
+
+        // Only the target performs the action
+        let request = AccessActionRequest::new(slider, AccessAction::Press);
+        assert!(perform(&mut ui, &state, request).0.is_empty());
+    }
+
+    #[test]
+    fn focus_moves_to_the_target() {
+        let state = State::default();
+        let mut ui = mixer(&state);
+        let slider = node_with_role(&ui.accessibility_tree(&state), Role::Slider);
+
+        let request = AccessActionRequest::new(slider, AccessAction::Focus);
+        let (messages, focus) = perform(&mut ui, &state, request);
+        assert!(messages.is_empty());
+        assert_eq!(focus, Some(Some(slider)));
+    }
+
+    #[test]
+    fn memory_accessibility_hands_out_actions_once() {
+        let mut reader = MemoryAccessibility::new();
+        let request = AccessActionRequest::new(1, AccessAction::Press);
+        reader.clone().perform(request.clone());
+        assert_eq!(reader.take_actions(), vec![request]);
+        assert!(reader.take_actions().is_empty());
+    }
+}
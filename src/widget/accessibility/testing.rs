@@ -0,0 +1,48 @@
+use super::{AccessActionRequest, AccessTree, AccessUpdate, AccessibilityConsumer};
+use std::{cell::RefCell, rc::Rc};
+
+#[derive(Default)]
+struct MemoryState {
+    tree: AccessTree,
+    updates: usize,
+    actions: Vec<AccessActionRequest>,
+}
+
+// Keeps the tree in memory instead of handing it to the platform, so what assistive technology
+// would see can be checked without a screen reader. Clones share the same tree.
+#[derive(Clone, Default)]
+pub struct MemoryAccessibility {
+    state: Rc<RefCell<MemoryState>>,
+}
+
+impl MemoryAccessibility {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn tree(&self) -> AccessTree {
+        self.state.borrow().tree.clone()
+    }
+
+    // How many updates were received, an unchanged window doesn't send any
+    pub fn update_count(&self) -> usize {
+        self.state.borrow().updates
+    }
+
+    // Performed on the window before its next frame, as if assistive technology asked for it
+    pub fn perform(&self, request: AccessActionRequest) {
+        self.state.borrow_mut().actions.push(request)
+    }
+}
+
+impl AccessibilityConsumer for MemoryAccessibility {
+    fn update(&mut self, update: &AccessUpdate) {
+        let mut state = self.state.borrow_mut();
+        state.tree.apply(update);
+        state.updates += 1;
+    }
+
+    fn take_actions(&mut self) -> Vec<AccessActionRequest> {
+        std::mem::take(&mut self.state.borrow_mut().actions)
+    }
+}
@@ -1,11 +1,21 @@
 use crate::{
+    animation::Animated,
     app::AppState,
-    canvas::{font::Edging, Canvas2D, Color4f, Font, FontStyle, Paint, Size, TextBlob, Typeface},
+    canvas::{
+        font::Edging, Canvas2D, Color, Color4f, Font, FontStyle, Paint, Rect, Size, TextBlob,
+        Typeface,
+    },
     constraints::BoxConstraints,
-    widget::{style::Theme, Event, EventCtx, MouseEvent, PaintCtx, Widget},
+    widget::{
+        accessibility::{AccessAction, AccessNode, Role},
+        icon::IconSource,
+        style::{TextButtonStyle, Theme},
+        Event, EventCtx, MouseEvent, PaintCtx, Widget,
+    },
 };
 
 use super::LayoutCtx;
+use std::any::Any;
 
 enum ButtonState {
     Inactive,
@@ -23,18 +33,115 @@ pub struct TextButton<Message> {
     state: ButtonState,
     style: ButtonStyle,
     text: String,
+    font_size: f32,
+    // Picked from the app's fonts during layout
     font: Font,
     on_click: Option<Message>,
+    background: Animated<Color>,
+}
+
+fn background_animation() -> Animated<Color> {
+    Animated::new(Color::TRANSPARENT).with_initial_jump()
+}
+
+fn button_font(typeface: Typeface, font_size: f32) -> Font {
+    let mut font = Font::new(typeface, font_size);
+    font.set_edging(Edging::SubpixelAntiAlias);
+    font.set_subpixel(true);
+    font
+}
+
+// Hover and press tracking shared by the buttons, publishes the message on a click
+fn handle_event<Message: Clone>(
+    state: &mut ButtonState,
+    on_click: &Option<Message>,
+    event: &Event,
+    ctx: &mut EventCtx<Message>,
+) -> bool {
+    match event {
+        Event::Mouse(MouseEvent::MouseEnter(_)) => {
+            *state = ButtonState::Hover;
+            true
+        }
+        Event::Mouse(MouseEvent::MouseMove(_)) => {
+            *state = ButtonState::Hover;
+            true
+        }
+        Event::Mouse(MouseEvent::MouseLeave(_)) => {
+            *state = ButtonState::Inactive;
+            true
+        }
+        Event::Mouse(MouseEvent::MouseDown(_)) => {
+            *state = ButtonState::Active;
+            true
+        }
+        Event::Mouse(MouseEvent::MouseUp(_)) => {
+            if let Some(message) = on_click {
+                ctx.publish(message.clone())
+            }
+
+            *state = ButtonState::Inactive;
+            true
+        }
+        Event::Access(request) if request.action == AccessAction::Press => {
+            if let Some(message) = on_click {
+                ctx.publish(message.clone())
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+// Paints the background and outline of the button, returns the color of its content
+fn paint_background(
+    button: &ButtonStyle,
+    state: &ButtonState,
+    background: &Animated<Color>,
+    style: &TextButtonStyle,
+    ctx: &PaintCtx,
+    canvas: &mut dyn Canvas2D,
+) -> Color {
+    let rect = ctx.rect();
+
+    // Filled buttons change color, the others fade a tinted background in and out
+    let target = match (button, state) {
+        (ButtonStyle::Fill, ButtonState::Inactive) => style.inactive,
+        (ButtonStyle::Fill, ButtonState::Active) => style.active,
+        (ButtonStyle::Fill, ButtonState::Hover) => style.hovered,
+        (_, ButtonState::Inactive) => style.active.with_a(0),
+        (_, ButtonState::Active) => style.active.with_a(100),
+        (_, ButtonState::Hover) => style.active.with_a(50),
+    };
+
+    let now = ctx.now();
+    let color = background.animate_to(target, now);
+    if background.is_animating(now) {
+        ctx.request_animation_frame()
+    }
+
+    let mut bg_paint = Paint::default();
+    bg_paint.set_anti_alias(true);
+    if color.a() > 0 {
+        bg_paint.set_color(color);
+        canvas.draw_rounded_rect(&rect, style.rounding, style.rounding, &bg_paint);
+    }
+
+    match button {
+        ButtonStyle::Fill => style.text,
+        ButtonStyle::Outline => {
+            bg_paint.set_color(style.active);
+            bg_paint.set_stroke(true);
+            canvas.draw_rounded_rect(&rect, style.rounding, style.rounding, &bg_paint);
+            style.active
+        }
+        ButtonStyle::Text => style.active,
+    }
 }
 
 impl<Message> TextButton<Message> {
     pub fn new(text: &str, font_size: f32) -> Self {
-        let mut font = Font::new(
-            Typeface::new("Monospace", FontStyle::normal()).unwrap(),
-            font_size,
-        );
-        font.set_edging(Edging::SubpixelAntiAlias);
-        font.set_subpixel(true);
+        let font = button_font(Typeface::default(), font_size);
         let mut bg_paint = Paint::default();
         bg_paint.set_anti_alias(true);
         bg_paint.set_color4f(Color4f::new(0.25, 0.25, 0.25, 1.0), None);
@@ -44,9 +151,11 @@ impl<Message> TextButton<Message> {
         Self {
             state: ButtonState::Inactive,
             text: text.to_string(),
+            font_size,
             font,
             on_click: None,
             style: ButtonStyle::Outline,
+            background: background_animation(),
         }
     }
 
@@ -63,36 +172,12 @@ impl<Message> TextButton<Message> {
 
 impl<State: AppState> Widget<State> for TextButton<State::Message> {
     fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, _: &State) -> bool {
-        match event {
-            Event::Mouse(MouseEvent::MouseEnter(_)) => {
-                self.state = ButtonState::Hover;
-                true
-            }
-            Event::Mouse(MouseEvent::MouseMove(_)) => {
-                self.state = ButtonState::Hover;
-                true
-            }
-            Event::Mouse(MouseEvent::MouseLeave(_)) => {
-                self.state = ButtonState::Inactive;
-                true
-            }
-            Event::Mouse(MouseEvent::MouseDown(_)) => {
-                self.state = ButtonState::Active;
-                true
-            }
-            Event::Mouse(MouseEvent::MouseUp(_)) => {
-                if let Some(message) = &self.on_click {
-                    ctx.publish(message.clone())
-                }
-
-                self.state = ButtonState::Inactive;
-                true
-            }
-            _ => false,
-        }
+        handle_event(&mut self.state, &self.on_click, event, ctx)
     }
 
-    fn layout(&mut self, constraints: &BoxConstraints, _ctx: &mut LayoutCtx, _: &State) -> Size {
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, _: &State) -> Size {
+        let typeface = ctx.fonts().typeface(Some("monospace"), FontStyle::normal());
+        self.font = button_font(typeface, self.font_size);
         let blob = TextBlob::from_str(&self.text, &self.font);
         let size = blob.unwrap().bounds().size();
         let width = constraints.max_width().unwrap_or(size.width);
@@ -100,101 +185,203 @@ impl<State: AppState> Widget<State> for TextButton<State::Message> {
         Size::new(width, height)
     }
 
+    fn accessibility(&self, _: &State) -> Option<AccessNode> {
+        Some(
+            AccessNode::new(Role::Button)
+                .with_name(&self.text)
+                .with_action(AccessAction::Press),
+        )
+    }
+
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new((
+            std::mem::replace(&mut self.state, ButtonState::Inactive),
+            std::mem::replace(&mut self.background, background_animation()),
+        )))
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        if let Ok(local_state) = local_state.downcast::<(ButtonState, Animated<Color>)>() {
+            (self.state, self.background) = *local_state
+        }
+    }
+
     fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, _: &State) {
+        let style = ctx.style::<TextButtonStyle>(theme);
+        let color = paint_background(
+            &self.style,
+            &self.state,
+            &self.background,
+            &style,
+            ctx,
+            canvas,
+        );
+
         let mut text_paint = Paint::default();
         text_paint.set_anti_alias(true);
+        text_paint.set_color(color);
+        canvas.draw_string(&ctx.rect(), &self.text, &self.font, &text_paint);
+    }
+}
 
-        let rect = ctx.rect();
-        match self.style {
-            ButtonStyle::Fill => {
-                let mut bg_paint = Paint::default();
-                bg_paint.set_anti_alias(true);
-                match self.state {
-                    ButtonState::Inactive => bg_paint.set_color(theme.primary.with_a(200)),
-                    ButtonState::Active => bg_paint.set_color(theme.primary),
-                    ButtonState::Hover => bg_paint.set_color(theme.primary.with_a(230)),
-                };
-                canvas.draw_rounded_rect(
-                    &rect,
-                    theme.button.rounding,
-                    theme.button.rounding,
-                    &bg_paint,
-                );
-                text_paint.set_color(theme.text);
-                canvas.draw_string(&rect, &self.text, &self.font, &text_paint);
-            }
-            ButtonStyle::Outline => {
-                let mut bg_paint = Paint::default();
-                bg_paint.set_anti_alias(true);
-
-                bg_paint.set_color(theme.primary);
-                bg_paint.set_stroke(true);
-                canvas.draw_rounded_rect(
-                    &rect,
-                    theme.button.rounding,
-                    theme.button.rounding,
-                    &bg_paint,
-                );
-
-                match self.state {
-                    ButtonState::Inactive => (),
-                    ButtonState::Active => {
-                        bg_paint.set_color(theme.primary.with_a(100));
-                        bg_paint.set_stroke(false);
-                        canvas.draw_rounded_rect(
-                            &rect,
-                            theme.button.rounding,
-                            theme.button.rounding,
-                            &bg_paint,
-                        );
-                    }
-                    ButtonState::Hover => {
-                        bg_paint.set_color(theme.primary.with_a(50));
-                        bg_paint.set_stroke(false);
-                        canvas.draw_rounded_rect(
-                            &rect,
-                            theme.button.rounding,
-                            theme.button.rounding,
-                            &bg_paint,
-                        );
-                    }
-                }
-
-                text_paint.set_color(theme.primary);
-
-                canvas.draw_string(&rect, &self.text, &self.font, &text_paint);
-            }
-            ButtonStyle::Text => {
-                text_paint.set_color(theme.primary);
-
-                let mut bg_paint = Paint::default();
-                bg_paint.set_anti_alias(true);
-                match self.state {
-                    ButtonState::Inactive => (),
-                    ButtonState::Active => {
-                        bg_paint.set_color(theme.primary.with_a(100));
-                        bg_paint.set_stroke(false);
-                        canvas.draw_rounded_rect(
-                            &rect,
-                            theme.button.rounding,
-                            theme.button.rounding,
-                            &bg_paint,
-                        );
-                    }
-                    ButtonState::Hover => {
-                        bg_paint.set_color(theme.primary.with_a(50));
-                        bg_paint.set_stroke(false);
-                        canvas.draw_rounded_rect(
-                            &rect,
-                            theme.button.rounding,
-                            theme.button.rounding,
-                            &bg_paint,
-                        );
-                    }
-                }
-
-                canvas.draw_string(&rect, &self.text, &self.font, &text_paint);
+// Space between the icon and the text of an IconButton
+const ICON_SPACING: f32 = 6.0;
+
+// A button showing a registered icon, with optional text after it. The icon takes the text color.
+pub struct IconButton<Message> {
+    state: ButtonState,
+    style: ButtonStyle,
+    icon: IconSource,
+    icon_size: f32,
+    text: Option<String>,
+    font_size: f32,
+    // Picked from the app's fonts during layout
+    font: Font,
+    // Of the text, from the last layout
+    text_width: f32,
+    description: Option<String>,
+    on_click: Option<Message>,
+    background: Animated<Color>,
+}
+
+impl<Message> IconButton<Message> {
+    pub fn new(icon: &str) -> Self {
+        Self {
+            state: ButtonState::Inactive,
+            style: ButtonStyle::Text,
+            icon: IconSource::from(icon),
+            icon_size: 20.0,
+            text: None,
+            font_size: 14.0,
+            font: button_font(Typeface::default(), 14.0),
+            text_width: 0.0,
+            description: None,
+            on_click: None,
+            background: background_animation(),
+        }
+    }
+
+    pub fn with_text(mut self, text: &str, font_size: f32) -> Self {
+        self.text = Some(text.to_string());
+        self.font_size = font_size;
+        self
+    }
+
+    pub fn with_icon_size(mut self, size: f32) -> Self {
+        self.icon_size = size;
+        self
+    }
+
+    // Read out by assistive technology instead of the text, icon only buttons should have one
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn style(mut self, style: ButtonStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn on_click(mut self, message: Message) -> Self {
+        self.on_click = Some(message);
+        self
+    }
+
+    fn content_width(&self) -> f32 {
+        match self.text {
+            Some(_) => self.icon_size + ICON_SPACING + self.text_width,
+            None => self.icon_size,
+        }
+    }
+}
+
+impl<State: AppState> Widget<State> for IconButton<State::Message> {
+    fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, _: &State) -> bool {
+        handle_event(&mut self.state, &self.on_click, event, ctx)
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, _: &State) -> Size {
+        let mut height = self.icon_size;
+        self.text_width = 0.0;
+        if let Some(text) = &self.text {
+            let typeface = ctx.fonts().typeface(Some("monospace"), FontStyle::normal());
+            self.font = button_font(typeface, self.font_size);
+            if let Some(blob) = TextBlob::from_str(text, &self.font) {
+                let size = blob.bounds().size();
+                self.text_width = size.width;
+                height = height.max(size.height);
             }
         }
+
+        // Leaves room around the icon for the background
+        let padding = self.icon_size / 4.0;
+        let width = constraints
+            .max_width()
+            .unwrap_or_else(|| constraints.constrain_width(self.content_width() + padding * 2.0));
+        let height = constraints
+            .max_height()
+            .unwrap_or_else(|| constraints.constrain_height(height + padding * 2.0));
+        Size::new(width, height)
+    }
+
+    fn accessibility(&self, _: &State) -> Option<AccessNode> {
+        let name = self.description.as_ref().or(self.text.as_ref());
+        let node = AccessNode::new(Role::Button).with_action(AccessAction::Press);
+        Some(match name {
+            Some(name) => node.with_name(name),
+            None => node,
+        })
+    }
+
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new((
+            std::mem::replace(&mut self.state, ButtonState::Inactive),
+            std::mem::replace(&mut self.background, background_animation()),
+        )))
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        if let Ok(local_state) = local_state.downcast::<(ButtonState, Animated<Color>)>() {
+            (self.state, self.background) = *local_state
+        }
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, _: &State) {
+        let style = ctx.style::<TextButtonStyle>(theme);
+        let color = paint_background(
+            &self.style,
+            &self.state,
+            &self.background,
+            &style,
+            ctx,
+            canvas,
+        );
+
+        // The icon and the text are centered together
+        let rect = ctx.rect();
+        let left = rect.center_x() - self.content_width() / 2.0;
+        let icon = Rect::from_xywh(
+            left,
+            rect.center_y() - self.icon_size / 2.0,
+            self.icon_size,
+            self.icon_size,
+        );
+        if let Some(icons) = ctx.icons() {
+            icons.draw(canvas, &self.icon, &icon, color)
+        }
+
+        if let Some(text) = &self.text {
+            let mut text_paint = Paint::default();
+            text_paint.set_anti_alias(true);
+            text_paint.set_color(color);
+            let text_rect = Rect::from_xywh(
+                icon.right + ICON_SPACING,
+                rect.top,
+                self.text_width,
+                rect.height(),
+            );
+            canvas.draw_string(&text_rect, text, &self.font, &text_paint);
+        }
     }
 }
@@ -6,6 +6,7 @@ use crate::{
 };
 
 use super::LayoutCtx;
+use std::any::Any;
 
 pub struct Center<State> {
     child: ChildSlot<State>,
@@ -60,4 +61,20 @@ impl<State: AppState> Widget<State> for Center<State> {
     fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
         self.child.paint(theme, ctx, canvas, state)
     }
+
+    fn children_mut(&mut self) -> Vec<&mut ChildSlot<State>> {
+        vec![&mut self.child]
+    }
+
+    fn children(&self) -> Vec<&ChildSlot<State>> {
+        vec![&self.child]
+    }
+
+    fn take_layout_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new(self.size))
+    }
+
+    fn restore_layout_state(&mut self, layout: Box<dyn Any>) -> bool {
+        layout.downcast_ref::<Option<Size>>() == Some(&self.size)
+    }
 }
@@ -0,0 +1,454 @@
+use crate::{
+    animation::Animated,
+    app::AppState,
+    canvas::{Canvas2D, Font, Paint, Path, Rect, Size},
+    constraints::BoxConstraints,
+    lens::Binding,
+    widget::{
+        accessibility::{AccessAction, AccessNode, AccessValue, Role},
+        style::{CheckboxStyle, Theme},
+        Event, EventCtx, KeyEvent, MouseEvent, PaintCtx, Widget,
+    },
+};
+
+use super::LayoutCtx;
+use std::any::Any;
+use winit::event::{ElementState, VirtualKeyCode};
+
+// Of the box, and of the circles of a RadioGroup
+pub(crate) const BOX_SIZE: f32 = 18.0;
+// Between the box and its label
+pub(crate) const LABEL_SPACING: f32 = 8.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CheckState {
+    #[default]
+    Unchecked,
+    Checked,
+    // Neither, like a "select all" box when only some of the items are selected
+    Indeterminate,
+}
+
+impl CheckState {
+    // The state after a click, an indeterminate box gets checked
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Checked => Self::Unchecked,
+            Self::Unchecked | Self::Indeterminate => Self::Checked,
+        }
+    }
+}
+
+impl From<bool> for CheckState {
+    fn from(checked: bool) -> Self {
+        if checked {
+            Self::Checked
+        } else {
+            Self::Unchecked
+        }
+    }
+}
+
+enum CheckBinding<State: AppState> {
+    Bool(Binding<State, bool>),
+    TriState(Binding<State, CheckState>),
+}
+
+pub struct Checkbox<State: AppState> {
+    binding: Option<CheckBinding<State>>,
+    // Used when there's no binding, kept across rebuilds
+    checked: CheckState,
+    on_change: Option<Box<dyn Fn(CheckState) -> State::Message>>,
+    label: Option<String>,
+    // The theme's font size when None
+    font_size: Option<f32>,
+    // Picked from the app's fonts during layout
+    font: Font,
+    // Of the label, from the last layout
+    label_width: f32,
+    hovered: bool,
+    focused: bool,
+    // Held down keys repeat, the box only toggles on the first press
+    space_pressed: bool,
+    // 0 is unchecked, 1 is checked or indeterminate
+    fill: Animated<f32>,
+}
+
+fn fill_animation() -> Animated<f32> {
+    Animated::new(0.0).with_initial_jump()
+}
+
+// Measured with the theme's font, the size of the text when none is given
+pub(crate) fn label_font(ctx: &LayoutCtx, font_size: Option<f32>) -> Font {
+    let fonts = ctx.fonts();
+    fonts.font(None, font_size.unwrap_or(fonts.style().size))
+}
+
+impl<State: AppState> Checkbox<State> {
+    pub fn new() -> Self {
+        Self {
+            binding: None,
+            checked: CheckState::Unchecked,
+            on_change: None,
+            label: None,
+            font_size: None,
+            font: Font::default(),
+            label_width: 0.0,
+            hovered: false,
+            focused: false,
+            space_pressed: false,
+            fill: fill_animation(),
+        }
+    }
+
+    // Shown after the box, clicking it toggles the box too
+    pub fn with_label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_string());
+        self
+    }
+
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    // How a box without a binding starts out. It keeps track of its state itself after that.
+    pub fn checked(mut self, checked: impl Into<CheckState>) -> Self {
+        self.checked = checked.into();
+        self
+    }
+
+    // Publishes the message when the user toggles a box without a binding
+    pub fn on_change<F>(mut self, f: F) -> Self
+    where
+        F: Fn(CheckState) -> State::Message + 'static,
+    {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    // Shows the value from the state and publishes the message from on_change when the user toggles the box.
+    pub fn bind<G, F>(mut self, get: G, on_change: F) -> Self
+    where
+        G: Fn(&State) -> &bool + 'static,
+        F: Fn(&bool) -> State::Message + 'static,
+    {
+        self.binding = Some(CheckBinding::Bool(Binding::new(get, on_change)));
+        self
+    }
+
+    // Like bind, for a box that can be indeterminate. The user only ever checks or unchecks it,
+    // whether it's indeterminate is up to the state.
+    pub fn bind_tri_state<G, F>(mut self, get: G, on_change: F) -> Self
+    where
+        G: Fn(&State) -> &CheckState + 'static,
+        F: Fn(&CheckState) -> State::Message + 'static,
+    {
+        self.binding = Some(CheckBinding::TriState(Binding::new(get, on_change)));
+        self
+    }
+
+    fn check_state(&self, state: &State) -> CheckState {
+        match &self.binding {
+            Some(CheckBinding::Bool(binding)) => CheckState::from(*binding.get(state)),
+            Some(CheckBinding::TriState(binding)) => *binding.get(state),
+            None => self.checked,
+        }
+    }
+
+    fn toggle(&mut self, ctx: &mut EventCtx<State::Message>, state: &State) {
+        self.checked = self.check_state(state).toggled();
+        match &self.binding {
+            Some(CheckBinding::Bool(binding)) => {
+                ctx.publish(binding.changed(&(self.checked == CheckState::Checked)))
+            }
+            Some(CheckBinding::TriState(binding)) => ctx.publish(binding.changed(&self.checked)),
+            None => {
+                if let Some(on_change) = &self.on_change {
+                    ctx.publish(on_change(self.checked))
+                }
+            }
+        }
+    }
+}
+
+impl<State: AppState> Widget<State> for Checkbox<State> {
+    fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, state: &State) -> bool {
+        match event {
+            Event::Mouse(MouseEvent::MouseEnter(_)) | Event::Mouse(MouseEvent::MouseMove(_)) => {
+                self.hovered = true;
+                true
+            }
+            Event::Mouse(MouseEvent::MouseLeave(_)) => {
+                self.hovered = false;
+                true
+            }
+            Event::Mouse(MouseEvent::MouseDown(_)) => {
+                ctx.request_focus();
+                true
+            }
+            // Released outside of the box it stays as it is
+            Event::Mouse(MouseEvent::MouseUp(_)) => {
+                if self.hovered {
+                    self.toggle(ctx, state);
+                }
+                true
+            }
+            // The release goes elsewhere once the focus is gone
+            Event::Focus(focused) => {
+                self.focused = *focused;
+                self.space_pressed = false;
+                true
+            }
+            Event::Key(KeyEvent::Input(input))
+                if input.virtual_keycode == Some(VirtualKeyCode::Space) =>
+            {
+                let pressed = input.state == ElementState::Pressed;
+                if pressed && !self.space_pressed {
+                    self.toggle(ctx, state);
+                }
+                self.space_pressed = pressed;
+                true
+            }
+            Event::Key(KeyEvent::Char(' ')) => true,
+            Event::Access(request) if request.action == AccessAction::Press => {
+                self.toggle(ctx, state);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, _: &State) -> Size {
+        self.font = label_font(ctx, self.font_size);
+        let (mut width, mut height) = (BOX_SIZE, BOX_SIZE);
+        if let Some(label) = &self.label {
+            self.label_width = self.font.measure_str(label, None).0;
+            width += LABEL_SPACING + self.label_width;
+            height = height.max(self.font.spacing());
+        }
+
+        Size::new(
+            constraints.constrain_width(width),
+            constraints.constrain_height(height),
+        )
+    }
+
+    fn accessibility(&self, state: &State) -> Option<AccessNode> {
+        let value = match self.check_state(state) {
+            CheckState::Unchecked => AccessValue::Toggled(false),
+            CheckState::Checked => AccessValue::Toggled(true),
+            CheckState::Indeterminate => AccessValue::Mixed,
+        };
+        let node = AccessNode::new(Role::Checkbox)
+            .with_value(value)
+            .with_action(AccessAction::Press)
+            .with_action(AccessAction::Focus);
+        Some(match &self.label {
+            Some(label) => node.with_name(label),
+            None => node,
+        })
+    }
+
+    // A bound box shows the state, the one it keeps itself is only used without a binding.
+    // The pressed space bar is kept too, the rebuild after a toggle would otherwise take the repeats.
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new((
+            self.checked,
+            self.hovered,
+            self.focused,
+            self.space_pressed,
+            std::mem::replace(&mut self.fill, fill_animation()),
+        )))
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        if let Ok(local_state) =
+            local_state.downcast::<(CheckState, bool, bool, bool, Animated<f32>)>()
+        {
+            (
+                self.checked,
+                self.hovered,
+                self.focused,
+                self.space_pressed,
+                self.fill,
+            ) = *local_state
+        }
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
+        let rect = ctx.rect();
+        let style = ctx.style::<CheckboxStyle>(theme);
+        let check = self.check_state(state);
+
+        let now = ctx.now();
+        let target = if check == CheckState::Unchecked {
+            0.0
+        } else {
+            1.0
+        };
+        let fill = self.fill.animate_to(target, now);
+        if self.fill.is_animating(now) {
+            ctx.request_animation_frame()
+        }
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        let rounding = style.rounding;
+        let bounds = Rect::from_xywh(
+            rect.left,
+            rect.center_y() - BOX_SIZE / 2.0,
+            BOX_SIZE,
+            BOX_SIZE,
+        );
+
+        if self.focused {
+            paint.set_color(style.focus);
+            canvas.draw_rounded_rect(
+                &bounds.with_outset((3.0, 3.0)),
+                rounding + 3.0,
+                rounding + 3.0,
+                &paint,
+            );
+        }
+
+        if fill > 0.0 {
+            paint.set_color(style.fill);
+            paint.set_alpha_f(fill * style.fill.a() as f32 / 255.0);
+            canvas.draw_rounded_rect(&bounds, rounding, rounding, &paint);
+        }
+
+        let border = if self.hovered || fill > 0.0 {
+            style.fill
+        } else {
+            style.border
+        };
+        paint.set_color(border);
+        paint.set_stroke(true);
+        paint.set_stroke_width(1.5);
+        canvas.draw_rounded_rect(&bounds.with_inset((0.75, 0.75)), rounding, rounding, &paint);
+
+        let mut mark = Path::new();
+        let (left, top, size) = (bounds.left, bounds.top, BOX_SIZE);
+        match check {
+            CheckState::Checked => {
+                mark.move_to((left + size * 0.25, top + size * 0.5))
+                    .line_to((left + size * 0.42, top + size * 0.68))
+                    .line_to((left + size * 0.75, top + size * 0.32));
+            }
+            CheckState::Indeterminate => {
+                mark.move_to((left + size * 0.27, top + size * 0.5))
+                    .line_to((left + size * 0.73, top + size * 0.5));
+            }
+            CheckState::Unchecked => (),
+        }
+        paint.set_color(style.check);
+        paint.set_alpha_f(fill * style.check.a() as f32 / 255.0);
+        paint.set_stroke_width(2.0);
+        canvas.draw_path(&mark, &paint);
+
+        if let Some(label) = &self.label {
+            let mut text_paint = Paint::default();
+            text_paint.set_anti_alias(true);
+            text_paint.set_color(style.text);
+            let label_rect = Rect::from_xywh(
+                bounds.right + LABEL_SPACING,
+                rect.top,
+                self.label_width,
+                rect.height(),
+            );
+            canvas.draw_string(&label_rect, label, &self.font, &text_paint);
+        }
+    }
+}
+
+impl<State: AppState> Default for Checkbox<State> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        app::MessageCtx,
+        widget::{accessibility::AccessActionRequest, Properties},
+    };
+    use std::sync::mpsc;
+    use winit::event::{KeyboardInput, ModifiersState};
+
+    struct State;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Message {
+        Changed(CheckState),
+    }
+
+    impl AppState for State {
+        type Message = Message;
+
+        fn handle_message(&mut self, _: Self::Message, _: &mut MessageCtx<Self>) {}
+    }
+
+    fn space(state: ElementState) -> Event {
+        #[allow(deprecated)]
+        let input = KeyboardInput {
+            scancode: 0,
+            state,
+            virtual_keycode: Some(VirtualKeyCode::Space),
+            modifiers: ModifiersState::empty(),
+        };
+        Event::Key(KeyEvent::Input(input))
+    }
+
+    // Sends the events to the box and returns what it published
+    fn send(checkbox: &mut Checkbox<State>, events: &[Event]) -> Vec<Message> {
+        let properties = Properties::default();
+        let (message_tx, message_rx) = mpsc::channel();
+        for event in events {
+            let mut ctx = EventCtx::headless(&properties, message_tx.clone());
+            checkbox.event(event, &mut ctx, &State);
+        }
+        message_rx.try_iter().collect()
+    }
+
+    fn checkbox() -> Checkbox<State> {
+        Checkbox::new().on_change(Message::Changed)
+    }
+
+    #[test]
+    fn held_space_toggles_once() {
+        let mut checkbox = checkbox();
+        let pressed = || space(ElementState::Pressed);
+        let messages = send(&mut checkbox, &[pressed(), pressed(), pressed()]);
+        assert_eq!(messages, vec![Message::Changed(CheckState::Checked)]);
+
+        let messages = send(&mut checkbox, &[space(ElementState::Released), pressed()]);
+        assert_eq!(messages, vec![Message::Changed(CheckState::Unchecked)]);
+
+        // Losing the focus while held doesn't swallow the next press
+        let messages = send(&mut checkbox, &[Event::Focus(false), pressed()]);
+        assert_eq!(messages, vec![Message::Changed(CheckState::Checked)]);
+    }
+
+    #[test]
+    fn unbound_state_survives_a_rebuild() {
+        let mut old = checkbox().checked(CheckState::Indeterminate);
+        let messages = send(
+            &mut old,
+            &[Event::Access(AccessActionRequest::new(
+                0,
+                AccessAction::Press,
+            ))],
+        );
+        assert_eq!(messages, vec![Message::Changed(CheckState::Checked)]);
+
+        let mut new = checkbox().checked(CheckState::Indeterminate);
+        new.restore_local_state(old.take_local_state().unwrap());
+        assert_eq!(new.check_state(&State), CheckState::Checked);
+
+        let messages = send(&mut new, &[space(ElementState::Pressed)]);
+        assert_eq!(messages, vec![Message::Changed(CheckState::Unchecked)]);
+    }
+}
@@ -1,10 +1,13 @@
 use std::{sync::atomic::AtomicUsize, sync::atomic::Ordering};
 
 use crate::{
-    app::AppState,
-    canvas::{Canvas2D, Point, Size},
+    app::{AppState, ImageSource, Images},
+    canvas::{Canvas2D, Point, Rect, Size},
     constraints::BoxConstraints,
-    widget::{Event, EventCtx, MouseEvent, PaintCtx, Properties, Theme, Widget},
+    widget::{
+        accessibility::{AccessAction, AccessNode},
+        Event, EventCtx, MouseEvent, PaintCtx, Properties, Theme, Widget,
+    },
 };
 
 use super::LayoutCtx;
@@ -18,15 +21,18 @@ pub struct ChildSlot<State> {
     uid: usize,
     widget: Box<dyn Widget<State>>,
     properties: Properties,
+    // Layout cache. The cached size is reused as long as the constraints don't change and nothing below was invalidated.
+    needs_layout: bool,
+    depends_on_state: bool,
+    // Images the widget laid out without because they were still loading
+    loading_images: Vec<ImageSource>,
+    constraints: Option<BoxConstraints>,
+    layout_size: Size,
 }
 
 impl<State: AppState> ChildSlot<State> {
     pub fn new(widget: impl Widget<State> + 'static) -> Self {
-        Self {
-            uid: next_uid(),
-            widget: Box::new(widget),
-            properties: Properties::default(),
-        }
+        Self::new_with_box(Box::new(widget))
     }
 
     pub fn new_with_box(widget: Box<dyn Widget<State>>) -> Self {
@@ -34,6 +40,113 @@ impl<State: AppState> ChildSlot<State> {
             uid: next_uid(),
             widget,
             properties: Properties::default(),
+            needs_layout: true,
+            depends_on_state: false,
+            loading_images: Vec::new(),
+            constraints: None,
+            layout_size: Size::default(),
+        }
+    }
+
+    pub fn needs_layout(&self) -> bool {
+        self.needs_layout
+    }
+
+    // Only marks this slot. Ancestors are marked when the request travels up through event handling,
+    // or by calling invalidate_state_dependents on the root.
+    pub fn mark_needs_layout(&mut self) {
+        self.needs_layout = true
+    }
+
+    // Marks this slot and everything below it, forcing a full layout
+    pub fn invalidate_layout(&mut self) {
+        self.needs_layout = true;
+        for child in self.widget.children_mut() {
+            child.invalidate_layout()
+        }
+    }
+
+    // Marks every slot whose layout depends on the application state, and all their ancestors, as needing layout.
+    // Returns true if anything in this subtree was marked.
+    pub fn invalidate_state_dependents(&mut self) -> bool {
+        let mut dirty = self.depends_on_state;
+        for child in self.widget.children_mut() {
+            dirty |= child.invalidate_state_dependents();
+        }
+
+        if dirty {
+            self.needs_layout = true
+        }
+
+        dirty
+    }
+
+    // Like invalidate_state_dependents, for the slots that laid out an image which has been decoded since.
+    pub fn invalidate_loaded_images(&mut self, images: &Images) -> bool {
+        let mut dirty = self
+            .loading_images
+            .iter()
+            .any(|source| !images.is_loading(source));
+        for child in self.widget.children_mut() {
+            dirty |= child.invalidate_loaded_images(images);
+        }
+
+        if dirty {
+            self.needs_layout = true
+        }
+
+        dirty
+    }
+
+    // A new slot can take over an old one if it holds the same kind of widget with the same key.
+    pub fn can_reconcile(&self, old: &ChildSlot<State>) -> bool {
+        self.widget.type_name() == old.widget.type_name() && self.widget.key() == old.widget.key()
+    }
+
+    // Takes over identity, geometry and widget local state from the slot this one replaces,
+    // then does the same for the children.
+    pub fn reconcile(&mut self, old: &mut ChildSlot<State>) {
+        self.uid = old.uid;
+        self.properties.position = old.properties.position;
+        self.properties.size = old.properties.size;
+
+        if let Some(local_state) = old.widget.take_local_state() {
+            self.widget.restore_local_state(local_state)
+        }
+
+        let old_children: Vec<usize> = old
+            .widget
+            .children()
+            .iter()
+            .map(|child| child.uid)
+            .collect();
+        reconcile_children(self.widget.children_mut(), old.widget.children_mut());
+
+        // The cached layout stays valid if the widget lays out like the old one and has the same children,
+        // in the same order. Children that changed only make it lay out again, their siblings keep their cache.
+        let same_children = self
+            .widget
+            .children()
+            .iter()
+            .map(|child| child.uid)
+            .eq(old_children);
+        let layout_kept = same_children
+            && match old.widget.take_layout_state() {
+                Some(layout) => self.widget.restore_layout_state(layout),
+                None => false,
+            };
+        if layout_kept {
+            self.properties.children = std::mem::take(&mut old.properties.children);
+            self.depends_on_state = old.depends_on_state;
+            self.loading_images = std::mem::take(&mut old.loading_images);
+            self.constraints = old.constraints;
+            self.layout_size = old.layout_size;
+            self.needs_layout = old.needs_layout
+                || self
+                    .widget
+                    .children()
+                    .iter()
+                    .any(|child| child.needs_layout());
         }
     }
 
@@ -63,6 +176,22 @@ impl<State: AppState> ChildSlot<State> {
         x && y
     }
 
+    // Hands a broadcast event to the widget, which passes it on to its children
+    fn deliver(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, state: &State) {
+        // Siblings may already have requested layout, only a request from this subtree marks this slot.
+        let requested = ctx.layout_requested;
+        ctx.layout_requested = false;
+        if self.widget.event(event, ctx, state) {
+            ctx.set_consumer(self.uid())
+        }
+        self.take_focus_request(ctx);
+
+        if ctx.layout_requested {
+            self.needs_layout = true
+        }
+        ctx.layout_requested |= requested;
+    }
+
     fn propagate_mouse_event(
         &mut self,
         event: &MouseEvent,
@@ -77,6 +206,12 @@ impl<State: AppState> ChildSlot<State> {
             cursor: ctx.cursor,
             consumer: ctx.consumer,
             target: ctx.target,
+            timer_tx: ctx.timer_tx.clone(),
+            layout_requested: false,
+            now: ctx.now,
+            animation_frame_requested: false,
+            focus_requested: None,
+            focus_change: None,
         };
 
         if let Some(target) = ctx.target() {
@@ -100,6 +235,58 @@ impl<State: AppState> ChildSlot<State> {
                 ctx.set_consumer(uid)
             }
         }
+
+        if inner_ctx.layout_requested() {
+            self.needs_layout = true;
+            ctx.request_layout();
+        }
+
+        if inner_ctx.animation_frame_requested() {
+            ctx.request_animation_frame()
+        }
+
+        self.take_focus_request(&mut inner_ctx);
+        if inner_ctx.focus_change.is_some() {
+            ctx.focus_change = inner_ctx.focus_change
+        }
+    }
+
+    // Turns a focus request of this slot's widget into a change of the window's focus
+    fn take_focus_request(&self, ctx: &mut EventCtx<State::Message>) {
+        match ctx.focus_requested.take() {
+            Some(true) => ctx.focus_change = Some(Some(self.uid)),
+            Some(false) => ctx.focus_change = Some(None),
+            None => (),
+        }
+    }
+}
+
+// Keyed children are matched by key, wherever they moved. Others are matched by index.
+pub(crate) fn reconcile_children<State: AppState>(
+    new_children: Vec<&mut ChildSlot<State>>,
+    old_children: Vec<&mut ChildSlot<State>>,
+) {
+    let mut old_children: Vec<Option<&mut ChildSlot<State>>> =
+        old_children.into_iter().map(Some).collect();
+
+    for (index, child) in new_children.into_iter().enumerate() {
+        let old_index = match child.widget.key() {
+            Some(key) => old_children.iter().position(|old| {
+                old.as_ref()
+                    .map_or(false, |old| old.widget.key() == Some(key))
+            }),
+            None => old_children
+                .get(index)
+                .and_then(|old| old.as_ref())
+                .filter(|old| old.widget.key().is_none())
+                .map(|_| index),
+        };
+
+        if let Some(old) = old_index.and_then(|old_index| old_children[old_index].take()) {
+            if child.can_reconcile(old) {
+                child.reconcile(old)
+            }
+        }
     }
 }
 
@@ -107,9 +294,30 @@ impl<State: AppState> Widget<State> for ChildSlot<State> {
     fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, state: &State) -> bool {
         match event {
             Event::Mouse(event) => self.propagate_mouse_event(event, ctx, state),
-            Event::Key(_) => {
-                if self.widget.event(event, ctx, state) {
-                    ctx.set_consumer(self.uid())
+            Event::Timer(_) | Event::Window(_) => self.deliver(event, ctx, state),
+            Event::Key(_) | Event::Focus(_) => {
+                if ctx.target() == Some(self.uid) || !self.widget.children().is_empty() {
+                    self.deliver(event, ctx, state)
+                }
+            }
+            Event::Command(command) => match command.key() {
+                Some(key) if self.widget.key() == Some(key) => {
+                    self.deliver(&Event::Command(command.arrived()), ctx, state)
+                }
+                // Leaves would take it for their own, only containers pass it on
+                Some(_) if !self.widget.children().is_empty() => self.deliver(event, ctx, state),
+                Some(_) => (),
+                // Already delivered to an ancestor, it's not meant for this subtree
+                None => (),
+            },
+            Event::Access(request) => {
+                // Focusing is up to the slot, as if the widget had called request_focus
+                if request.target == self.uid && request.action == AccessAction::Focus {
+                    ctx.focus_change = Some(Some(self.uid))
+                }
+
+                if request.target == self.uid || !self.widget.children().is_empty() {
+                    self.deliver(event, ctx, state)
                 }
             }
         }
@@ -117,23 +325,25 @@ impl<State: AppState> Widget<State> for ChildSlot<State> {
         false
     }
 
-    fn layout(
-        &mut self,
-        constraints: &BoxConstraints,
-        _ctx: &mut LayoutCtx,
-        state: &State,
-    ) -> Size {
-        let mut inner_ctx = LayoutCtx::new();
-        let size = self.widget.layout(constraints, &mut inner_ctx, state);
-        for child in &inner_ctx.children {
-            self.properties.children.push(*child)
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, state: &State) -> Size {
+        if !self.needs_layout && self.constraints.as_ref() == Some(constraints) {
+            return self.layout_size;
         }
 
+        let mut inner_ctx = LayoutCtx::new(ctx.fonts(), ctx.images());
+        let size = self.widget.layout(constraints, &mut inner_ctx, state);
+        self.properties.children = inner_ctx.children;
+        self.depends_on_state = inner_ctx.depends_on_state;
+        self.loading_images = inner_ctx.loading_images;
+        self.constraints = Some(*constraints);
+        self.layout_size = size;
+        self.needs_layout = false;
+
         size
     }
 
-    fn paint(&self, theme: &Theme, _: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
-        let inner_ctx = PaintCtx::new(&self.properties);
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
+        let inner_ctx = ctx.for_child(&self.properties);
 
         canvas.save();
         canvas.translate(self.position());
@@ -141,6 +351,21 @@ impl<State: AppState> Widget<State> for ChildSlot<State> {
         canvas.restore();
     }
 
+    fn paint_overlay(
+        &self,
+        theme: &Theme,
+        ctx: &PaintCtx,
+        canvas: &mut dyn Canvas2D,
+        state: &State,
+    ) {
+        let inner_ctx = ctx.for_child(&self.properties);
+
+        canvas.save();
+        canvas.translate(self.position());
+        self.widget.paint_overlay(theme, &inner_ctx, canvas, state);
+        canvas.restore();
+    }
+
     fn flex(&self) -> f32 {
         self.widget.flex()
     }
@@ -148,4 +373,28 @@ impl<State: AppState> Widget<State> for ChildSlot<State> {
     fn uid(&self) -> usize {
         self.uid
     }
+
+    fn children_mut(&mut self) -> Vec<&mut ChildSlot<State>> {
+        self.widget.children_mut()
+    }
+
+    fn children(&self) -> Vec<&ChildSlot<State>> {
+        self.widget.children()
+    }
+
+    fn key(&self) -> Option<&str> {
+        self.widget.key()
+    }
+
+    fn type_name(&self) -> &'static str {
+        self.widget.type_name()
+    }
+
+    fn accessibility(&self, state: &State) -> Option<AccessNode> {
+        self.widget.accessibility(state)
+    }
+
+    fn caret_rect(&self, size: &Size, state: &State) -> Option<Rect> {
+        self.widget.caret_rect(size, state)
+    }
 }
@@ -1,49 +1,98 @@
 use crate::{
     app::AppState,
-    canvas::{Canvas2D, Color4f, Paint, Point, Rect, Size},
+    canvas::{Canvas2D, Color4f, Point, Size},
     constraints::BoxConstraints,
-    widget::{ChildSlot, Event, EventCtx, PaintCtx, Theme, Widget},
+    edge_insets::EdgeInsets,
+    widget::{
+        decoration::{Background, Border, BorderRadius, BoxShadow},
+        ChildSlot, Event, EventCtx, PaintCtx, Theme, Widget,
+    },
 };
 
 use super::LayoutCtx;
+use std::any::Any;
 
 pub struct Container<State> {
-    padding: f32,
-    margin: f32,
-    border: f32,
+    padding: EdgeInsets,
+    margin: EdgeInsets,
+    border: Border,
+    corner_radius: BorderRadius,
+    shadows: Vec<BoxShadow>,
+    background: Option<Background>,
     width: Option<f32>,
     height: Option<f32>,
     child: ChildSlot<State>,
-    paint: Option<Paint>,
 }
 
 impl<State: AppState> Container<State> {
     pub fn new(child: impl Widget<State> + 'static) -> Self {
         Self {
-            padding: 0.0,
-            margin: 0.0,
-            border: 0.0,
+            padding: EdgeInsets::zero(),
+            margin: EdgeInsets::zero(),
+            border: Border::default(),
+            corner_radius: BorderRadius::default(),
+            shadows: Vec::new(),
+            background: None,
             width: None,
             height: None,
             child: ChildSlot::new(child),
-            paint: None,
         }
     }
 
     pub fn with_color(mut self, color: &Color4f) -> Self {
-        self.paint = Some(Paint::new(*color, None));
+        self.background = Some(Background::Color(color.to_color()));
         self
     }
 
-    pub fn with_padding(mut self, padding: f32) -> Self {
-        self.padding = padding;
+    pub fn with_background(mut self, background: impl Into<Background>) -> Self {
+        self.background = Some(background.into());
         self
     }
 
-    pub fn with_margin(mut self, margin: f32) -> Self {
-        self.margin = margin;
+    pub fn with_padding(mut self, padding: impl Into<EdgeInsets>) -> Self {
+        self.padding = padding.into();
         self
     }
+
+    pub fn with_margin(mut self, margin: impl Into<EdgeInsets>) -> Self {
+        self.margin = margin.into();
+        self
+    }
+
+    pub fn with_border(mut self, border: Border) -> Self {
+        self.border = border;
+        self
+    }
+
+    pub fn with_corner_radius(mut self, radius: impl Into<BorderRadius>) -> Self {
+        self.corner_radius = radius.into();
+        self
+    }
+
+    // Shadows are painted in the order they are added
+    pub fn with_shadow(mut self, shadow: BoxShadow) -> Self {
+        self.shadows.push(shadow);
+        self
+    }
+
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    pub fn with_size(self, width: f32, height: f32) -> Self {
+        self.with_width(width).with_height(height)
+    }
+
+    // Everything between the outer edge and the child: margin, border and padding
+    fn insets(&self) -> EdgeInsets {
+        self.margin + self.border.widths() + self.padding
+    }
 }
 
 impl<State: AppState> Widget<State> for Container<State> {
@@ -54,59 +103,78 @@ impl<State: AppState> Widget<State> for Container<State> {
     // The container's layout strategy is to be as small as possible.
     // So shrink input constraints by border, padding and margin
     // Then return its child's size as its own size.
-
+    // A fixed width or height replaces the child's size, but is still clamped by the incoming constraints.
     fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, state: &State) -> Size {
-        // If the container is not given constraints from the parent check if we've been given a size
-        // If not given a size we ask the child to layout without constraints
+        ctx.register_child(self.child.uid());
+        let insets = self.insets();
+
+        let width = self.width.map(|width| constraints.constrain_width(width));
+        let height = self
+            .height
+            .map(|height| constraints.constrain_height(height));
+
+        // If neither a size nor a max constraint is given in a direction the child lays out unconstrained in that direction.
         // This might panic if the child is a flex container.
-        // If given a size we ask the child to layout with that size.
-        // This might still panic if the child is for example a horizontal container, but only height is given.
+        let mut child_constraints = BoxConstraints::new();
+        if let Some(max_width) = width.or_else(|| constraints.max_width()) {
+            child_constraints =
+                child_constraints.with_max_width((max_width - insets.horizontal()).max(0.0));
+        }
+        if let Some(max_height) = height.or_else(|| constraints.max_height()) {
+            child_constraints =
+                child_constraints.with_max_height((max_height - insets.vertical()).max(0.0));
+        }
 
-        // If the container is given constraints we'll shrink them by padding/margin and ask the child to layout with those constraints
-        ctx.register_child(self.child.uid());
-        let space_around = self.padding + self.margin + self.border;
-        let child_size = if constraints.max_width().is_none() || constraints.max_height().is_none()
-        {
-            if self.width.is_none() || self.height.is_none() {
-                self.child.layout(&BoxConstraints::new(), ctx, state)
-            } else {
-                let mut child_constraints = BoxConstraints::new();
-                if self.width.is_some() {
-                    child_constraints = child_constraints.with_max_width(self.width.unwrap_or(0.0))
-                }
-                if self.height.is_some() {
-                    child_constraints =
-                        child_constraints.with_max_height(self.height.unwrap_or(0.0))
-                }
-                self.child.layout(
-                    &child_constraints.shrunk(space_around, space_around),
-                    ctx,
-                    state,
-                )
-            }
-        } else {
-            let child_constraints = constraints.shrunk(space_around * 2.0, space_around * 2.0);
-            self.child.layout(&child_constraints, ctx, state)
-        };
+        let child_size = self.child.layout(&child_constraints, ctx, state);
 
         self.child
-            .set_position(&Point::new(space_around, space_around));
+            .set_position(&Point::new(insets.left, insets.top));
         self.child.set_size(&child_size);
 
         Size::new(
-            (child_size.width + space_around * 2.0).max(constraints.min_width().unwrap_or(0.0)),
-            (child_size.height + space_around * 2.0).max(constraints.min_height().unwrap_or(0.0)),
+            width.unwrap_or_else(|| {
+                constraints.constrain_width(child_size.width + insets.horizontal())
+            }),
+            height.unwrap_or_else(|| {
+                constraints.constrain_height(child_size.height + insets.vertical())
+            }),
         )
     }
 
     fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
-        if let Some(paint) = &self.paint {
-            canvas.draw_rect(&Rect::from_size(*ctx.size()), paint);
+        // The decoration covers the border box, everything inside the margin
+        let rect = self.margin.deflate_rect(&ctx.rect());
+
+        for shadow in &self.shadows {
+            shadow.paint(canvas, &rect, &self.corner_radius);
+        }
+
+        if let Some(background) = &self.background {
+            background.paint(canvas, &rect, &self.corner_radius);
         }
 
+        self.border.paint(canvas, &rect, &self.corner_radius);
+
         self.child.paint(theme, ctx, canvas, state);
     }
 
+    fn children_mut(&mut self) -> Vec<&mut ChildSlot<State>> {
+        vec![&mut self.child]
+    }
+
+    fn children(&self) -> Vec<&ChildSlot<State>> {
+        vec![&self.child]
+    }
+
+    fn take_layout_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new((self.insets(), self.width, self.height)))
+    }
+
+    fn restore_layout_state(&mut self, layout: Box<dyn Any>) -> bool {
+        layout.downcast_ref::<(EdgeInsets, Option<f32>, Option<f32>)>()
+            == Some(&(self.insets(), self.width, self.height))
+    }
+
     fn flex(&self) -> f32 {
         0.0
     }
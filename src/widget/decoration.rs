@@ -0,0 +1,459 @@
+use crate::{
+    canvas::{
+        BlurStyle, Canvas2D, Color, Image, MaskFilter, Paint, Path, Point, RRect, Rect, Shader,
+        TileMode, Vector,
+    },
+    edge_insets::EdgeInsets,
+};
+
+#[derive(Clone, Copy, Default)]
+pub struct BorderSide {
+    pub width: f32,
+    pub color: Color,
+}
+
+impl BorderSide {
+    pub fn new(width: f32, color: Color) -> Self {
+        Self { width, color }
+    }
+
+    pub fn none() -> Self {
+        Self::default()
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Border {
+    pub left: BorderSide,
+    pub top: BorderSide,
+    pub right: BorderSide,
+    pub bottom: BorderSide,
+}
+
+impl Border {
+    pub fn new(left: BorderSide, top: BorderSide, right: BorderSide, bottom: BorderSide) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    pub fn all(width: f32, color: Color) -> Self {
+        let side = BorderSide::new(width, color);
+        Self::new(side, side, side, side)
+    }
+
+    pub fn with_left(mut self, side: BorderSide) -> Self {
+        self.left = side;
+        self
+    }
+
+    pub fn with_top(mut self, side: BorderSide) -> Self {
+        self.top = side;
+        self
+    }
+
+    pub fn with_right(mut self, side: BorderSide) -> Self {
+        self.right = side;
+        self
+    }
+
+    pub fn with_bottom(mut self, side: BorderSide) -> Self {
+        self.bottom = side;
+        self
+    }
+
+    pub fn widths(&self) -> EdgeInsets {
+        EdgeInsets::new(
+            self.left.width,
+            self.top.width,
+            self.right.width,
+            self.bottom.width,
+        )
+    }
+
+    pub fn is_uniform(&self) -> bool {
+        let sides = [self.top, self.right, self.bottom];
+        sides
+            .iter()
+            .all(|side| side.width == self.left.width && side.color == self.left.color)
+    }
+
+    // Paints the border inside the outer rect. Uniform borders are drawn as a single ring,
+    // otherwise every side is drawn separately, clipped to its own trapezoid so corners are mitred.
+    pub fn paint(&self, canvas: &mut dyn Canvas2D, rect: &Rect, radius: &BorderRadius) {
+        let widths = self.widths();
+        if widths == EdgeInsets::zero() {
+            return;
+        }
+
+        let outer = radius.to_rrect(rect);
+        let inner = radius.inner_rrect(rect, &widths);
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+
+        if self.is_uniform() {
+            paint.set_color(self.left.color);
+            canvas.draw_drrect(&outer, &inner, &paint);
+            return;
+        }
+
+        let inner_rect = widths.deflate_rect(rect);
+        let corners = [
+            Point::new(rect.left, rect.top),
+            Point::new(rect.right, rect.top),
+            Point::new(rect.right, rect.bottom),
+            Point::new(rect.left, rect.bottom),
+        ];
+        let inner_corners = [
+            Point::new(inner_rect.left, inner_rect.top),
+            Point::new(inner_rect.right, inner_rect.top),
+            Point::new(inner_rect.right, inner_rect.bottom),
+            Point::new(inner_rect.left, inner_rect.bottom),
+        ];
+
+        // Sides in clockwise order starting at the top, each spanning two consecutive corners.
+        let sides = [self.top, self.right, self.bottom, self.left];
+        for (index, side) in sides.iter().enumerate() {
+            if side.width <= 0.0 {
+                continue;
+            }
+
+            let next = (index + 1) % 4;
+            let clip = Path::polygon(
+                &[
+                    corners[index],
+                    corners[next],
+                    inner_corners[next],
+                    inner_corners[index],
+                ],
+                true,
+                None,
+                None,
+            );
+
+            paint.set_color(side.color);
+            canvas.save();
+            canvas.clip_path(&clip);
+            canvas.draw_drrect(&outer, &inner, &paint);
+            canvas.restore();
+        }
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct BorderRadius {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_right: f32,
+    pub bottom_left: f32,
+}
+
+impl BorderRadius {
+    pub fn new(top_left: f32, top_right: f32, bottom_right: f32, bottom_left: f32) -> Self {
+        Self {
+            top_left,
+            top_right,
+            bottom_right,
+            bottom_left,
+        }
+    }
+
+    pub fn all(radius: f32) -> Self {
+        Self::new(radius, radius, radius, radius)
+    }
+
+    pub fn top(radius: f32) -> Self {
+        Self::new(radius, radius, 0.0, 0.0)
+    }
+
+    pub fn bottom(radius: f32) -> Self {
+        Self::new(0.0, 0.0, radius, radius)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn to_rrect(&self, rect: &Rect) -> RRect {
+        RRect::new_rect_radii(
+            rect,
+            &[
+                Vector::new(self.top_left, self.top_left),
+                Vector::new(self.top_right, self.top_right),
+                Vector::new(self.bottom_right, self.bottom_right),
+                Vector::new(self.bottom_left, self.bottom_left),
+            ],
+        )
+    }
+
+    // The rounded rect that remains after insetting by the border widths.
+    // Every corner radius shrinks by the width of the sides adjacent to it.
+    pub fn inner_rrect(&self, rect: &Rect, insets: &EdgeInsets) -> RRect {
+        let inner = insets.deflate_rect(rect);
+        let shrink = |radius: f32, by: f32| (radius - by).max(0.0);
+        RRect::new_rect_radii(
+            inner,
+            &[
+                Vector::new(
+                    shrink(self.top_left, insets.left),
+                    shrink(self.top_left, insets.top),
+                ),
+                Vector::new(
+                    shrink(self.top_right, insets.right),
+                    shrink(self.top_right, insets.top),
+                ),
+                Vector::new(
+                    shrink(self.bottom_right, insets.right),
+                    shrink(self.bottom_right, insets.bottom),
+                ),
+                Vector::new(
+                    shrink(self.bottom_left, insets.left),
+                    shrink(self.bottom_left, insets.bottom),
+                ),
+            ],
+        )
+    }
+}
+
+impl From<f32> for BorderRadius {
+    fn from(radius: f32) -> Self {
+        Self::all(radius)
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct BoxShadow {
+    pub color: Color,
+    pub offset: Point,
+    pub blur_radius: f32,
+    pub spread_radius: f32,
+}
+
+impl BoxShadow {
+    pub fn new(color: Color, offset: Point, blur_radius: f32) -> Self {
+        Self {
+            color,
+            offset,
+            blur_radius,
+            spread_radius: 0.0,
+        }
+    }
+
+    pub fn with_spread(mut self, spread_radius: f32) -> Self {
+        self.spread_radius = spread_radius;
+        self
+    }
+
+    pub fn paint(&self, canvas: &mut dyn Canvas2D, rect: &Rect, radius: &BorderRadius) {
+        let mut shadow_rect = rect.with_offset(self.offset);
+        shadow_rect.outset((self.spread_radius, self.spread_radius));
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(self.color);
+        if self.blur_radius > 0.0 {
+            // Convert the blur radius to a standard deviation the same way browsers do
+            paint.set_mask_filter(MaskFilter::blur(
+                BlurStyle::Normal,
+                self.blur_radius * 0.5,
+                None,
+            ));
+        }
+
+        canvas.draw_rrect(&radius.to_rrect(&shadow_rect), &paint);
+    }
+}
+
+// Gradient start and end points are relative to the box, (0, 0) is the top left and (1, 1) the bottom right corner.
+#[derive(Clone)]
+pub struct LinearGradient {
+    pub start: Point,
+    pub end: Point,
+    pub colors: Vec<Color>,
+    pub stops: Option<Vec<f32>>,
+}
+
+impl LinearGradient {
+    pub fn new(start: Point, end: Point, colors: Vec<Color>) -> Self {
+        Self {
+            start,
+            end,
+            colors,
+            stops: None,
+        }
+    }
+
+    pub fn vertical(colors: Vec<Color>) -> Self {
+        Self::new(Point::new(0.0, 0.0), Point::new(0.0, 1.0), colors)
+    }
+
+    pub fn horizontal(colors: Vec<Color>) -> Self {
+        Self::new(Point::new(0.0, 0.0), Point::new(1.0, 0.0), colors)
+    }
+
+    pub fn with_stops(mut self, stops: Vec<f32>) -> Self {
+        self.stops = Some(stops);
+        self
+    }
+
+    fn shader(&self, rect: &Rect) -> Option<Shader> {
+        let resolve = |p: &Point| {
+            Point::new(
+                rect.left + p.x * rect.width(),
+                rect.top + p.y * rect.height(),
+            )
+        };
+        Shader::linear_gradient(
+            (resolve(&self.start), resolve(&self.end)),
+            self.colors.as_slice(),
+            self.stops.as_deref(),
+            TileMode::Clamp,
+            None,
+            None,
+        )
+    }
+}
+
+// Center and radius are relative to the box, the radius is a fraction of the shortest side.
+#[derive(Clone)]
+pub struct RadialGradient {
+    pub center: Point,
+    pub radius: f32,
+    pub colors: Vec<Color>,
+    pub stops: Option<Vec<f32>>,
+}
+
+impl RadialGradient {
+    pub fn new(colors: Vec<Color>) -> Self {
+        Self {
+            center: Point::new(0.5, 0.5),
+            radius: 0.5,
+            colors,
+            stops: None,
+        }
+    }
+
+    pub fn with_center(mut self, center: Point) -> Self {
+        self.center = center;
+        self
+    }
+
+    pub fn with_radius(mut self, radius: f32) -> Self {
+        self.radius = radius;
+        self
+    }
+
+    pub fn with_stops(mut self, stops: Vec<f32>) -> Self {
+        self.stops = Some(stops);
+        self
+    }
+
+    fn shader(&self, rect: &Rect) -> Option<Shader> {
+        let center = Point::new(
+            rect.left + self.center.x * rect.width(),
+            rect.top + self.center.y * rect.height(),
+        );
+        Shader::radial_gradient(
+            center,
+            self.radius * rect.width().min(rect.height()),
+            self.colors.as_slice(),
+            self.stops.as_deref(),
+            TileMode::Clamp,
+            None,
+            None,
+        )
+    }
+}
+
+#[derive(Clone)]
+pub enum Background {
+    Color(Color),
+    LinearGradient(LinearGradient),
+    RadialGradient(RadialGradient),
+    // The image is stretched to fill the box
+    Image(Image),
+}
+
+impl Background {
+    pub fn paint(&self, canvas: &mut dyn Canvas2D, rect: &Rect, radius: &BorderRadius) {
+        let rrect = radius.to_rrect(rect);
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+
+        match self {
+            Background::Color(color) => {
+                paint.set_color(*color);
+                canvas.draw_rrect(&rrect, &paint);
+            }
+            Background::LinearGradient(gradient) => fill_gradient(
+                canvas,
+                &rrect,
+                paint,
+                gradient.shader(rect),
+                &gradient.colors,
+            ),
+            Background::RadialGradient(gradient) => fill_gradient(
+                canvas,
+                &rrect,
+                paint,
+                gradient.shader(rect),
+                &gradient.colors,
+            ),
+            Background::Image(image) => {
+                canvas.save();
+                canvas.clip_rrect(&rrect);
+                canvas.draw_image_rect(image, rect, &paint);
+                canvas.restore();
+            }
+        }
+    }
+}
+
+// Skia makes no shader for fewer than two colors, or stops that don't match the colors. A single color
+// is filled in plainly, anything else is left out instead of painting the paint's default black.
+fn fill_gradient(
+    canvas: &mut dyn Canvas2D,
+    rrect: &RRect,
+    mut paint: Paint,
+    shader: Option<Shader>,
+    colors: &[Color],
+) {
+    match (shader, colors) {
+        (Some(shader), _) => {
+            paint.set_shader(shader);
+        }
+        (None, [color]) => {
+            paint.set_color(*color);
+        }
+        (None, _) => return,
+    }
+    canvas.draw_rrect(rrect, &paint)
+}
+
+impl From<Color> for Background {
+    fn from(color: Color) -> Self {
+        Self::Color(color)
+    }
+}
+
+impl From<LinearGradient> for Background {
+    fn from(gradient: LinearGradient) -> Self {
+        Self::LinearGradient(gradient)
+    }
+}
+
+impl From<RadialGradient> for Background {
+    fn from(gradient: RadialGradient) -> Self {
+        Self::RadialGradient(gradient)
+    }
+}
+
+impl From<Image> for Background {
+    fn from(image: Image) -> Self {
+        Self::Image(image)
+    }
+}
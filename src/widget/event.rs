@@ -1,8 +1,58 @@
-use crate::{canvas::Point, window};
+use crate::{app::TimerId, canvas::Point, widget::accessibility::AccessActionRequest, window};
+use std::{any::Any, rc::Rc};
 
 pub enum Event {
     Mouse(MouseEvent),
+    // Only seen by the focused widget and the containers above it, see EventCtx::request_focus
     Key(KeyEvent),
+    // The widget gained or lost keyboard focus
+    Focus(bool),
+    // A timer started with EventCtx::start_timer expired. Every widget in the window sees it,
+    // compare the id with the one that was returned when starting the timer.
+    Timer(TimerId),
+    // Delivered to every widget in the window as well
+    Window(window::WindowEvent),
+    // Only seen by the widget with the command's key
+    Command(Command),
+    // Only seen by the widget whose slot uid is the request's target
+    Access(AccessActionRequest),
+}
+
+// A request for one widget in a window, addressed by the key it was given with WidgetExt::with_key.
+// The payload is anything the receiving widget knows how to handle.
+#[derive(Clone)]
+pub struct Command {
+    key: Option<String>,
+    payload: Rc<dyn Any>,
+}
+
+impl Command {
+    pub fn new(key: &str, payload: impl Any) -> Self {
+        Self {
+            key: Some(key.to_string()),
+            payload: Rc::new(payload),
+        }
+    }
+
+    pub fn get<T: Any>(&self) -> Option<&T> {
+        self.payload.downcast_ref()
+    }
+
+    pub fn is<T: Any>(&self) -> bool {
+        self.payload.is::<T>()
+    }
+
+    pub(crate) fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    // The copy handed to the target, it isn't passed on to the target's children
+    pub(crate) fn arrived(&self) -> Self {
+        Self {
+            key: None,
+            payload: self.payload.clone(),
+        }
+    }
 }
 
 pub enum MouseEvent {
@@ -41,4 +91,12 @@ impl MouseEvent {
 pub enum KeyEvent {
     Input(winit::event::KeyboardInput),
     Char(char),
+    // Text an input method is composing, to be shown at the caret until it's committed or cleared.
+    // The cursor is a byte range in the text, None hides the caret.
+    Preedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    // Text an input method finished composing, to be inserted at the caret
+    Commit(String),
 }
@@ -6,6 +6,7 @@ use crate::{
 };
 
 use super::LayoutCtx;
+use std::any::Any;
 
 pub struct Expanded<State> {
     child: ChildSlot<State>,
@@ -70,6 +71,23 @@ impl<State: AppState> Widget<State> for Expanded<State> {
         self.child.paint(theme, ctx, canvas, state)
     }
 
+    fn children_mut(&mut self) -> Vec<&mut ChildSlot<State>> {
+        vec![&mut self.child]
+    }
+
+    fn children(&self) -> Vec<&ChildSlot<State>> {
+        vec![&self.child]
+    }
+
+    fn take_layout_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new((self.width, self.height, self.flex)))
+    }
+
+    fn restore_layout_state(&mut self, layout: Box<dyn Any>) -> bool {
+        layout.downcast_ref::<(Option<f32>, Option<f32>, f32)>()
+            == Some(&(self.width, self.height, self.flex))
+    }
+
     fn flex(&self) -> f32 {
         self.flex
     }
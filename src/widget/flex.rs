@@ -2,11 +2,17 @@ use crate::{
     app::AppState,
     canvas::{Canvas2D, Point, Size},
     constraints::BoxConstraints,
-    widget::{style::Theme, ChildSlot, Event, EventCtx, PaintCtx, Widget},
+    widget::{
+        accessibility::{AccessNode, Role},
+        style::Theme,
+        ChildSlot, Event, EventCtx, PaintCtx, Widget,
+    },
 };
 
 use super::LayoutCtx;
+use std::any::Any;
 
+#[derive(Clone, Copy, PartialEq)]
 enum Direction {
     Horizontal,
     Vertical,
@@ -169,6 +175,26 @@ impl<State: AppState> Widget<State> for Flex<State> {
     fn flex(&self) -> f32 {
         0.0
     }
+
+    fn children_mut(&mut self) -> Vec<&mut ChildSlot<State>> {
+        self.children.iter_mut().collect()
+    }
+
+    fn children(&self) -> Vec<&ChildSlot<State>> {
+        self.children.iter().collect()
+    }
+
+    fn take_layout_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new((self.direction, self.spacing)))
+    }
+
+    fn restore_layout_state(&mut self, layout: Box<dyn Any>) -> bool {
+        layout.downcast_ref::<(Direction, f32)>() == Some(&(self.direction, self.spacing))
+    }
+
+    fn accessibility(&self, _: &State) -> Option<AccessNode> {
+        Some(AccessNode::new(Role::Group))
+    }
 }
 
 impl<State: AppState> Default for Flex<State> {
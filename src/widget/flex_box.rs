@@ -6,6 +6,7 @@ use crate::{
 };
 
 use super::LayoutCtx;
+use std::any::Any;
 
 pub struct FlexBox<State> {
     child: ChildSlot<State>,
@@ -26,6 +27,22 @@ impl<State: AppState> Widget<State> for FlexBox<State> {
         self.child.paint(theme, ctx, canvas, state)
     }
 
+    fn children_mut(&mut self) -> Vec<&mut ChildSlot<State>> {
+        vec![&mut self.child]
+    }
+
+    fn children(&self) -> Vec<&ChildSlot<State>> {
+        vec![&self.child]
+    }
+
+    fn take_layout_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new(self.flex))
+    }
+
+    fn restore_layout_state(&mut self, layout: Box<dyn Any>) -> bool {
+        layout.downcast_ref::<f32>() == Some(&self.flex)
+    }
+
     fn flex(&self) -> f32 {
         self.flex
     }
@@ -0,0 +1,381 @@
+use crate::{
+    app::AppState,
+    canvas::{svg::Dom, Canvas2D, Color, Rect, Size},
+    constraints::BoxConstraints,
+    widget::{
+        accessibility::{AccessNode, Role},
+        style::{IconStyle, Theme},
+        Event, EventCtx, PaintCtx, Widget,
+    },
+};
+
+use super::LayoutCtx;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+use thiserror::Error;
+
+// Parsed documents are kept per color, the cache starts over when it gets this big
+const CACHE_SIZE: usize = 256;
+
+#[derive(Debug, Error)]
+pub enum IconError {
+    #[error("failed to read icon file {0:?}: {1}")]
+    Io(PathBuf, std::io::Error),
+    #[error("\"{0}\" is not an SVG document skia can read")]
+    InvalidSvg(String),
+}
+
+// SVG documents by name, shared by all themes. Register them with StyleContext::register_icon.
+#[derive(Default)]
+pub struct Icons {
+    sources: HashMap<String, Rc<str>>,
+    cache: RefCell<HashMap<(DocumentKey, [u8; 4]), Option<Dom>>>,
+}
+
+// Identifies a document by its address instead of its contents, so finding it in the cache doesn't
+// hash the whole document. The cache holds on to it, so the address can't be reused while it's in there.
+#[derive(Clone)]
+struct DocumentKey(Rc<str>);
+
+impl PartialEq for DocumentKey {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for DocumentKey {}
+
+impl Hash for DocumentKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (Rc::as_ptr(&self.0) as *const u8).hash(state)
+    }
+}
+
+impl Icons {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Replaces the icon with the same name
+    pub fn register(&mut self, name: &str, svg: &str) -> Result<(), IconError> {
+        let source: Rc<str> = Rc::from(svg);
+        if document(&source, Color::BLACK).is_none() {
+            return Err(IconError::InvalidSvg(name.to_string()));
+        }
+
+        self.sources.insert(name.to_string(), source);
+        self.cache.borrow_mut().clear();
+        Ok(())
+    }
+
+    pub fn register_file(&mut self, name: &str, path: impl AsRef<Path>) -> Result<(), IconError> {
+        let path = path.as_ref();
+        let svg = std::fs::read_to_string(path)
+            .map_err(|error| IconError::Io(path.to_path_buf(), error))?;
+        self.register(name, &svg)
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.sources.contains_key(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sources.keys().map(|name| name.as_str())
+    }
+
+    fn source(&self, icon: &IconSource) -> Option<Rc<str>> {
+        match icon {
+            IconSource::Name(name) => self.sources.get(name).cloned(),
+            IconSource::Svg(svg) => Some(svg.clone()),
+        }
+    }
+
+    // Scales the document to the rect. currentColor, and everything without a fill of its own, is
+    // painted in the color.
+    pub(crate) fn draw(
+        &self,
+        canvas: &mut dyn Canvas2D,
+        icon: &IconSource,
+        rect: &Rect,
+        color: Color,
+    ) {
+        let source = match self.source(icon) {
+            Some(source) => source,
+            None => return,
+        };
+
+        let mut cache = self.cache.borrow_mut();
+        if cache.len() > CACHE_SIZE {
+            cache.clear()
+        }
+        let dom = cache
+            .entry((
+                DocumentKey(source.clone()),
+                [color.a(), color.r(), color.g(), color.b()],
+            ))
+            .or_insert_with(|| document(&source, color));
+        if let Some(dom) = dom {
+            canvas.draw_svg(dom, rect)
+        }
+    }
+}
+
+// A registered icon, or an SVG document of its own
+#[derive(Clone, Debug)]
+pub enum IconSource {
+    Name(String),
+    Svg(Rc<str>),
+}
+
+impl From<&str> for IconSource {
+    fn from(name: &str) -> Self {
+        Self::Name(name.to_string())
+    }
+}
+
+// Where the closing bracket of the markup is, skipping the ones in quoted values and, for a DOCTYPE,
+// in its internal subset
+fn markup_end(markup: &str) -> Option<usize> {
+    let mut quote = None;
+    let mut depth = 0;
+    for (index, char) in markup.char_indices() {
+        match (quote, char) {
+            (Some(open), _) if char == open => quote = None,
+            (Some(_), _) => (),
+            (None, '"' | '\'') => quote = Some(char),
+            (None, '[') => depth += 1,
+            (None, ']') => depth -= 1,
+            (None, '>') if depth == 0 => return Some(index),
+            _ => (),
+        }
+    }
+    None
+}
+
+// The start of the root element and its closing bracket. Comments, processing instructions and the
+// DOCTYPE before it are skipped, None when the root isn't an svg element.
+fn root_element(svg: &str) -> Option<(usize, usize)> {
+    let mut position = 0;
+    loop {
+        let start = position + svg[position..].find('<')?;
+        let markup = &svg[start..];
+        position = if markup.starts_with("<!--") {
+            start + 4 + markup[4..].find("-->")? + 3
+        } else if markup.starts_with("<?") {
+            start + markup.find("?>")? + 2
+        } else if markup.starts_with("<!") {
+            start + markup_end(markup)? + 1
+        } else {
+            let name_end = markup[1..]
+                .find(|char: char| char.is_whitespace() || char == '/' || char == '>')?
+                + 1;
+            return match &markup[1..name_end] {
+                "svg" => Some((start, start + markup_end(markup)?)),
+                _ => None,
+            };
+        };
+    }
+}
+
+// The attributes of a tag, the part between the element name and the closing bracket
+fn attributes(tag: &str) -> Vec<(&str, &str)> {
+    let mut attributes = Vec::new();
+    let mut rest = tag;
+    while let Some(equals) = rest.find('=') {
+        let name = rest[..equals].trim();
+        let value = rest[equals + 1..].trim_start();
+        let quote = match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => break,
+        };
+        let end = match value[1..].find(quote) {
+            Some(end) => end + 1,
+            None => break,
+        };
+        attributes.push((name, &value[1..end]));
+        rest = &value[end + 1..];
+    }
+    attributes
+}
+
+fn parse_length(value: &str) -> Option<f32> {
+    value.trim().trim_end_matches("px").parse().ok()
+}
+
+fn document(svg: &str, color: Color) -> Option<Dom> {
+    Dom::from_bytes(rewrite_root(svg, color)?.as_bytes()).ok()
+}
+
+// Rewrites the root element so the document scales to whatever size it's drawn at. The color is set
+// on the root, that's what currentColor refers to.
+fn rewrite_root(svg: &str, color: Color) -> Option<String> {
+    let (start, end) = root_element(svg)?;
+    let tag = svg[start + 4..end].trim_end_matches('/');
+
+    let mut root = String::from("<svg");
+    let (mut width, mut height) = (None, None);
+    let (mut has_view_box, mut has_fill) = (false, false);
+    for (name, value) in attributes(tag) {
+        match name {
+            // Without them the document takes the size of the container
+            "width" => width = parse_length(value),
+            "height" => height = parse_length(value),
+            "color" => (),
+            _ => {
+                has_view_box |= name == "viewBox";
+                has_fill |= name == "fill";
+                let quote = if value.contains('"') { '\'' } else { '"' };
+                root.push_str(&format!(" {}={}{}{}", name, quote, value, quote));
+            }
+        }
+    }
+    root.push_str(&format!(
+        " color=\"#{:02X}{:02X}{:02X}\"",
+        color.r(),
+        color.g(),
+        color.b()
+    ));
+    if let (false, Some(width), Some(height)) = (has_view_box, width, height) {
+        root.push_str(&format!(" viewBox=\"0 0 {} {}\"", width, height));
+    }
+    if !has_fill {
+        root.push_str(" fill=\"currentColor\"");
+    }
+    if color.a() < 255 {
+        root.push_str(&format!(" opacity=\"{}\"", color.a() as f32 / 255.0));
+    }
+    if svg[start..end].ends_with('/') {
+        root.push('/');
+    }
+
+    Some([&svg[..start], &root, &svg[end..]].concat())
+}
+
+// An SVG icon, crisp at any size. It's painted in the icon color of the theme unless it's given one.
+pub struct Icon {
+    icon: IconSource,
+    size: f32,
+    color: Option<Color>,
+    description: Option<String>,
+}
+
+impl Icon {
+    // A registered icon
+    pub fn new(name: &str) -> Self {
+        Self::from_source(IconSource::from(name))
+    }
+
+    // A document that isn't registered
+    pub fn from_svg(svg: &str) -> Self {
+        Self::from_source(IconSource::Svg(Rc::from(svg)))
+    }
+
+    fn from_source(icon: IconSource) -> Self {
+        Self {
+            icon,
+            size: 20.0,
+            color: None,
+            description: None,
+        }
+    }
+
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    // Read out by assistive technology, icons without one are left out
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+}
+
+impl<State: AppState> Widget<State> for Icon {
+    fn event(&mut self, _: &Event, _: &mut EventCtx<State::Message>, _: &State) -> bool {
+        false
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, _: &mut LayoutCtx, _: &State) -> Size {
+        Size::new(
+            constraints.constrain_width(self.size),
+            constraints.constrain_height(self.size),
+        )
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, _: &State) {
+        let style = ctx.style::<IconStyle>(theme);
+        let color = self.color.unwrap_or(style.color);
+
+        // Centered when the widget was made bigger than the icon
+        let rect = ctx.rect();
+        let size = self.size.min(rect.width()).min(rect.height());
+        let icon = Rect::from_xywh(
+            rect.center_x() - size / 2.0,
+            rect.center_y() - size / 2.0,
+            size,
+            size,
+        );
+        if let Some(icons) = ctx.icons() {
+            icons.draw(canvas, &self.icon, &icon, color)
+        }
+    }
+
+    fn accessibility(&self, _: &State) -> Option<AccessNode> {
+        let description = self.description.as_ref()?;
+        Some(AccessNode::new(Role::Image).with_name(description))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Color = Color::new(0xFFFF0000);
+
+    #[test]
+    fn skips_what_comes_before_the_root() {
+        let svg = r#"<?xml version="1.0"?>
+<!-- an <svg> in a comment -->
+<!DOCTYPE svg [ <!ENTITY arrow ">"> ]>
+<svg width="24" height="24"><path d="M0 0"/></svg>"#;
+        let (start, end) = root_element(svg).unwrap();
+        assert_eq!(&svg[start..=end], r#"<svg width="24" height="24">"#);
+    }
+
+    #[test]
+    fn brackets_in_values_dont_end_the_root() {
+        let svg = r#"<svg data-note="a > b" viewBox="0 0 24 24"/>"#;
+        assert_eq!(root_element(svg), Some((0, svg.len() - 1)));
+        assert_eq!(
+            rewrite_root(svg, RED).unwrap(),
+            r##"<svg data-note="a > b" viewBox="0 0 24 24" color="#FF0000" fill="currentColor"/>"##
+        );
+    }
+
+    #[test]
+    fn other_roots_are_rejected() {
+        assert_eq!(root_element("<svgs/>"), None);
+        assert_eq!(root_element("<html><svg/></html>"), None);
+        assert_eq!(root_element("<!-- <svg/> -->"), None);
+    }
+
+    #[test]
+    fn sizes_the_document_and_sets_its_color() {
+        let svg = r#"<svg width="24px" height="24" color="blue" fill='none'><text>currentColor</text></svg>"#;
+        assert_eq!(
+            rewrite_root(svg, RED.with_a(51)).unwrap(),
+            r##"<svg fill="none" color="#FF0000" viewBox="0 0 24 24" opacity="0.2"><text>currentColor</text></svg>"##
+        );
+    }
+}
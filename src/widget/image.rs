@@ -0,0 +1,219 @@
+use crate::{
+    app::{AppState, ImageSource, ImageStatus},
+    canvas::{Canvas2D, Color, Paint, Rect, Size},
+    constraints::BoxConstraints,
+    widget::{
+        accessibility::{AccessNode, Role},
+        decoration::BorderRadius,
+        style::{ImageStyle, Theme},
+        Event, EventCtx, PaintCtx, Widget,
+    },
+};
+
+use super::LayoutCtx;
+use skia_safe::{color_filters, BlendMode};
+
+// How the image is sized to the widget
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BoxFit {
+    // As large as possible while still showing all of it
+    Contain,
+    // Fills the widget, the parts that stick out are cut off
+    Cover,
+    // Stretched to the size of the widget, ignoring its aspect ratio
+    Fill,
+    // At its own size
+    None,
+    // Like Contain, but never larger than its own size
+    ScaleDown,
+}
+
+// Where the image sits when it doesn't match the size of the widget.
+// -1 is the left or top edge, 1 the right or bottom edge.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Alignment {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Alignment {
+    pub const TOP_LEFT: Self = Self::new(-1.0, -1.0);
+    pub const TOP_CENTER: Self = Self::new(0.0, -1.0);
+    pub const TOP_RIGHT: Self = Self::new(1.0, -1.0);
+    pub const CENTER_LEFT: Self = Self::new(-1.0, 0.0);
+    pub const CENTER: Self = Self::new(0.0, 0.0);
+    pub const CENTER_RIGHT: Self = Self::new(1.0, 0.0);
+    pub const BOTTOM_LEFT: Self = Self::new(-1.0, 1.0);
+    pub const BOTTOM_CENTER: Self = Self::new(0.0, 1.0);
+    pub const BOTTOM_RIGHT: Self = Self::new(1.0, 1.0);
+
+    pub const fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    // Places a rect of the size inside the bounds
+    fn inscribe(&self, size: Size, bounds: &Rect) -> Rect {
+        let left = bounds.left + (bounds.width() - size.width) * (self.x + 1.0) / 2.0;
+        let top = bounds.top + (bounds.height() - size.height) * (self.y + 1.0) / 2.0;
+        Rect::from_xywh(left, top, size.width, size.height)
+    }
+}
+
+impl Default for Alignment {
+    fn default() -> Self {
+        Self::CENTER
+    }
+}
+
+impl BoxFit {
+    fn apply(&self, image: Size, bounds: Size) -> Size {
+        let contain = (bounds.width / image.width).min(bounds.height / image.height);
+        let scale = match self {
+            BoxFit::Fill => return bounds,
+            BoxFit::None => 1.0,
+            BoxFit::Contain => contain,
+            BoxFit::Cover => (bounds.width / image.width).max(bounds.height / image.height),
+            BoxFit::ScaleDown => contain.min(1.0),
+        };
+        Size::new(image.width * scale, image.height * scale)
+    }
+}
+
+// Shows a picture from a file, encoded bytes or the image cache of the app.
+// Big files are decoded on a worker thread, the theme's placeholder color shows until they're done.
+pub struct Image {
+    source: ImageSource,
+    fit: BoxFit,
+    alignment: Alignment,
+    tint: Option<Color>,
+    radius: BorderRadius,
+    description: Option<String>,
+    // From the last layout
+    status: ImageStatus,
+}
+
+impl Image {
+    pub fn new(source: impl Into<ImageSource>) -> Self {
+        Self {
+            source: source.into(),
+            fit: BoxFit::Contain,
+            alignment: Alignment::CENTER,
+            tint: None,
+            radius: BorderRadius::default(),
+            description: None,
+            status: ImageStatus::Loading,
+        }
+    }
+
+    // Encoded PNG, JPEG, WebP or GIF data, cached under the key. GIFs only show their first frame.
+    pub fn from_bytes(key: &str, bytes: &[u8]) -> Self {
+        Self::new(ImageSource::bytes(key, bytes))
+    }
+
+    // An image put in the cache with Images::insert
+    pub fn from_key(key: &str) -> Self {
+        Self::new(ImageSource::key(key))
+    }
+
+    pub fn with_fit(mut self, fit: BoxFit) -> Self {
+        self.fit = fit;
+        self
+    }
+
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    // Paints every opaque pixel in the color, for single color pictures like logos
+    pub fn with_tint(mut self, color: Color) -> Self {
+        self.tint = Some(color);
+        self
+    }
+
+    // Rounds the corners of the widget, the image is clipped to them
+    pub fn with_corner_radius(mut self, radius: impl Into<BorderRadius>) -> Self {
+        self.radius = radius.into();
+        self
+    }
+
+    // Read out by assistive technology, images without one are left out
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    fn image_size(&self) -> Option<Size> {
+        match &self.status {
+            ImageStatus::Ready(image) => {
+                Some(Size::new(image.width() as f32, image.height() as f32))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<State: AppState> Widget<State> for Image {
+    fn event(&mut self, _: &Event, _: &mut EventCtx<State::Message>, _: &State) -> bool {
+        false
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, _: &State) -> Size {
+        // A failed image keeps showing the placeholder, Images::load tells why it failed
+        self.status = ctx.load_image(&self.source);
+
+        // Takes the space it's given, or keeps the aspect ratio of the image when only one side is bounded
+        let image = self.image_size().unwrap_or_default();
+        match (constraints.max_width(), constraints.max_height()) {
+            (Some(width), Some(height)) => Size::new(width, height),
+            (Some(width), None) if image.width > 0.0 => Size::new(
+                width,
+                constraints.constrain_height(image.height * width / image.width),
+            ),
+            (None, Some(height)) if image.height > 0.0 => Size::new(
+                constraints.constrain_width(image.width * height / image.height),
+                height,
+            ),
+            (width, height) => Size::new(
+                width.unwrap_or_else(|| constraints.constrain_width(image.width)),
+                height.unwrap_or_else(|| constraints.constrain_height(image.height)),
+            ),
+        }
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, _: &State) {
+        let rect = ctx.rect();
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+
+        canvas.save();
+        if self.radius.is_zero() {
+            canvas.clip_rect(&rect);
+        } else {
+            canvas.clip_rrect(&self.radius.to_rrect(&rect));
+        }
+
+        match (&self.status, self.image_size()) {
+            (ImageStatus::Ready(image), Some(size)) => {
+                let target = self
+                    .alignment
+                    .inscribe(self.fit.apply(size, rect.size()), &rect);
+                if let Some(tint) = self.tint {
+                    paint.set_color_filter(color_filters::blend(tint, BlendMode::SrcIn));
+                }
+                canvas.draw_image_rect(image, &target, &paint);
+            }
+            _ => {
+                let style = ctx.style::<ImageStyle>(theme);
+                paint.set_color(style.placeholder);
+                canvas.draw_rect(&rect, &paint);
+            }
+        }
+        canvas.restore();
+    }
+
+    fn accessibility(&self, _: &State) -> Option<AccessNode> {
+        let description = self.description.as_ref()?;
+        Some(AccessNode::new(Role::Image).with_name(description))
+    }
+}
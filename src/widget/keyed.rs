@@ -0,0 +1,134 @@
+use crate::{
+    app::AppState,
+    canvas::{Canvas2D, Rect, Size},
+    constraints::BoxConstraints,
+    widget::{
+        accessibility::AccessNode, ChildSlot, Event, EventCtx, LayoutCtx, PaintCtx, Theme, Widget,
+    },
+};
+use std::any::Any;
+
+// Gives a widget a stable identity. Everything except the key is forwarded to the wrapped widget.
+pub struct Keyed<W> {
+    key: String,
+    widget: W,
+}
+
+impl<W> Keyed<W> {
+    pub fn new(key: &str, widget: W) -> Self {
+        Self {
+            key: key.to_string(),
+            widget,
+        }
+    }
+}
+
+impl<State: AppState, W: Widget<State>> Widget<State> for Keyed<W> {
+    fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, state: &State) -> bool {
+        self.widget.event(event, ctx, state)
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, state: &State) -> Size {
+        self.widget.layout(constraints, ctx, state)
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
+        self.widget.paint(theme, ctx, canvas, state)
+    }
+
+    fn paint_overlay(
+        &self,
+        theme: &Theme,
+        ctx: &PaintCtx,
+        canvas: &mut dyn Canvas2D,
+        state: &State,
+    ) {
+        self.widget.paint_overlay(theme, ctx, canvas, state)
+    }
+
+    fn uid(&self) -> usize {
+        self.widget.uid()
+    }
+
+    fn flex(&self) -> f32 {
+        self.widget.flex()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut ChildSlot<State>> {
+        self.widget.children_mut()
+    }
+
+    fn children(&self) -> Vec<&ChildSlot<State>> {
+        self.widget.children()
+    }
+
+    fn key(&self) -> Option<&str> {
+        Some(&self.key)
+    }
+
+    fn type_name(&self) -> &'static str {
+        self.widget.type_name()
+    }
+
+    fn caret_rect(&self, size: &Size, state: &State) -> Option<Rect> {
+        self.widget.caret_rect(size, state)
+    }
+
+    fn accessibility(&self, state: &State) -> Option<AccessNode> {
+        self.widget.accessibility(state)
+    }
+
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        self.widget.take_local_state()
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        self.widget.restore_local_state(local_state)
+    }
+
+    fn take_layout_state(&mut self) -> Option<Box<dyn Any>> {
+        self.widget.take_layout_state()
+    }
+
+    fn restore_layout_state(&mut self, layout: Box<dyn Any>) -> bool {
+        self.widget.restore_layout_state(layout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        app::MessageCtx,
+        widget::{text_editor::TextBox, Properties, WidgetExt},
+    };
+    use std::sync::mpsc;
+
+    struct State;
+
+    impl AppState for State {
+        type Message = ();
+
+        fn handle_message(&mut self, _: Self::Message, _: &mut MessageCtx<Self>) {}
+    }
+
+    // The window only accepts input methods while the focused widget reports a caret
+    fn reports_caret(widget: &mut dyn Widget<State>) -> bool {
+        let properties = Properties::default();
+        let mut ctx = EventCtx::headless(&properties, mpsc::channel().0);
+        widget.event(&Event::Focus(true), &mut ctx, &State);
+        widget.caret_rect(&Size::new(200.0, 30.0), &State).is_some()
+    }
+
+    #[test]
+    fn wrapped_text_box_reports_its_caret() {
+        assert!(reports_caret(&mut TextBox::new("Notes")));
+        assert!(reports_caret(&mut TextBox::new("Notes").with_key("notes")));
+        assert!(reports_caret(
+            &mut TextBox::new("Notes").with_class("notes")
+        ));
+        assert!(reports_caret(
+            &mut TextBox::new("Notes").with_key("notes").with_class("notes")
+        ));
+    }
+}
@@ -0,0 +1,523 @@
+use crate::{
+    app::{AppState, Fonts},
+    canvas::{
+        textlayout::{
+            Paragraph, ParagraphBuilder, ParagraphStyle, RectHeightStyle, RectWidthStyle,
+            TextAlign, TextDecoration, TextStyle,
+        },
+        Canvas2D, Color, FontStyle, Paint, Point, Rect, Size,
+    },
+    constraints::BoxConstraints,
+    widget::{
+        accessibility::{AccessNode, Role},
+        style::{LabelStyle, Theme},
+        Event, EventCtx, MouseEvent, PaintCtx, Widget,
+    },
+};
+
+use super::LayoutCtx;
+use std::{any::Any, cell::RefCell, ops::Range};
+use winit::window::CursorIcon;
+
+// What happens to text that doesn't fit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    // Cut off at the edge of the widget
+    Clip,
+    // The last line that fits ends in an ellipsis
+    Ellipsis,
+}
+
+// A run of text in one style. Without a family, color or size of its own it uses the ones of the widget.
+#[derive(Clone)]
+pub struct Span<Message> {
+    text: String,
+    family: Option<String>,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+    color: Option<Color>,
+    size: Option<f32>,
+    link: Option<Message>,
+}
+
+impl<Message> Span<Message> {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            family: None,
+            bold: false,
+            italic: false,
+            underline: false,
+            color: None,
+            size: None,
+            link: None,
+        }
+    }
+
+    // A font family or one of the named families of the theme, like "monospace"
+    pub fn with_family(mut self, family: &str) -> Self {
+        self.family = Some(family.to_string());
+        self
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub fn italic(mut self) -> Self {
+        self.italic = true;
+        self
+    }
+
+    pub fn underlined(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn with_size(mut self, size: f32) -> Self {
+        self.size = Some(size);
+        self
+    }
+
+    // Clicking the span publishes the message. Links are underlined and take the link color of the theme.
+    pub fn with_link(mut self, message: Message) -> Self {
+        self.link = Some(message);
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+#[derive(Default)]
+struct TextState {
+    // The text the selection was made in
+    text: String,
+    // Offsets in utf-16 code units, the way skia counts text
+    anchor: usize,
+    extent: usize,
+    selecting: bool,
+    // The link a click started on
+    pressed_link: Option<usize>,
+}
+
+// Text made of spans in different styles, laid out with skia's paragraph layout
+pub struct RichText<State: AppState> {
+    spans: Vec<Span<State::Message>>,
+    family: Option<String>,
+    // The theme's font size when None
+    font_size: Option<f32>,
+    color: Option<Color>,
+    align: TextAlign,
+    wrap: bool,
+    max_lines: Option<usize>,
+    overflow: Overflow,
+    selectable: bool,
+    on_selection: Option<Box<dyn Fn(&str) -> State::Message>>,
+    state: TextState,
+    // From the last layout, for hit testing. Colors come from the theme, so painting builds its own.
+    paragraph: Option<Paragraph>,
+    layout_width: f32,
+    // Built by paint with the text and link colors it was painted in, dropped on layout
+    painted: RefCell<Option<([Color; 2], Paragraph)>>,
+}
+
+impl<State: AppState> RichText<State> {
+    pub fn new(spans: Vec<Span<State::Message>>) -> Self {
+        Self {
+            spans,
+            family: None,
+            font_size: None,
+            color: None,
+            align: TextAlign::Left,
+            wrap: true,
+            max_lines: None,
+            overflow: Overflow::Clip,
+            selectable: false,
+            on_selection: None,
+            state: TextState::default(),
+            paragraph: None,
+            layout_width: 0.0,
+            painted: RefCell::new(None),
+        }
+    }
+
+    pub fn push(mut self, span: Span<State::Message>) -> Self {
+        self.spans.push(span);
+        self
+    }
+
+    // Without a family the theme's font is used
+    pub fn with_family(mut self, family: &str) -> Self {
+        self.family = Some(family.to_string());
+        self
+    }
+
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    // Without wrapping lines only break at newlines
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn with_max_lines(mut self, lines: usize) -> Self {
+        self.max_lines = Some(lines);
+        self
+    }
+
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    // Lets the user select text with the mouse
+    pub fn selectable(mut self) -> Self {
+        self.selectable = true;
+        self
+    }
+
+    // Publishes the selected text when the user finishes selecting
+    pub fn on_selection<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str) -> State::Message + 'static,
+    {
+        self.selectable = true;
+        self.on_selection = Some(Box::new(f));
+        self
+    }
+
+    pub fn text(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    fn build(&self, style: &LabelStyle, fonts: &Fonts) -> Paragraph {
+        let mut paragraph_style = ParagraphStyle::new();
+        paragraph_style.set_text_align(self.align);
+        // Text that doesn't wrap can only be cut short on its first line
+        let max_lines = match (self.wrap, self.overflow) {
+            (false, Overflow::Ellipsis) => Some(1),
+            _ => self.max_lines,
+        };
+        paragraph_style.set_max_lines(max_lines);
+        if self.overflow == Overflow::Ellipsis {
+            paragraph_style.set_ellipsis("\u{2026}");
+        }
+
+        let mut builder = ParagraphBuilder::new(&paragraph_style, fonts.collection());
+        for span in &self.spans {
+            let family = span.family.as_ref().or(self.family.as_ref());
+            let mut text_style = TextStyle::new();
+            text_style.set_font_families(&fonts.families(family.map(String::as_str)));
+            let size = span.size.or(self.font_size);
+            text_style.set_font_size(size.unwrap_or(fonts.style().size));
+            text_style.set_font_style(match (span.bold, span.italic) {
+                (true, true) => FontStyle::bold_italic(),
+                (true, false) => FontStyle::bold(),
+                (false, true) => FontStyle::italic(),
+                (false, false) => FontStyle::normal(),
+            });
+
+            let color = match (&span.color, &span.link) {
+                (Some(color), _) => *color,
+                (None, Some(_)) => style.link,
+                (None, None) => self.color.unwrap_or(style.text),
+            };
+            text_style.set_color(color);
+            if span.underline || span.link.is_some() {
+                let decoration = text_style.decoration_mut();
+                decoration.ty = TextDecoration::UNDERLINE;
+                decoration.color = color;
+                decoration.thickness_multiplier = 1.0;
+            }
+
+            builder.push_style(&text_style);
+            builder.add_text(&span.text);
+            builder.pop();
+        }
+
+        builder.build()
+    }
+
+    fn selection(&self) -> Range<usize> {
+        self.state.anchor.min(self.state.extent)..self.state.anchor.max(self.state.extent)
+    }
+
+    fn selected_text(&self) -> String {
+        let text: Vec<u16> = self.text().encode_utf16().collect();
+        let selection = self.selection();
+        let end = selection.end.min(text.len());
+        String::from_utf16_lossy(&text[selection.start.min(end)..end])
+    }
+
+    fn position_at(&self, point: &Point) -> usize {
+        self.paragraph.as_ref().map_or(0, |paragraph| {
+            paragraph
+                .get_glyph_position_at_coordinate(*point)
+                .position
+                .max(0) as usize
+        })
+    }
+
+    // The index of the link span under the point
+    fn link_at(&self, point: &Point) -> Option<usize> {
+        let paragraph = self.paragraph.as_ref()?;
+        let mut start = 0;
+        for (index, span) in self.spans.iter().enumerate() {
+            let end = start + span.text.encode_utf16().count();
+            if span.link.is_some() {
+                let hit = paragraph
+                    .get_rects_for_range(start..end, RectHeightStyle::Max, RectWidthStyle::Tight)
+                    .iter()
+                    .any(|textbox| contains(&textbox.rect, point));
+                if hit {
+                    return Some(index);
+                }
+            }
+            start = end;
+        }
+
+        None
+    }
+}
+
+fn contains(rect: &Rect, point: &Point) -> bool {
+    point.x >= rect.left && point.x < rect.right && point.y >= rect.top && point.y < rect.bottom
+}
+
+impl<State: AppState> Widget<State> for RichText<State> {
+    fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, _: &State) -> bool {
+        match event {
+            Event::Mouse(MouseEvent::MouseMove(event)) => {
+                let cursor = if self.link_at(event.local_position()).is_some() {
+                    CursorIcon::Hand
+                } else if self.selectable {
+                    CursorIcon::Text
+                } else {
+                    CursorIcon::Default
+                };
+                ctx.change_cursor(cursor);
+                true
+            }
+            Event::Mouse(MouseEvent::MouseLeave(_)) => {
+                ctx.change_cursor(CursorIcon::Default);
+                true
+            }
+            Event::Mouse(MouseEvent::MouseDown(event)) => {
+                let position = event.local_position();
+                self.state.pressed_link = self.link_at(position);
+                if self.selectable {
+                    let offset = self.position_at(position);
+                    self.state.text = self.text();
+                    self.state.anchor = offset;
+                    self.state.extent = offset;
+                    self.state.selecting = true;
+                    ctx.request_focus();
+                }
+                true
+            }
+            Event::Mouse(MouseEvent::MouseDrag(event)) if self.state.selecting => {
+                self.state.extent = self.position_at(event.local_position());
+                true
+            }
+            Event::Mouse(MouseEvent::MouseUp(event)) => {
+                let position = event.local_position();
+                if std::mem::take(&mut self.state.selecting) && !self.selection().is_empty() {
+                    if let Some(on_selection) = &self.on_selection {
+                        ctx.publish(on_selection(&self.selected_text()))
+                    }
+                }
+
+                // Only a click that starts and ends on the same link follows it
+                if let Some(link) = self.state.pressed_link.take() {
+                    if self.link_at(position) == Some(link) {
+                        if let Some(message) = &self.spans[link].link {
+                            ctx.publish(message.clone())
+                        }
+                    }
+                }
+                true
+            }
+            Event::Focus(false) => {
+                self.state.anchor = 0;
+                self.state.extent = 0;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, _: &State) -> Size {
+        let mut paragraph = self.build(&LabelStyle::default(), ctx.fonts());
+        paragraph.layout(f32::INFINITY);
+        let intrinsic = paragraph.max_intrinsic_width().ceil();
+        let width = constraints.constrain_width(intrinsic);
+
+        // Clipped text that doesn't wrap keeps its lines whole
+        self.layout_width = if !self.wrap && self.overflow == Overflow::Clip {
+            width.max(intrinsic)
+        } else {
+            width
+        };
+        paragraph.layout(self.layout_width);
+        let height = constraints.constrain_height(paragraph.height().ceil());
+        self.paragraph = Some(paragraph);
+        self.painted.take();
+
+        Size::new(width, height)
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, _: &State) {
+        let style = ctx.style::<LabelStyle>(theme);
+        let colors = [style.text, style.link];
+        let mut painted = self.painted.borrow_mut();
+        let paragraph = match &mut *painted {
+            Some((painted_colors, paragraph)) if *painted_colors == colors => paragraph,
+            stale => {
+                let mut paragraph = self.build(&style, ctx.fonts());
+                paragraph.layout(self.layout_width);
+                &mut stale.insert((colors, paragraph)).1
+            }
+        };
+
+        canvas.save();
+        canvas.clip_rect(&ctx.rect());
+
+        let selection = self.selection();
+        if !selection.is_empty() {
+            let mut paint = Paint::default();
+            paint.set_color(style.selection);
+            for textbox in paragraph.get_rects_for_range(
+                selection,
+                RectHeightStyle::Max,
+                RectWidthStyle::Tight,
+            ) {
+                canvas.draw_rect(&textbox.rect, &paint)
+            }
+        }
+
+        canvas.draw_paragraph(&Point::new(0.0, 0.0), paragraph);
+        canvas.restore();
+    }
+
+    fn accessibility(&self, _: &State) -> Option<AccessNode> {
+        Some(AccessNode::new(Role::Label).with_name(&self.text()))
+    }
+
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new(std::mem::take(&mut self.state)))
+    }
+
+    // A selection only survives as long as the text stays the same
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        if let Ok(local_state) = local_state.downcast::<TextState>() {
+            if local_state.text == self.text() {
+                self.state = *local_state
+            }
+        }
+    }
+}
+
+// Text in a single style, see RichText for mixing styles and links
+pub struct Label<State: AppState> {
+    text: RichText<State>,
+}
+
+impl<State: AppState> Label<State> {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: RichText::new(vec![Span::new(text)]),
+        }
+    }
+
+    pub fn with_family(mut self, family: &str) -> Self {
+        self.text = self.text.with_family(family);
+        self
+    }
+
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.text = self.text.with_font_size(size);
+        self
+    }
+
+    // Overrides the text color of the theme
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.text.color = Some(color);
+        self
+    }
+
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.text = self.text.with_align(align);
+        self
+    }
+
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.text = self.text.with_wrap(wrap);
+        self
+    }
+
+    pub fn with_max_lines(mut self, lines: usize) -> Self {
+        self.text = self.text.with_max_lines(lines);
+        self
+    }
+
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.text = self.text.with_overflow(overflow);
+        self
+    }
+
+    pub fn selectable(mut self) -> Self {
+        self.text = self.text.selectable();
+        self
+    }
+
+    pub fn on_selection<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&str) -> State::Message + 'static,
+    {
+        self.text = self.text.on_selection(f);
+        self
+    }
+}
+
+impl<State: AppState> Widget<State> for Label<State> {
+    fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, state: &State) -> bool {
+        self.text.event(event, ctx, state)
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, state: &State) -> Size {
+        self.text.layout(constraints, ctx, state)
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
+        self.text.paint(theme, ctx, canvas, state)
+    }
+
+    fn accessibility(&self, state: &State) -> Option<AccessNode> {
+        self.text.accessibility(state)
+    }
+
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        self.text.take_local_state()
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        self.text.restore_local_state(local_state)
+    }
+}
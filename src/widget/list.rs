@@ -2,10 +2,14 @@ use crate::{
     app::AppState,
     canvas::{Canvas2D, Point, Size},
     constraints::BoxConstraints,
-    widget::{style::Theme, ChildSlot, Event, EventCtx, PaintCtx, Widget},
+    widget::{
+        accessibility::{AccessNode, Role},
+        style::Theme,
+        ChildSlot, Event, EventCtx, PaintCtx, Widget,
+    },
 };
 
-use super::LayoutCtx;
+use super::{child_slot::reconcile_children, LayoutCtx};
 
 pub struct List<State> {
     spacing: f32,
@@ -79,11 +83,18 @@ impl<State: AppState> Widget<State> for List<State> {
 
     fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, state: &State) -> Size {
         if let Some(builder) = &self.builder {
-            self.children.clear();
+            // Items are built from the state, so they have to be rebuilt whenever it changes
+            ctx.depends_on_state();
+            let mut old_children = std::mem::take(&mut self.children);
             for i in 0..self.item_count.unwrap() {
                 self.children
                     .push(ChildSlot::new_with_box(builder(i, state)))
             }
+
+            reconcile_children(
+                self.children.iter_mut().collect(),
+                old_children.iter_mut().collect(),
+            );
         }
 
         let mut y = 0.0;
@@ -115,6 +126,18 @@ impl<State: AppState> Widget<State> for List<State> {
             child.paint(theme, ctx, canvas, state)
         }
     }
+
+    fn children_mut(&mut self) -> Vec<&mut ChildSlot<State>> {
+        self.children.iter_mut().collect()
+    }
+
+    fn children(&self) -> Vec<&ChildSlot<State>> {
+        self.children.iter().collect()
+    }
+
+    fn accessibility(&self, _: &State) -> Option<AccessNode> {
+        Some(AccessNode::new(Role::List))
+    }
 }
 
 impl<State: AppState> Default for List<State> {
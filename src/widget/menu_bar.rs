@@ -0,0 +1,747 @@
+use crate::{
+    app::AppState,
+    canvas::{Canvas2D, Font, FontStyle, Paint, Path, Point, Rect, Size, TextBlob, Typeface},
+    constraints::BoxConstraints,
+    widget::{
+        popup::PopupMenu,
+        style::{MenuBarStyle, Theme},
+        ChildSlot, Event, EventCtx, KeyEvent, MouseEvent, PaintCtx, Widget,
+    },
+    window::WindowEvent,
+};
+
+use super::LayoutCtx;
+use std::any::Any;
+use winit::event::{ElementState, KeyboardInput, VirtualKeyCode};
+
+const PADDING: f32 = 6.0;
+const TITLE_PADDING: f32 = 10.0;
+const MENU_PADDING: f32 = 4.0;
+const CHECK_WIDTH: f32 = 24.0;
+const ARROW_WIDTH: f32 = 20.0;
+const SHORTCUT_GAP: f32 = 32.0;
+const SEPARATOR_HEIGHT: f32 = 9.0;
+
+// Which menus are open, moved to the new bar when the tree is rebuilt
+#[derive(Default)]
+struct MenuState {
+    // Index of the open menu in the bar
+    open: Option<usize>,
+    // The highlighted item of every shown menu. Each menu after the first is the sub menu of the
+    // highlighted item in the one before.
+    levels: Vec<Option<usize>>,
+    // Title under the cursor while no menu is open
+    hovered: Option<usize>,
+    alt_down: bool,
+    // The character typed with a mnemonic isn't passed on
+    mnemonic_pressed: bool,
+}
+
+// A row of menus along the top of the window, with the content below it. Menus open on click or with
+// Alt and their mnemonic, and are navigated with the mouse or the arrow keys. Activating an item
+// publishes the message from on_select with the item's id.
+pub struct MenuBar<State: AppState> {
+    menus: Vec<PopupMenu>,
+    content: ChildSlot<State>,
+    on_select: Option<Box<dyn Fn(usize) -> State::Message>>,
+    font: Font,
+    state: MenuState,
+    size: Size,
+    titles: Vec<Rect>,
+}
+
+impl<State: AppState> MenuBar<State> {
+    pub fn new(menus: Vec<PopupMenu>, content: impl Widget<State> + 'static) -> Self {
+        Self {
+            menus,
+            content: ChildSlot::new(content),
+            on_select: None,
+            font: Font::new(Typeface::default(), 14.0),
+            state: MenuState::default(),
+            size: Size::default(),
+            titles: Vec::new(),
+        }
+    }
+
+    pub fn on_select<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize) -> State::Message + 'static,
+    {
+        self.on_select = Some(Box::new(f));
+        self
+    }
+
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font.set_size(size);
+        self
+    }
+
+    fn row_height(&self) -> f32 {
+        self.font.size() + 2.0 * PADDING
+    }
+
+    fn text_width(&self, text: &str) -> f32 {
+        self.font.measure_str(text, None).0
+    }
+
+    fn item_height(&self, item: &PopupMenu) -> f32 {
+        if item.is_separator() {
+            SEPARATOR_HEIGHT
+        } else {
+            self.row_height()
+        }
+    }
+
+    // Where the item starts, relative to the top of its menu
+    fn item_top(&self, menu: &PopupMenu, index: usize) -> f32 {
+        MENU_PADDING
+            + menu.items()[..index]
+                .iter()
+                .map(|item| self.item_height(item))
+                .sum::<f32>()
+    }
+
+    fn menu_size(&self, menu: &PopupMenu) -> Size {
+        let label = menu
+            .items()
+            .iter()
+            .map(|item| self.text_width(item.label()))
+            .fold(0.0, f32::max);
+        let shortcut = menu
+            .items()
+            .iter()
+            .filter_map(|item| item.shortcut())
+            .map(|shortcut| self.text_width(&shortcut.to_string()))
+            .fold(0.0, f32::max);
+        let shortcut = if shortcut > 0.0 {
+            SHORTCUT_GAP + shortcut
+        } else {
+            0.0
+        };
+
+        Size::new(
+            CHECK_WIDTH + label + shortcut + ARROW_WIDTH,
+            self.item_top(menu, menu.items().len()) + MENU_PADDING,
+        )
+    }
+
+    // Moves a menu back inside the window
+    fn fit(&self, rect: Rect) -> Rect {
+        let dx = (self.size.width - rect.right).min(0.0);
+        let dy = (self.size.height - rect.bottom).min(0.0);
+        let rect = rect.with_offset((dx, dy));
+        rect.with_offset((-rect.left.min(0.0), -rect.top.min(0.0)))
+    }
+
+    // The menu shown at the level, the open menu of the bar is level 0
+    fn menu_at_level(&self, level: usize) -> Option<&PopupMenu> {
+        let mut menu = self.menus.get(self.state.open?)?;
+        for highlight in &self.state.levels[..level] {
+            menu = menu.items().get((*highlight)?)?;
+        }
+        Some(menu)
+    }
+
+    // The shown menus with their bounds
+    fn shown_menus(&self) -> Vec<(&PopupMenu, Rect)> {
+        let mut shown: Vec<(&PopupMenu, Rect)> = Vec::new();
+        for level in 0..self.state.levels.len() {
+            let menu = match self.menu_at_level(level) {
+                Some(menu) => menu,
+                None => break,
+            };
+            let size = self.menu_size(menu);
+
+            let rect = match shown.last() {
+                None => {
+                    let title = self.titles[self.state.open.unwrap()];
+                    Rect::from_xywh(title.left, title.bottom, size.width, size.height)
+                }
+                // Next to the item it belongs to, on the left if there's no room on the right
+                Some((parent, parent_rect)) => {
+                    let index = self.state.levels[level - 1].unwrap();
+                    let top = parent_rect.top + self.item_top(parent, index) - MENU_PADDING;
+                    let left = if parent_rect.right + size.width > self.size.width {
+                        parent_rect.left - size.width
+                    } else {
+                        parent_rect.right
+                    };
+                    Rect::from_xywh(left, top, size.width, size.height)
+                }
+            };
+
+            shown.push((menu, self.fit(rect)));
+        }
+
+        shown
+    }
+
+    fn title_at(&self, position: &Point) -> Option<usize> {
+        self.titles
+            .iter()
+            .position(|rect| contains(rect, position))
+            .filter(|index| self.menus[*index].is_enabled())
+    }
+
+    // The level of the innermost menu under the position, and the item in it if there is one
+    fn menu_at(&self, position: &Point) -> Option<(usize, Option<usize>)> {
+        let shown = self.shown_menus();
+        let (level, (menu, rect)) = shown
+            .iter()
+            .enumerate()
+            .rev()
+            .find(|(_, (_, rect))| contains(rect, position))?;
+
+        let mut top = rect.top + MENU_PADDING;
+        for (index, item) in menu.items().iter().enumerate() {
+            let bottom = top + self.item_height(item);
+            if position.y >= top && position.y < bottom {
+                return Some((level, Some(index)));
+            }
+            top = bottom;
+        }
+
+        Some((level, None))
+    }
+
+    fn open_menu(&mut self, index: usize, highlight_first: bool) {
+        let highlight = if highlight_first {
+            self.menus.get(index).and_then(first_enabled)
+        } else {
+            None
+        };
+
+        self.state.open = Some(index);
+        self.state.levels = vec![highlight];
+        self.state.hovered = None;
+    }
+
+    fn close(&mut self) {
+        self.state.open = None;
+        self.state.levels.clear();
+    }
+
+    // Highlights the item, and shows its sub menu if it has one
+    fn highlight(&mut self, level: usize, index: usize, open_sub_menu: bool) {
+        let item = match self
+            .menu_at_level(level)
+            .and_then(|menu| menu.items().get(index))
+        {
+            Some(item) if item.is_enabled() => item,
+            _ => {
+                self.state.levels.truncate(level + 1);
+                self.state.levels[level] = None;
+                return;
+            }
+        };
+
+        let sub_menu = item.has_sub_menu_items();
+        let first = first_enabled(item);
+        self.state.levels.truncate(level + 1);
+        self.state.levels[level] = Some(index);
+        if sub_menu && open_sub_menu {
+            self.state.levels.push(first)
+        } else if sub_menu {
+            self.state.levels.push(None)
+        }
+    }
+
+    fn activate(&mut self, level: usize, index: usize, ctx: &mut EventCtx<State::Message>) {
+        let item = match self
+            .menu_at_level(level)
+            .and_then(|menu| menu.items().get(index))
+        {
+            Some(item) if item.is_enabled() => item,
+            _ => return,
+        };
+
+        if item.has_sub_menu_items() {
+            self.highlight(level, index, true);
+            return;
+        }
+
+        if let Some(on_select) = &self.on_select {
+            ctx.publish(on_select(item.id()))
+        }
+        self.close()
+    }
+
+    // Moves the highlight in the innermost menu, skipping separators and disabled items
+    fn move_highlight(&mut self, forward: bool) {
+        let level = self.state.levels.len() - 1;
+        let menu = match self.menu_at_level(level) {
+            Some(menu) => menu,
+            None => return,
+        };
+
+        let count = menu.items().len();
+        let mut index = self.state.levels[level];
+        for _ in 0..count {
+            let next = match (index, forward) {
+                (Some(index), true) => (index + 1) % count,
+                (Some(index), false) => (index + count - 1) % count,
+                (None, true) => 0,
+                (None, false) => count - 1,
+            };
+            index = Some(next);
+
+            let item = &menu.items()[next];
+            if item.is_enabled() && !item.is_separator() {
+                self.state.levels[level] = index;
+                return;
+            }
+        }
+    }
+
+    fn switch_menu(&mut self, forward: bool) {
+        if let Some(open) = self.state.open {
+            let count = self.menus.len();
+            let next = if forward {
+                (open + 1) % count
+            } else {
+                (open + count - 1) % count
+            };
+            self.open_menu(next, true)
+        }
+    }
+
+    // Returns true if the event was for the menus, it isn't passed on to the content then
+    fn mouse_event(&mut self, event: &MouseEvent, ctx: &mut EventCtx<State::Message>) -> bool {
+        let position = *event.local_position();
+        let title = self.title_at(&position);
+
+        match event {
+            MouseEvent::MouseMove(_) | MouseEvent::MouseDrag(_) => {
+                if self.state.open.is_none() {
+                    self.state.hovered = title;
+                    return false;
+                }
+
+                match (title, self.menu_at(&position)) {
+                    (Some(title), _) if self.state.open != Some(title) => {
+                        self.open_menu(title, false)
+                    }
+                    (_, Some((level, Some(index)))) => self.highlight(level, index, false),
+                    _ => (),
+                }
+                true
+            }
+            MouseEvent::MouseDown(_) => match title {
+                Some(title) if self.state.open == Some(title) => {
+                    self.close();
+                    true
+                }
+                Some(title) => {
+                    self.open_menu(title, false);
+                    true
+                }
+                // A click outside the menus only closes them
+                None if self.state.open.is_some() => {
+                    if self.menu_at(&position).is_none() {
+                        self.close()
+                    }
+                    true
+                }
+                None => false,
+            },
+            MouseEvent::MouseUp(_) if self.state.open.is_some() => {
+                if let Some((level, Some(index))) = self.menu_at(&position) {
+                    self.activate(level, index, ctx)
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn key_input(&mut self, input: &KeyboardInput, ctx: &mut EventCtx<State::Message>) -> bool {
+        let key = match input.virtual_keycode {
+            Some(key) => key,
+            None => return self.state.open.is_some(),
+        };
+
+        if matches!(key, VirtualKeyCode::LAlt | VirtualKeyCode::RAlt) {
+            self.state.alt_down = input.state == ElementState::Pressed;
+            return false;
+        }
+
+        if input.state == ElementState::Released {
+            return self.state.open.is_some();
+        }
+
+        let letter = key_letter(key);
+        if self.state.open.is_none() {
+            if !self.state.alt_down {
+                return false;
+            }
+
+            let index = letter.and_then(|letter| {
+                self.menus
+                    .iter()
+                    .position(|menu| menu.is_enabled() && menu.mnemonic() == Some(letter))
+            });
+            if let Some(index) = index {
+                self.open_menu(index, true);
+                self.state.mnemonic_pressed = true;
+                return true;
+            }
+            return false;
+        }
+
+        let level = self.state.levels.len() - 1;
+        let highlighted = self.state.levels[level];
+        match key {
+            VirtualKeyCode::Escape if level > 0 => {
+                self.state.levels.pop();
+            }
+            VirtualKeyCode::Escape => self.close(),
+            VirtualKeyCode::Down => self.move_highlight(true),
+            VirtualKeyCode::Up => self.move_highlight(false),
+            VirtualKeyCode::Left if level > 0 => {
+                self.state.levels.pop();
+            }
+            VirtualKeyCode::Left => self.switch_menu(false),
+            VirtualKeyCode::Right => {
+                let sub_menu = highlighted
+                    .and_then(|index| self.menu_at_level(level)?.items().get(index))
+                    .map_or(false, |item| item.has_sub_menu_items());
+                match highlighted {
+                    Some(index) if sub_menu => self.highlight(level, index, true),
+                    _ => self.switch_menu(true),
+                }
+            }
+            VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter | VirtualKeyCode::Space => {
+                if let Some(index) = highlighted {
+                    self.activate(level, index, ctx)
+                }
+            }
+            _ => {
+                let index = letter.and_then(|letter| {
+                    self.menu_at_level(level)?
+                        .items()
+                        .iter()
+                        .position(|item| item.is_enabled() && item.mnemonic() == Some(letter))
+                });
+                if let Some(index) = index {
+                    self.activate(level, index, ctx);
+                    self.state.mnemonic_pressed = true;
+                }
+            }
+        }
+
+        // The content doesn't get keys while a menu is open
+        true
+    }
+
+    fn key_event(&mut self, event: &KeyEvent, ctx: &mut EventCtx<State::Message>) -> bool {
+        match event {
+            KeyEvent::Input(input) => self.key_input(input, ctx),
+            KeyEvent::Char(_) => {
+                std::mem::take(&mut self.state.mnemonic_pressed) || self.state.open.is_some()
+            }
+            KeyEvent::Preedit { .. } | KeyEvent::Commit(_) => self.state.open.is_some(),
+        }
+    }
+
+    fn draw_label(
+        &self,
+        canvas: &mut dyn Canvas2D,
+        item: &PopupMenu,
+        x: f32,
+        row: &Rect,
+        paint: &Paint,
+        show_mnemonic: bool,
+    ) {
+        let baseline = self.draw_text(canvas, item.label(), x, row, paint);
+        let offset = match item.mnemonic_offset() {
+            Some(offset) if show_mnemonic => offset,
+            _ => return,
+        };
+
+        let label = item.label();
+        let end = label[offset..]
+            .chars()
+            .next()
+            .map_or(offset, |c| offset + c.len_utf8());
+        let left = x + self.text_width(&label[..offset]);
+        let width = self.text_width(&label[offset..end]);
+        canvas.draw_rect(&Rect::from_xywh(left, baseline + 2.0, width, 1.0), paint);
+    }
+
+    // Draws the text vertically centered in the row, returns the baseline
+    fn draw_text(
+        &self,
+        canvas: &mut dyn Canvas2D,
+        text: &str,
+        x: f32,
+        row: &Rect,
+        paint: &Paint,
+    ) -> f32 {
+        let (_, metrics) = self.font.metrics();
+        let baseline = row.center_y() - (metrics.ascent + metrics.descent) / 2.0;
+        if let Some(blob) = TextBlob::from_str(text, &self.font) {
+            canvas.draw_text_blob(&Point::new(x, baseline), &blob, paint)
+        }
+        baseline
+    }
+
+    fn paint_menu(
+        &self,
+        canvas: &mut dyn Canvas2D,
+        style: &MenuBarStyle,
+        menu: &PopupMenu,
+        rect: &Rect,
+        highlighted: Option<usize>,
+    ) {
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(style.menu_background);
+        canvas.draw_rounded_rect(rect, style.rounding, style.rounding, &paint);
+        paint.set_color(style.border);
+        paint.set_stroke(true);
+        canvas.draw_rounded_rect(rect, style.rounding, style.rounding, &paint);
+        paint.set_stroke(false);
+
+        let mut top = rect.top + MENU_PADDING;
+        for (index, item) in menu.items().iter().enumerate() {
+            let height = self.item_height(item);
+            let row = Rect::from_xywh(rect.left, top, rect.width(), height);
+            top += height;
+
+            if item.is_separator() {
+                paint.set_color(style.border);
+                let line = Rect::from_xywh(
+                    row.left + MENU_PADDING,
+                    row.center_y().floor(),
+                    row.width() - 2.0 * MENU_PADDING,
+                    1.0,
+                );
+                canvas.draw_rect(&line, &paint);
+                continue;
+            }
+
+            if highlighted == Some(index) {
+                paint.set_color(style.highlight);
+                canvas.draw_rounded_rect(
+                    &row.with_inset((MENU_PADDING, 0.0)),
+                    style.rounding,
+                    style.rounding,
+                    &paint,
+                );
+            }
+
+            paint.set_color(if item.is_enabled() {
+                style.text
+            } else {
+                style.disabled
+            });
+
+            if item.checked() == Some(true) {
+                let center = Point::new(row.left + CHECK_WIDTH / 2.0, row.center_y());
+                let mut check = Path::new();
+                check
+                    .move_to((center.x - 4.0, center.y))
+                    .line_to((center.x - 1.0, center.y + 3.0))
+                    .line_to((center.x + 4.0, center.y - 4.0));
+                let mut stroke = paint.clone();
+                stroke.set_stroke(true);
+                stroke.set_stroke_width(1.5);
+                canvas.draw_path(&check, &stroke);
+            }
+
+            self.draw_label(canvas, item, row.left + CHECK_WIDTH, &row, &paint, true);
+
+            if let Some(shortcut) = item.shortcut() {
+                let text = shortcut.to_string();
+                let x = row.right - ARROW_WIDTH - self.text_width(&text);
+                self.draw_text(canvas, &text, x, &row, &paint);
+            }
+
+            if item.has_sub_menu_items() {
+                let x = row.right - ARROW_WIDTH / 2.0;
+                let y = row.center_y();
+                let mut arrow = Path::new();
+                arrow
+                    .move_to((x - 2.0, y - 4.0))
+                    .line_to((x + 2.0, y))
+                    .line_to((x - 2.0, y + 4.0))
+                    .close();
+                canvas.draw_path(&arrow, &paint);
+            }
+        }
+    }
+}
+
+impl<State: AppState> Widget<State> for MenuBar<State> {
+    fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, state: &State) -> bool {
+        let handled = match event {
+            Event::Mouse(event) => self.mouse_event(event, ctx),
+            Event::Key(event) => self.key_event(event, ctx),
+            Event::Window(WindowEvent::CursorLeft) => {
+                self.state.hovered = None;
+                false
+            }
+            // Alt may have been released in another window
+            Event::Window(WindowEvent::Blurred) => {
+                self.state.alt_down = false;
+                self.close();
+                false
+            }
+            _ => false,
+        };
+
+        if handled {
+            return true;
+        }
+
+        self.content.event(event, ctx, state)
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, state: &State) -> Size {
+        ctx.register_child(self.content.uid());
+        self.font
+            .set_typeface(ctx.fonts().typeface(None, FontStyle::normal()));
+        let size = Size::new(
+            constraints.max_width().unwrap(),
+            constraints.max_height().unwrap(),
+        );
+        self.size = size;
+
+        let height = self.row_height();
+        let mut x = 0.0;
+        self.titles = self
+            .menus
+            .iter()
+            .map(|menu| {
+                let width = self.text_width(menu.label()) + 2.0 * TITLE_PADDING;
+                let rect = Rect::from_xywh(x, 0.0, width, height);
+                x += width;
+                rect
+            })
+            .collect();
+
+        let content_size = self.content.layout(
+            &BoxConstraints::new()
+                .with_tight_constraints(size.width, (size.height - height).max(0.0)),
+            ctx,
+            state,
+        );
+        self.content.set_size(&content_size);
+        self.content.set_position(&Point::new(0.0, height));
+
+        size
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
+        self.content.paint(theme, ctx, canvas, state);
+
+        let style = ctx.style::<MenuBarStyle>(theme);
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+
+        let height = self.row_height();
+        paint.set_color(style.background);
+        canvas.draw_rect(&Rect::from_xywh(0.0, 0.0, self.size.width, height), &paint);
+        paint.set_color(style.border);
+        canvas.draw_rect(
+            &Rect::from_xywh(0.0, height - 1.0, self.size.width, 1.0),
+            &paint,
+        );
+
+        let show_mnemonics = self.state.alt_down || self.state.open.is_some();
+        for (index, (menu, rect)) in self.menus.iter().zip(&self.titles).enumerate() {
+            if self.state.open == Some(index) || self.state.hovered == Some(index) {
+                paint.set_color(style.highlight);
+                canvas.draw_rounded_rect(
+                    &rect.with_inset((2.0, 2.0)),
+                    style.rounding,
+                    style.rounding,
+                    &paint,
+                );
+            }
+
+            paint.set_color(if menu.is_enabled() {
+                style.text
+            } else {
+                style.disabled
+            });
+            self.draw_label(
+                canvas,
+                menu,
+                rect.left + TITLE_PADDING,
+                rect,
+                &paint,
+                show_mnemonics,
+            );
+        }
+    }
+
+    // The open menus cover everything, including popups of the content
+    fn paint_overlay(
+        &self,
+        theme: &Theme,
+        ctx: &PaintCtx,
+        canvas: &mut dyn Canvas2D,
+        state: &State,
+    ) {
+        self.content.paint_overlay(theme, ctx, canvas, state);
+
+        let style = ctx.style::<MenuBarStyle>(theme);
+        for (level, (menu, rect)) in self.shown_menus().into_iter().enumerate() {
+            self.paint_menu(canvas, &style, menu, &rect, self.state.levels[level]);
+        }
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut ChildSlot<State>> {
+        vec![&mut self.content]
+    }
+
+    fn children(&self) -> Vec<&ChildSlot<State>> {
+        vec![&self.content]
+    }
+
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new(std::mem::take(&mut self.state)))
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        if let Ok(local_state) = local_state.downcast::<MenuState>() {
+            self.state = *local_state;
+            // The menus may have changed with the state
+            if self
+                .state
+                .open
+                .map_or(false, |open| open >= self.menus.len())
+            {
+                self.close()
+            }
+        }
+    }
+}
+
+fn contains(rect: &Rect, point: &Point) -> bool {
+    point.x >= rect.left && point.x < rect.right && point.y >= rect.top && point.y < rect.bottom
+}
+
+fn first_enabled(menu: &PopupMenu) -> Option<usize> {
+    menu.items()
+        .iter()
+        .position(|item| item.is_enabled() && !item.is_separator())
+}
+
+// The character a mnemonic has to be for the key
+fn key_letter(key: VirtualKeyCode) -> Option<char> {
+    use VirtualKeyCode::*;
+    let letters = [
+        A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    ];
+    let digits = [Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9];
+
+    if let Some(index) = letters.iter().position(|letter| *letter == key) {
+        return Some((b'a' + index as u8) as char);
+    }
+    digits
+        .iter()
+        .position(|digit| *digit == key)
+        .map(|index| (b'0' + index as u8) as char)
+}
@@ -1,33 +1,52 @@
+pub mod accessibility;
 pub mod button;
 pub mod center;
+pub mod checkbox;
 pub mod container;
+pub mod decoration;
 pub mod expanded;
 pub mod flex;
 pub mod flex_box;
+pub mod icon;
+pub mod image;
+pub mod keyed;
+pub mod label;
 pub mod list;
+pub mod menu_bar;
 pub mod popup;
+pub mod query;
+pub mod radio_group;
 pub mod sized_box;
 pub mod slider;
 pub mod style;
+pub mod styled;
 pub mod switch;
 pub mod text_editor;
+pub mod tooltip;
 
 mod child_slot;
 mod event;
 mod properties;
 
 pub use child_slot::ChildSlot;
-pub use event::{Event, KeyEvent, MouseEvent};
+pub use event::{Command, Event, KeyEvent, MouseEvent};
 pub use properties::Properties;
 
 use crate::{
-    app::AppState,
+    app::{
+        App, AppState, Fonts, ImageSource, ImageStatus, Images, TimerAction, TimerCommand, TimerId,
+    },
     canvas::{Canvas2D, Point, Rect, Size},
     constraints::BoxConstraints,
 };
+use accessibility::AccessNode;
+use icon::Icons;
+use keyed::Keyed;
 use popup::PopupRequest;
-use std::sync::mpsc;
-use style::Theme;
+use std::{any::Any, borrow::Cow, cell::Cell, collections::HashMap, sync::mpsc, time::Duration};
+use style::{StyleClass, StyleScope, Theme, WidgetStyle};
+use styled::Styled;
+use tooltip::Tooltip;
 use winit::window::{CursorIcon, WindowId};
 
 pub fn map_range(x: f32, a: f32, b: f32, c: f32, d: f32) -> f32 {
@@ -37,9 +56,6 @@ pub fn map_range(x: f32, a: f32, b: f32, c: f32, d: f32) -> f32 {
 
 pub enum Action<State> {
     None,
-    Layout {
-        nodes: Vec<&'static str>,
-    },
     PopupRequest {
         request: PopupRequest<State>,
         position: Point,
@@ -63,6 +79,20 @@ pub trait Widget<State: AppState> {
 
     fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State);
 
+    // Painted after the whole window, on top of everything else. Popups like tooltips and menus
+    // paint here, they aren't limited to the bounds of the widget. The default passes it on to the children.
+    fn paint_overlay(
+        &self,
+        theme: &Theme,
+        ctx: &PaintCtx,
+        canvas: &mut dyn Canvas2D,
+        state: &State,
+    ) {
+        for child in self.children() {
+            child.paint_overlay(theme, ctx, canvas, state)
+        }
+    }
+
     fn uid(&self) -> usize {
         std::usize::MAX
     }
@@ -70,22 +100,138 @@ pub trait Widget<State: AppState> {
     fn flex(&self) -> f32 {
         0.0
     }
+
+    // The child slots this widget owns, used to walk the tree without going through event, layout or paint.
+    fn children_mut(&mut self) -> Vec<&mut ChildSlot<State>> {
+        Vec::new()
+    }
+
+    fn children(&self) -> Vec<&ChildSlot<State>> {
+        Vec::new()
+    }
+
+    // When the tree is rebuilt a new widget only takes over the state of an old one if key and type match.
+    fn key(&self) -> Option<&str> {
+        None
+    }
+
+    fn type_name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
+    // Widgets that take text input return where the caret is, in local coordinates. While such a widget
+    // has focus the window accepts input methods and shows their candidate window below the caret.
+    fn caret_rect(&self, size: &Size, state: &State) -> Option<Rect> {
+        None
+    }
+
+    // How assistive technology sees this widget. Widgets without a node are left out of the
+    // accessibility tree, their children are attached to the closest ancestor that has one.
+    fn accessibility(&self, state: &State) -> Option<AccessNode> {
+        None
+    }
+
+    // State that lives in the widget instead of the application state, like hover state or the contents of a text box.
+    // It is moved from the old widget to the new one when the tree is rebuilt.
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        None
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        let _ = local_state;
+    }
+
+    // What the last layout was computed from, and any results the widget keeps from it. When the tree is rebuilt
+    // it is offered to the widget replacing this one.
+    fn take_layout_state(&mut self) -> Option<Box<dyn Any>> {
+        None
+    }
+
+    // Returns true to keep the cached layout of the widget this one replaces, which is only correct if it would lay
+    // out exactly the same. Widgets that don't implement it are laid out again after every rebuild.
+    fn restore_layout_state(&mut self, layout: Box<dyn Any>) -> bool {
+        let _ = layout;
+        false
+    }
 }
 
-pub struct LayoutCtx {
+// Builder methods available on every widget. Not generic over the state, most widgets implement Widget for many states.
+pub trait WidgetExt: Sized {
+    fn with_key(self, key: &str) -> Keyed<Self> {
+        Keyed::new(key, self)
+    }
+
+    // Applies a class registered with StyleContext::register_class
+    fn with_class(self, class: &str) -> Styled<Self> {
+        Styled::new(self).with_class(class)
+    }
+
+    // Changes the style of this widget only, on top of the theme and its classes
+    fn with_style<S, F>(self, f: F) -> Styled<Self>
+    where
+        S: WidgetStyle,
+        F: Fn(&Theme, &mut S) + 'static,
+    {
+        Styled::new(self).with_style(f)
+    }
+
+    // Shows the text in a popup when the mouse rests on this widget
+    fn with_tooltip<State: AppState>(self, text: &str) -> Tooltip<State>
+    where
+        Self: Widget<State> + 'static,
+    {
+        Tooltip::new(self, text)
+    }
+}
+
+impl<W> WidgetExt for W {}
+
+pub struct LayoutCtx<'a> {
     children: Vec<usize>,
+    depends_on_state: bool,
+    loading_images: Vec<ImageSource>,
+    fonts: &'a Fonts,
+    images: &'a Images,
 }
 
-impl LayoutCtx {
-    pub(crate) fn new() -> Self {
+impl<'a> LayoutCtx<'a> {
+    pub(crate) fn new(fonts: &'a Fonts, images: &'a Images) -> Self {
         Self {
             children: Vec::new(),
+            depends_on_state: false,
+            loading_images: Vec::new(),
+            fonts,
+            images,
+        }
+    }
+
+    // Text has to be measured with the same fonts it's painted with
+    pub fn fonts(&self) -> &'a Fonts {
+        self.fonts
+    }
+
+    pub fn images(&self) -> &'a Images {
+        self.images
+    }
+
+    // Like Images::load, but the widget is laid out again once an image that's still loading is decoded
+    pub fn load_image(&mut self, source: &ImageSource) -> ImageStatus {
+        let status = self.images.load(source);
+        if let ImageStatus::Loading = status {
+            self.loading_images.push(source.clone())
         }
+        status
     }
 
     pub fn register_child(&mut self, child: usize) {
         self.children.push(child)
     }
+
+    // Call this from layout when the result depends on the application state.
+    // The widget will be laid out again every time the state changes instead of reusing its cached layout.
+    pub fn depends_on_state(&mut self) {
+        self.depends_on_state = true
+    }
 }
 
 pub struct EventCtx<'a, Message> {
@@ -95,21 +241,58 @@ pub struct EventCtx<'a, Message> {
     cursor: CursorIcon,
     consumer: Option<usize>,
     target: Option<usize>,
+    timer_tx: mpsc::Sender<TimerCommand<Message>>,
+    layout_requested: bool,
+    now: Duration,
+    animation_frame_requested: bool,
+    // Set by the widget, true to take focus and false to give it up
+    focus_requested: Option<bool>,
+    // Set by the slot of the widget, the uid that gets focus
+    focus_change: Option<Option<usize>>,
 }
 
 impl<'a, Message> EventCtx<'a, Message> {
-    pub(crate) fn new(
+    pub(crate) fn new<State>(
         properties: &'a Properties,
         window_id: WindowId,
-        message_tx: mpsc::Sender<Message>,
-    ) -> Self {
+        app: &App<State>,
+    ) -> Self
+    where
+        State: AppState<Message = Message> + 'static,
+    {
         Self {
             properties,
             window_id,
+            message_tx: app.message_tx.clone(),
+            cursor: CursorIcon::Default,
+            consumer: None,
+            target: None,
+            timer_tx: app.timer_tx(),
+            layout_requested: false,
+            now: app.frame_time(),
+            animation_frame_requested: false,
+            focus_requested: None,
+            focus_change: None,
+        }
+    }
+
+    // Sends events straight to a tree without an app, timers started by the widgets go nowhere
+    #[cfg(test)]
+    pub(crate) fn headless(properties: &'a Properties, message_tx: mpsc::Sender<Message>) -> Self {
+        Self {
+            properties,
+            // Only used to address the window in requests, which go nowhere either
+            window_id: unsafe { WindowId::dummy() },
             message_tx,
             cursor: CursorIcon::Default,
             consumer: None,
             target: None,
+            timer_tx: mpsc::channel().0,
+            layout_requested: false,
+            now: Duration::ZERO,
+            animation_frame_requested: false,
+            focus_requested: None,
+            focus_change: None,
         }
     }
 
@@ -121,6 +304,50 @@ impl<'a, Message> EventCtx<'a, Message> {
         self.message_tx.send(message).unwrap()
     }
 
+    fn send_timer(
+        &self,
+        delay: Duration,
+        interval: Option<Duration>,
+        action: TimerAction<Message>,
+    ) -> TimerId {
+        let id = TimerId::next();
+        self.timer_tx
+            .send(TimerCommand::Schedule {
+                id,
+                delay,
+                interval,
+                action,
+            })
+            .unwrap();
+        id
+    }
+
+    // Publishes the message after the delay has passed
+    pub fn schedule(&self, delay: Duration, message: Message) -> TimerId {
+        self.send_timer(delay, None, TimerAction::Message(message))
+    }
+
+    // Sends Event::Timer with the returned id to the widgets of this window after the delay has passed.
+    // The timer belongs to the window, not to the widget: closing the window cancels it, but a widget
+    // that's dropped by a rebuild doesn't. Keep the id in the widget's local state so the widget that
+    // replaces it still recognizes the event, and cancel intervals the widget no longer needs.
+    pub fn start_timer(&self, delay: Duration) -> TimerId {
+        self.send_timer(delay, None, TimerAction::Window(self.window_id))
+    }
+
+    // Like start_timer but keeps repeating until cancelled
+    pub fn start_interval(&self, interval: Duration) -> TimerId {
+        self.send_timer(
+            interval,
+            Some(interval),
+            TimerAction::Window(self.window_id),
+        )
+    }
+
+    pub fn cancel_timer(&self, id: TimerId) {
+        self.timer_tx.send(TimerCommand::Cancel(id)).unwrap()
+    }
+
     pub fn change_cursor(&mut self, icon: CursorIcon) {
         self.cursor = icon
     }
@@ -144,15 +371,128 @@ impl<'a, Message> EventCtx<'a, Message> {
     pub fn target(&self) -> Option<usize> {
         self.target
     }
+
+    // Marks the widget handling this event, and all its ancestors, as needing layout.
+    pub fn request_layout(&mut self) {
+        self.layout_requested = true
+    }
+
+    pub fn layout_requested(&self) -> bool {
+        self.layout_requested
+    }
+
+    // The time of the current frame, from the clock of the app
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    // Keeps the window painting frames, widgets that are animating have to ask again every frame from paint.
+    pub fn request_animation_frame(&mut self) {
+        self.animation_frame_requested = true
+    }
+
+    pub fn animation_frame_requested(&self) -> bool {
+        self.animation_frame_requested
+    }
+
+    // Key events go to the focused widget only. It's told with Event::Focus when it gains or loses focus.
+    pub fn request_focus(&mut self) {
+        self.focus_requested = Some(true)
+    }
+
+    pub fn resign_focus(&mut self) {
+        self.focus_requested = Some(false)
+    }
+
+    pub(crate) fn focus_change(&self) -> Option<Option<usize>> {
+        self.focus_change
+    }
 }
 
 pub struct PaintCtx<'a> {
     properties: &'a Properties,
+    // Where the widget is in the window
+    origin: Point,
+    window_size: Size,
+    classes: Option<&'a HashMap<String, StyleClass>>,
+    scope: Option<&'a StyleScope>,
+    fonts: &'a Fonts,
+    images: &'a Images,
+    icons: Option<&'a Icons>,
+    now: Duration,
+    animation_frame_requested: &'a Cell<bool>,
 }
 
 impl<'a> PaintCtx<'a> {
-    pub(crate) fn new(properties: &'a Properties) -> Self {
-        Self { properties }
+    pub(crate) fn new(
+        properties: &'a Properties,
+        fonts: &'a Fonts,
+        images: &'a Images,
+        now: Duration,
+        animation_frame_requested: &'a Cell<bool>,
+    ) -> Self {
+        Self {
+            properties,
+            origin: Point::new(0.0, 0.0),
+            window_size: properties.size,
+            classes: None,
+            scope: None,
+            fonts,
+            images,
+            icons: None,
+            now,
+            animation_frame_requested,
+        }
+    }
+
+    pub(crate) fn with_classes(mut self, classes: &'a HashMap<String, StyleClass>) -> Self {
+        self.classes = Some(classes);
+        self
+    }
+
+    pub(crate) fn with_icons(mut self, icons: &'a Icons) -> Self {
+        self.icons = Some(icons);
+        self
+    }
+
+    // Styles don't cascade to children, a child only sees the registered classes
+    pub(crate) fn for_child<'b>(&'b self, properties: &'b Properties) -> PaintCtx<'b> {
+        PaintCtx {
+            properties,
+            origin: self.origin + properties.position,
+            window_size: self.window_size,
+            classes: self.classes,
+            scope: None,
+            fonts: self.fonts,
+            images: self.images,
+            icons: self.icons,
+            now: self.now,
+            animation_frame_requested: self.animation_frame_requested,
+        }
+    }
+
+    pub(crate) fn with_scope<'b>(&'b self, scope: &'b StyleScope) -> PaintCtx<'b> {
+        PaintCtx {
+            properties: self.properties,
+            origin: self.origin,
+            window_size: self.window_size,
+            classes: self.classes,
+            scope: Some(scope),
+            fonts: self.fonts,
+            images: self.images,
+            icons: self.icons,
+            now: self.now,
+            animation_frame_requested: self.animation_frame_requested,
+        }
+    }
+
+    // The style a widget should paint with, resolved from the theme, its classes and inline overrides
+    pub fn style<'t, S: WidgetStyle>(&self, theme: &'t Theme) -> Cow<'t, S> {
+        match (self.scope, self.classes) {
+            (Some(scope), Some(classes)) => scope.resolve(theme, classes),
+            (Some(scope), None) => scope.resolve(theme, &HashMap::new()),
+            _ => Cow::Borrowed(S::from_theme(theme)),
+        }
     }
 
     pub fn size(&self) -> &Size {
@@ -162,4 +502,35 @@ impl<'a> PaintCtx<'a> {
     pub fn rect(&self) -> Rect {
         Rect::from_size(self.properties.size)
     }
+
+    pub fn origin(&self) -> Point {
+        self.origin
+    }
+
+    // Popups use it to stay inside the window
+    pub fn window_size(&self) -> &Size {
+        &self.window_size
+    }
+
+    pub fn fonts(&self) -> &'a Fonts {
+        self.fonts
+    }
+
+    pub fn images(&self) -> &'a Images {
+        self.images
+    }
+
+    // The icons registered with the StyleContext, None when painting outside of a window
+    pub fn icons(&self) -> Option<&'a Icons> {
+        self.icons
+    }
+
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    // Asks for another frame after this one, call it on every paint while an animation is running
+    pub fn request_animation_frame(&self) {
+        self.animation_frame_requested.set(true)
+    }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    app::AppState,
+    app::{AppState, Shortcut, ShortcutCommand, ShortcutError, Shortcuts},
     canvas::{Canvas2D, Size},
     constraints::BoxConstraints,
     widget::{Action, Event, EventCtx, PaintCtx, Theme, Widget},
@@ -7,10 +7,20 @@ use crate::{
 
 use super::LayoutCtx;
 
+// A menu item, or a menu when it has items of its own. A '&' in the name marks the next character
+// as the mnemonic, "&&" stands for a literal '&'.
+#[derive(Clone, Debug)]
 pub struct PopupMenu {
-    _id: usize,
-    _name: String,
+    id: usize,
+    label: String,
+    // Byte offset of the mnemonic in the label
+    mnemonic: Option<usize>,
     items: Vec<PopupMenu>,
+    shortcut: Option<Shortcut>,
+    // None for items that can't be checked
+    checked: Option<bool>,
+    enabled: bool,
+    separator: bool,
 }
 
 struct PopupMenuWidget {
@@ -47,10 +57,25 @@ impl<State: AppState> Widget<State> for PopupMenuWidget {
 
 impl PopupMenu {
     pub fn new(id: usize, name: &str) -> Self {
+        let (label, mnemonic) = parse_mnemonic(name);
         PopupMenu {
-            _id: id,
-            _name: name.to_string(),
+            id,
+            label,
+            mnemonic,
             items: Vec::new(),
+            shortcut: None,
+            checked: None,
+            enabled: true,
+            separator: false,
+        }
+    }
+
+    // A line between groups of items, it can't be hovered or activated
+    pub fn separator() -> Self {
+        PopupMenu {
+            enabled: false,
+            separator: true,
+            ..PopupMenu::new(usize::MAX, "")
         }
     }
 
@@ -64,9 +89,155 @@ impl PopupMenu {
         self
     }
 
+    // Adds an item built with the other builder methods, like one with a shortcut
+    pub fn push(mut self, item: PopupMenu) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    pub fn with_separator(mut self) -> Self {
+        self.items.push(PopupMenu::separator());
+        self
+    }
+
+    // Shown next to the item, see register_shortcuts to make the keys work
+    pub fn with_shortcut(mut self, shortcut: impl Into<Shortcut>) -> Self {
+        self.shortcut = Some(shortcut.into());
+        self
+    }
+
+    // Makes the item checkable, the menu shows a check mark while it's checked
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = Some(checked);
+        self
+    }
+
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
     pub fn has_sub_menu_items(&self) -> bool {
         !self.items.is_empty()
     }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    // The name without mnemonic markers
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn mnemonic_offset(&self) -> Option<usize> {
+        self.mnemonic
+    }
+
+    // Lowercase, so it can be compared with the key that was pressed
+    pub fn mnemonic(&self) -> Option<char> {
+        self.mnemonic
+            .and_then(|offset| self.label[offset..].chars().next())
+            .map(|c| c.to_ascii_lowercase())
+    }
+
+    pub fn items(&self) -> &[PopupMenu] {
+        &self.items
+    }
+
+    pub fn shortcut(&self) -> Option<&Shortcut> {
+        self.shortcut.as_ref()
+    }
+
+    pub fn checked(&self) -> Option<bool> {
+        self.checked
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn is_separator(&self) -> bool {
+        self.separator
+    }
+
+    // The item anywhere below this menu with the id
+    pub fn find(&self, id: usize) -> Option<&PopupMenu> {
+        self.items.iter().find_map(|item| {
+            if item.id == id {
+                Some(item)
+            } else {
+                item.find(id)
+            }
+        })
+    }
+
+    // Registers the shortcut of every enabled item below this menu as a command named "menu-<id>" that
+    // publishes the message for the item's id. The commands of disabled items, and of items in disabled
+    // menus, are removed, so calling it again after the menus were built from a changed state keeps the
+    // shortcuts in line with them.
+    pub fn register_shortcuts<Message, F>(
+        &self,
+        shortcuts: &mut Shortcuts<Message>,
+        message: F,
+    ) -> Result<(), ShortcutError>
+    where
+        Message: Clone,
+        F: Fn(usize) -> Message,
+    {
+        self.register_item_shortcuts(shortcuts, &message, self.enabled)
+    }
+
+    fn register_item_shortcuts<Message: Clone>(
+        &self,
+        shortcuts: &mut Shortcuts<Message>,
+        message: &dyn Fn(usize) -> Message,
+        enabled: bool,
+    ) -> Result<(), ShortcutError> {
+        for item in &self.items {
+            let enabled = enabled && item.enabled;
+            if let Some(shortcut) = &item.shortcut {
+                let name = format!("menu-{}", item.id);
+                shortcuts.unregister(&name);
+                if enabled {
+                    shortcuts.register(ShortcutCommand::new(
+                        &name,
+                        shortcut.clone(),
+                        message(item.id),
+                    ))?
+                }
+            }
+
+            item.register_item_shortcuts(shortcuts, message, enabled)?
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_mnemonic(name: &str) -> (String, Option<usize>) {
+    let mut label = String::with_capacity(name.len());
+    let mut mnemonic = None;
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c != '&' {
+            label.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('&') => label.push('&'),
+            Some(next) => {
+                if mnemonic.is_none() {
+                    mnemonic = Some(label.len())
+                }
+                label.push(next)
+            }
+            None => (),
+        }
+    }
+
+    (label, mnemonic)
 }
 
 pub struct PopupRequest<State> {
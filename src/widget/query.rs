@@ -0,0 +1,237 @@
+use crate::{
+    app::AppState,
+    canvas::{Point, Rect, Size},
+    widget::{ChildSlot, Widget},
+};
+
+// A widget in the tree as it was after the last layout
+#[derive(Clone, Debug, PartialEq)]
+pub struct WidgetInfo {
+    pub uid: usize,
+    pub key: Option<String>,
+    pub type_name: &'static str,
+    // Relative to the window
+    pub position: Point,
+    pub size: Size,
+    pub depth: usize,
+    parent: Option<usize>,
+}
+
+impl WidgetInfo {
+    pub fn rect(&self) -> Rect {
+        Rect::from_xywh(
+            self.position.x,
+            self.position.y,
+            self.size.width,
+            self.size.height,
+        )
+    }
+
+    pub fn is<W: 'static>(&self) -> bool {
+        self.type_name == std::any::type_name::<W>()
+    }
+}
+
+// A snapshot of a window's widget tree for lookups by key, type or anything else.
+// Widgets are stored depth first, so parents come before their children.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WidgetTree {
+    widgets: Vec<WidgetInfo>,
+}
+
+impl WidgetTree {
+    pub fn new<State: AppState>(root: &ChildSlot<State>) -> Self {
+        let mut tree = Self::default();
+        tree.push(root, Point::new(0.0, 0.0), None, 0);
+        tree
+    }
+
+    fn push<State: AppState>(
+        &mut self,
+        slot: &ChildSlot<State>,
+        origin: Point,
+        parent: Option<usize>,
+        depth: usize,
+    ) {
+        let position = Point::new(origin.x + slot.position().x, origin.y + slot.position().y);
+        let index = self.widgets.len();
+        self.widgets.push(WidgetInfo {
+            uid: slot.uid(),
+            key: slot.key().map(str::to_string),
+            type_name: slot.type_name(),
+            position,
+            size: *slot.size(),
+            depth,
+            parent,
+        });
+
+        for child in slot.children() {
+            self.push(child, position, Some(index), depth + 1)
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &WidgetInfo> {
+        self.widgets.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.widgets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.widgets.is_empty()
+    }
+
+    pub fn root(&self) -> Option<&WidgetInfo> {
+        self.widgets.first()
+    }
+
+    pub fn find(&self, predicate: impl Fn(&WidgetInfo) -> bool) -> Option<&WidgetInfo> {
+        self.widgets.iter().find(|info| predicate(info))
+    }
+
+    pub fn filter<'a>(
+        &'a self,
+        predicate: impl Fn(&WidgetInfo) -> bool + 'a,
+    ) -> impl Iterator<Item = &'a WidgetInfo> {
+        self.widgets.iter().filter(move |info| predicate(info))
+    }
+
+    pub fn by_key(&self, key: &str) -> Option<&WidgetInfo> {
+        self.find(|info| info.key.as_deref() == Some(key))
+    }
+
+    pub fn by_uid(&self, uid: usize) -> Option<&WidgetInfo> {
+        self.find(|info| info.uid == uid)
+    }
+
+    // For example tree.of_type::<TextBox<State>>()
+    pub fn of_type<W: 'static>(&self) -> impl Iterator<Item = &WidgetInfo> {
+        self.filter(|info| info.is::<W>())
+    }
+
+    pub fn parent(&self, widget: &WidgetInfo) -> Option<&WidgetInfo> {
+        widget.parent.map(|index| &self.widgets[index])
+    }
+
+    pub fn children<'a>(&'a self, widget: &'a WidgetInfo) -> impl Iterator<Item = &'a WidgetInfo> {
+        let index = self.index_of(widget);
+        self.widgets
+            .iter()
+            .filter(move |info| index.is_some() && info.parent == index)
+    }
+
+    // Walks up from the widget to the root
+    pub fn ancestors<'a>(&'a self, widget: &WidgetInfo) -> impl Iterator<Item = &'a WidgetInfo> {
+        std::iter::successors(self.parent(widget), move |info| self.parent(info))
+    }
+
+    // The deepest widget under the point
+    pub fn hit_test(&self, point: &Point) -> Option<&WidgetInfo> {
+        self.widgets
+            .iter()
+            .filter(|info| {
+                point.x >= info.position.x
+                    && point.x < info.position.x + info.size.width
+                    && point.y >= info.position.y
+                    && point.y < info.position.y + info.size.height
+            })
+            .max_by_key(|info| info.depth)
+    }
+
+    fn index_of(&self, widget: &WidgetInfo) -> Option<usize> {
+        self.widgets.iter().position(|info| info.uid == widget.uid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        app::MessageCtx,
+        constraints::BoxConstraints,
+        user_interface::UserInterface,
+        widget::{flex::Flex, sized_box::SizedBox, WidgetExt},
+    };
+    use std::rc::Rc;
+
+    struct State;
+
+    impl AppState for State {
+        type Message = ();
+
+        fn handle_message(&mut self, _: Self::Message, _: &mut MessageCtx<Self>) {}
+    }
+
+    fn sized(width: f32, height: f32) -> SizedBox<State> {
+        SizedBox::new(Size::new(width, height), Flex::row())
+    }
+
+    // A header above a row of two boxes, laid out without a window
+    fn tree() -> Rc<WidgetTree> {
+        let root = Flex::column()
+            .with_spacing(10.0)
+            .push(sized(100.0, 50.0).with_key("header"))
+            .push(
+                Flex::row()
+                    .push(sized(40.0, 40.0).with_key("left"))
+                    .push(sized(60.0, 30.0).with_key("right")),
+            );
+        let mut ui = UserInterface::new(Box::new(root), "light");
+        ui.layout(&BoxConstraints::new_with_max(800.0, 600.0), &State);
+        ui.tree()
+    }
+
+    #[test]
+    fn by_key_finds_the_widget_in_window_coordinates() {
+        let tree = tree();
+        let header = tree.by_key("header").unwrap();
+        assert_eq!(header.rect(), Rect::from_xywh(0.0, 0.0, 100.0, 50.0));
+        assert_eq!(header.depth, 1);
+
+        let right = tree.by_key("right").unwrap();
+        assert_eq!(right.rect(), Rect::from_xywh(40.0, 60.0, 60.0, 30.0));
+        assert_eq!(right.depth, 2);
+
+        assert!(tree.by_key("footer").is_none());
+    }
+
+    #[test]
+    fn of_type_sees_through_keys() {
+        let tree = tree();
+        assert_eq!(tree.len(), 8);
+        assert_eq!(tree.of_type::<SizedBox<State>>().count(), 3);
+        assert_eq!(tree.of_type::<Flex<State>>().count(), 5);
+        assert!(tree.by_key("left").unwrap().is::<SizedBox<State>>());
+    }
+
+    #[test]
+    fn ancestors_walk_up_to_the_root() {
+        let tree = tree();
+        let right = tree.by_key("right").unwrap();
+        let depths: Vec<usize> = tree.ancestors(right).map(|info| info.depth).collect();
+        assert_eq!(depths, vec![1, 0]);
+        assert_eq!(tree.ancestors(right).last(), tree.root());
+
+        let row = tree.parent(right).unwrap();
+        let children: Vec<Option<&str>> =
+            tree.children(row).map(|info| info.key.as_deref()).collect();
+        assert_eq!(children, vec![Some("left"), Some("right")]);
+        assert_eq!(tree.ancestors(tree.root().unwrap()).count(), 0);
+    }
+
+    #[test]
+    fn hit_test_finds_the_deepest_widget() {
+        let tree = tree();
+        let hit = tree.hit_test(&Point::new(50.0, 65.0)).unwrap();
+        assert_eq!(hit.depth, 3);
+        assert_eq!(tree.parent(hit).unwrap().key.as_deref(), Some("right"));
+
+        // Below the right box, but still inside the row
+        let hit = tree.hit_test(&Point::new(50.0, 95.0)).unwrap();
+        assert_eq!(hit.depth, 1);
+        assert!(hit.is::<Flex<State>>());
+
+        assert!(tree.hit_test(&Point::new(150.0, 10.0)).is_none());
+    }
+}
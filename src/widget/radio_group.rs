@@ -0,0 +1,446 @@
+use crate::{
+    app::AppState,
+    canvas::{Canvas2D, Font, Paint, Point, Rect, Size},
+    constraints::BoxConstraints,
+    lens::Binding,
+    widget::{
+        accessibility::{AccessAction, AccessNode, AccessValue, Role},
+        checkbox::{label_font, BOX_SIZE, LABEL_SPACING},
+        style::{RadioStyle, Theme},
+        Event, EventCtx, KeyEvent, MouseEvent, PaintCtx, Widget,
+    },
+};
+
+use super::LayoutCtx;
+use std::any::Any;
+use winit::event::{ElementState, VirtualKeyCode};
+
+enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+// A set of options of which one is selected. The arrow keys move the selection while it has focus.
+pub struct RadioGroup<State: AppState, T> {
+    direction: Direction,
+    options: Vec<(T, String)>,
+    binding: Option<Binding<State, T>>,
+    // Used when there's no binding, kept across rebuilds
+    selected: Option<usize>,
+    on_select: Option<Box<dyn Fn(&T) -> State::Message>>,
+    spacing: f32,
+    // The theme's font size when None
+    font_size: Option<f32>,
+    // Picked from the app's fonts during layout
+    font: Font,
+    // Of every option, from the last layout
+    rects: Vec<Rect>,
+    hovered: Option<usize>,
+    focused: bool,
+}
+
+impl<State: AppState, T: PartialEq + 'static> RadioGroup<State, T> {
+    pub fn row() -> Self {
+        Self::new(Direction::Horizontal)
+    }
+
+    pub fn column() -> Self {
+        Self::new(Direction::Vertical)
+    }
+
+    fn new(direction: Direction) -> Self {
+        Self {
+            direction,
+            options: Vec::new(),
+            binding: None,
+            selected: None,
+            on_select: None,
+            spacing: 8.0,
+            font_size: None,
+            font: Font::default(),
+            rects: Vec::new(),
+            hovered: None,
+            focused: false,
+        }
+    }
+
+    pub fn with_option(mut self, value: T, label: &str) -> Self {
+        self.options.push((value, label.to_string()));
+        self
+    }
+
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn with_font_size(mut self, size: f32) -> Self {
+        self.font_size = Some(size);
+        self
+    }
+
+    // The option a group without a binding starts out with, nothing is selected when no option has the value.
+    // The group keeps track of the selection itself after that.
+    pub fn selected(mut self, value: T) -> Self {
+        self.selected = self.options.iter().position(|(option, _)| *option == value);
+        self
+    }
+
+    // Publishes the message when the user selects another option of a group without a binding
+    pub fn on_select<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&T) -> State::Message + 'static,
+    {
+        self.on_select = Some(Box::new(f));
+        self
+    }
+
+    // Selects the option with the value from the state and publishes the message from on_change when the
+    // user selects another one.
+    pub fn bind<G, F>(mut self, get: G, on_change: F) -> Self
+    where
+        G: Fn(&State) -> &T + 'static,
+        F: Fn(&T) -> State::Message + 'static,
+    {
+        self.binding = Some(Binding::new(get, on_change));
+        self
+    }
+
+    fn selected_index(&self, state: &State) -> Option<usize> {
+        match &self.binding {
+            Some(binding) => {
+                let value = binding.get(state);
+                self.options.iter().position(|(option, _)| option == value)
+            }
+            None => self.selected,
+        }
+    }
+
+    fn select(&mut self, index: usize, ctx: &mut EventCtx<State::Message>, state: &State) {
+        if self.selected_index(state) == Some(index) {
+            return;
+        }
+
+        self.selected = Some(index);
+        let value = &self.options[index].0;
+        if let Some(binding) = &self.binding {
+            ctx.publish(binding.changed(value))
+        } else if let Some(on_select) = &self.on_select {
+            ctx.publish(on_select(value))
+        }
+    }
+
+    // Wraps around at both ends
+    fn select_next(&mut self, forward: bool, ctx: &mut EventCtx<State::Message>, state: &State) {
+        let count = self.options.len();
+        if count == 0 {
+            return;
+        }
+
+        let index = match (self.selected_index(state), forward) {
+            (Some(index), true) => (index + 1) % count,
+            (Some(index), false) => (index + count - 1) % count,
+            (None, true) => 0,
+            (None, false) => count - 1,
+        };
+        self.select(index, ctx, state)
+    }
+
+    fn option_at(&self, position: &Point) -> Option<usize> {
+        self.rects.iter().position(|rect| rect.contains(*position))
+    }
+
+    fn key_input(
+        &mut self,
+        key: VirtualKeyCode,
+        ctx: &mut EventCtx<State::Message>,
+        state: &State,
+    ) -> bool {
+        match key {
+            VirtualKeyCode::Down | VirtualKeyCode::Right => self.select_next(true, ctx, state),
+            VirtualKeyCode::Up | VirtualKeyCode::Left => self.select_next(false, ctx, state),
+            VirtualKeyCode::Space if self.selected_index(state).is_none() => {
+                self.select_next(true, ctx, state)
+            }
+            VirtualKeyCode::Space => (),
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl<State: AppState, T: PartialEq + 'static> Widget<State> for RadioGroup<State, T> {
+    fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, state: &State) -> bool {
+        match event {
+            Event::Mouse(MouseEvent::MouseEnter(event))
+            | Event::Mouse(MouseEvent::MouseMove(event)) => {
+                self.hovered = self.option_at(event.local_position());
+                true
+            }
+            Event::Mouse(MouseEvent::MouseLeave(_)) => {
+                self.hovered = None;
+                true
+            }
+            Event::Mouse(MouseEvent::MouseDown(_)) => {
+                ctx.request_focus();
+                true
+            }
+            Event::Mouse(MouseEvent::MouseUp(event)) => {
+                if let Some(index) = self.option_at(event.local_position()) {
+                    self.select(index, ctx, state)
+                }
+                true
+            }
+            Event::Focus(focused) => {
+                self.focused = *focused;
+                true
+            }
+            Event::Key(KeyEvent::Input(input)) => match input.virtual_keycode {
+                Some(key) if input.state == ElementState::Pressed => {
+                    self.key_input(key, ctx, state)
+                }
+                _ => false,
+            },
+            Event::Key(KeyEvent::Char(' ')) => true,
+            Event::Access(request) => match request.action {
+                AccessAction::Increment => {
+                    self.select_next(true, ctx, state);
+                    true
+                }
+                AccessAction::Decrement => {
+                    self.select_next(false, ctx, state);
+                    true
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, _: &State) -> Size {
+        self.font = label_font(ctx, self.font_size);
+        let height = BOX_SIZE.max(self.font.spacing());
+        let mut position = Point::new(0.0, 0.0);
+        let mut size = Size::new(0.0, 0.0);
+        self.rects.clear();
+        for (_, label) in &self.options {
+            let width = BOX_SIZE + LABEL_SPACING + self.font.measure_str(label, None).0;
+            let rect = Rect::from_xywh(position.x, position.y, width, height);
+            size.width = size.width.max(rect.right);
+            size.height = size.height.max(rect.bottom);
+            match self.direction {
+                Direction::Horizontal => position.x = rect.right + self.spacing,
+                Direction::Vertical => position.y = rect.bottom + self.spacing,
+            }
+            self.rects.push(rect);
+        }
+
+        Size::new(
+            constraints.constrain_width(size.width),
+            constraints.constrain_height(size.height),
+        )
+    }
+
+    fn accessibility(&self, state: &State) -> Option<AccessNode> {
+        let node = AccessNode::new(Role::RadioGroup)
+            .with_action(AccessAction::Increment)
+            .with_action(AccessAction::Decrement)
+            .with_action(AccessAction::Focus);
+        Some(match self.selected_index(state) {
+            Some(index) => node.with_value(AccessValue::Text(self.options[index].1.clone())),
+            None => node,
+        })
+    }
+
+    // A bound group shows the state, the selection it keeps itself is only used without a binding.
+    // The selected value is taken out of the old group, so it's found again when the options were
+    // reordered, and nothing is selected when it's gone.
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        let selected = self
+            .selected
+            .take()
+            .map(|index| self.options.swap_remove(index).0);
+        Some(Box::new((selected, self.hovered.take(), self.focused)))
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        if let Ok(local_state) = local_state.downcast::<(Option<T>, Option<usize>, bool)>() {
+            let (selected, hovered, focused) = *local_state;
+            self.selected = selected
+                .and_then(|value| self.options.iter().position(|(option, _)| *option == value));
+            self.hovered = hovered.filter(|index| *index < self.options.len());
+            self.focused = focused;
+        }
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
+        let style = ctx.style::<RadioStyle>(theme);
+        let selected = self.selected_index(state);
+        let radius = BOX_SIZE / 2.0;
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        let mut text_paint = Paint::default();
+        text_paint.set_anti_alias(true);
+        text_paint.set_color(style.text);
+
+        // The focus ring is on the option the arrow keys move away from
+        let focused = if self.focused {
+            Some(selected.unwrap_or(0))
+        } else {
+            None
+        };
+
+        for (index, ((_, label), row)) in self.options.iter().zip(&self.rects).enumerate() {
+            let center = Point::new(row.left + radius, row.center_y());
+            if focused == Some(index) {
+                paint.set_color(style.focus);
+                paint.set_stroke(false);
+                canvas.draw_circle(&center, radius + 3.0, &paint);
+            }
+
+            let is_selected = selected == Some(index);
+            let border = if is_selected || self.hovered == Some(index) {
+                style.fill
+            } else {
+                style.border
+            };
+            paint.set_color(border);
+            paint.set_stroke(true);
+            paint.set_stroke_width(1.5);
+            canvas.draw_circle(&center, radius - 0.75, &paint);
+
+            if is_selected {
+                paint.set_color(style.fill);
+                paint.set_stroke(false);
+                canvas.draw_circle(&center, radius / 2.0, &paint);
+            }
+
+            let label_rect = Rect::from_ltrb(
+                row.left + BOX_SIZE + LABEL_SPACING,
+                row.top,
+                row.right,
+                row.bottom,
+            );
+            canvas.draw_string(&label_rect, label, &self.font, &text_paint);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{app::MessageCtx, widget::Properties};
+    use std::sync::mpsc;
+    use winit::event::{KeyboardInput, ModifiersState};
+
+    struct State;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Message {
+        Selected(&'static str),
+    }
+
+    impl AppState for State {
+        type Message = Message;
+
+        fn handle_message(&mut self, _: Self::Message, _: &mut MessageCtx<Self>) {}
+    }
+
+    fn key(key: VirtualKeyCode) -> Event {
+        #[allow(deprecated)]
+        let input = KeyboardInput {
+            scancode: 0,
+            state: ElementState::Pressed,
+            virtual_keycode: Some(key),
+            modifiers: ModifiersState::empty(),
+        };
+        Event::Key(KeyEvent::Input(input))
+    }
+
+    // Sends the events to the group and returns what it published
+    fn send(group: &mut RadioGroup<State, &'static str>, events: &[Event]) -> Vec<Message> {
+        let properties = Properties::default();
+        let (message_tx, message_rx) = mpsc::channel();
+        for event in events {
+            let mut ctx = EventCtx::headless(&properties, message_tx.clone());
+            group.event(event, &mut ctx, &State);
+        }
+        message_rx.try_iter().collect()
+    }
+
+    fn radio_group(options: &[&'static str]) -> RadioGroup<State, &'static str> {
+        options
+            .iter()
+            .fold(RadioGroup::column(), |group, option| {
+                group.with_option(*option, option)
+            })
+            .on_select(|option| Message::Selected(*option))
+    }
+
+    fn selected(group: &RadioGroup<State, &'static str>) -> Option<&'static str> {
+        group
+            .selected_index(&State)
+            .map(|index| group.options[index].0)
+    }
+
+    #[test]
+    fn arrow_keys_wrap_around() {
+        let mut group = radio_group(&["small", "medium", "large"]).selected("large");
+        let messages = send(&mut group, &[key(VirtualKeyCode::Down)]);
+        assert_eq!(messages, vec![Message::Selected("small")]);
+
+        let messages = send(
+            &mut group,
+            &[key(VirtualKeyCode::Up), key(VirtualKeyCode::Left)],
+        );
+        assert_eq!(
+            messages,
+            vec![Message::Selected("large"), Message::Selected("medium")]
+        );
+
+        // Without a selection up starts at the end
+        let mut group = radio_group(&["small", "medium", "large"]);
+        let messages = send(&mut group, &[key(VirtualKeyCode::Up)]);
+        assert_eq!(messages, vec![Message::Selected("large")]);
+    }
+
+    #[test]
+    fn space_only_selects_when_nothing_is() {
+        let mut group = radio_group(&["small", "medium"]);
+        let messages = send(
+            &mut group,
+            &[key(VirtualKeyCode::Space), key(VirtualKeyCode::Space)],
+        );
+        assert_eq!(messages, vec![Message::Selected("small")]);
+        assert_eq!(selected(&group), Some("small"));
+    }
+
+    #[test]
+    fn selection_survives_a_rebuild() {
+        let mut old = radio_group(&["small", "medium", "large"]).selected("small");
+        send(&mut old, &[key(VirtualKeyCode::Down)]);
+
+        let mut new = radio_group(&["small", "medium", "large"]).selected("small");
+        new.restore_local_state(old.take_local_state().unwrap());
+        assert_eq!(selected(&new), Some("medium"));
+
+        // Found by value when the options are reordered
+        let mut reordered = radio_group(&["large", "medium", "small"]);
+        reordered.restore_local_state(new.take_local_state().unwrap());
+        assert_eq!(selected(&reordered), Some("medium"));
+    }
+
+    #[test]
+    fn selection_is_dropped_when_its_option_is() {
+        let mut old = radio_group(&["small", "medium", "large"]).selected("large");
+        let mut shrunk = radio_group(&["small", "medium"]);
+        shrunk.restore_local_state(old.take_local_state().unwrap());
+        assert_eq!(selected(&shrunk), None);
+
+        let mut old = radio_group(&["small", "medium", "large"]).selected("medium");
+        let mut shrunk = radio_group(&["medium"]);
+        shrunk.restore_local_state(old.take_local_state().unwrap());
+        assert_eq!(selected(&shrunk), Some("medium"));
+    }
+}
@@ -1,18 +1,26 @@
 use crate::{
+    animation::Animated,
     app::AppState,
     canvas::{Canvas2D, Paint, Point, Rect, Size},
     constraints::BoxConstraints,
-    widget::{map_range, style::Theme, Event, EventCtx, MouseEvent, PaintCtx, Widget},
+    lens::Binding,
+    widget::{
+        accessibility::{AccessAction, AccessNode, AccessValue, ActionData, Role},
+        map_range,
+        style::{SliderStyle, Theme},
+        Event, EventCtx, MouseEvent, PaintCtx, Widget,
+    },
 };
 
 use super::LayoutCtx;
+use std::any::Any;
 
 enum SliderState {
     Active,
     Inactive,
 }
 
-pub struct Slider {
+pub struct Slider<State: AppState> {
     min: f32,
     max: f32,
     discrete: bool,
@@ -20,23 +28,42 @@ pub struct Slider {
     current_value: f32,
     last_position: f32,
     value_changed: Option<Box<dyn FnMut(f32)>>,
+    binding: Option<Binding<State, f32>>,
     state: SliderState,
+    // Size of the halo around the thumb while it is dragged, 0 is hidden
+    halo: Animated<f32>,
 }
 
-impl Slider {
+impl<State: AppState> Slider<State> {
     pub fn new(min: f32, max: f32, value: f32, discrete: bool) -> Self {
         Slider {
             min,
             max,
             discrete,
-            current_normalized: value / (max - min),
+            current_normalized: map_range(value, min, max, 0., 1.),
             current_value: value,
             last_position: 0.,
             value_changed: None,
+            binding: None,
             state: SliderState::Inactive,
+            halo: Animated::new(0.0),
         }
     }
 
+    // Shows the value from the state and publishes the message from on_change when the user drags the slider.
+    pub fn bind<G, F>(self, get: G, on_change: F) -> Self
+    where
+        G: Fn(&State) -> &f32 + 'static,
+        F: Fn(&f32) -> State::Message + 'static,
+    {
+        self.with_binding(Binding::new(get, on_change))
+    }
+
+    pub fn with_binding(mut self, binding: Binding<State, f32>) -> Self {
+        self.binding = Some(binding);
+        self
+    }
+
     pub fn value_changed<F>(mut self, f: F) -> Self
     where
         F: FnMut(f32) + 'static,
@@ -49,11 +76,64 @@ impl Slider {
         self.current_value = value.max(self.min).min(self.max);
         self.current_normalized = map_range(self.current_value, self.min, self.max, 0., 1.)
     }
+
+    fn notify_value_changed(&mut self, ctx: &mut EventCtx<State::Message>) {
+        if let Some(l) = &mut self.value_changed {
+            (l)(self.current_value);
+        }
+
+        if let Some(binding) = &self.binding {
+            ctx.publish(binding.changed(&self.current_value))
+        }
+    }
+
+    fn value(&self, state: &State) -> f32 {
+        match &self.binding {
+            Some(binding) => binding.get(state).max(self.min).min(self.max),
+            None => self.current_value,
+        }
+    }
+
+    // Assistive technology moves the slider in steps of one when discrete and a tenth of the range otherwise
+    fn step(&self) -> f32 {
+        if self.discrete {
+            1.0
+        } else {
+            (self.max - self.min) / 10.0
+        }
+    }
+
+    fn change_value(&mut self, value: f32, ctx: &mut EventCtx<State::Message>) {
+        self.set_value(if self.discrete { value.round() } else { value });
+        self.notify_value_changed(ctx)
+    }
+
+    fn normalized(&self, state: &State) -> f32 {
+        match &self.binding {
+            Some(binding) => {
+                let value = binding.get(state).max(self.min).min(self.max);
+                map_range(value, self.min, self.max, 0., 1.)
+            }
+            None => self.current_normalized,
+        }
+    }
 }
 
-impl<State: AppState> Widget<State> for Slider {
-    fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, _: &State) -> bool {
+impl<State: AppState> Widget<State> for Slider<State> {
+    fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, state: &State) -> bool {
         match event {
+            Event::Access(request) => {
+                let value = self.value(state);
+                match (request.action, &request.data) {
+                    (AccessAction::SetValue, Some(ActionData::NumericValue(value))) => {
+                        self.change_value(*value, ctx)
+                    }
+                    (AccessAction::Increment, _) => self.change_value(value + self.step(), ctx),
+                    (AccessAction::Decrement, _) => self.change_value(value - self.step(), ctx),
+                    _ => return false,
+                }
+                true
+            }
             Event::Mouse(MouseEvent::MouseEnter(_)) => {
                 self.state = SliderState::Active;
                 true
@@ -70,9 +150,7 @@ impl<State: AppState> Widget<State> for Slider {
                 if self.discrete {
                     self.current_value = self.current_value.round();
                 }
-                if let Some(l) = &mut self.value_changed {
-                    (l)(self.current_value);
-                }
+                self.notify_value_changed(ctx);
                 true
             }
             Event::Mouse(MouseEvent::MouseUp(_)) => {
@@ -89,16 +167,14 @@ impl<State: AppState> Widget<State> for Slider {
                 if self.discrete {
                     self.current_value = self.current_value.round();
                 }
-                if let Some(l) = &mut self.value_changed {
-                    (l)(self.current_value);
-                }
+                self.notify_value_changed(ctx);
                 true
             }
             _ => false,
         }
     }
 
-    fn layout(&mut self, constraints: &BoxConstraints, _ctx: &mut LayoutCtx, _: &State) -> Size {
+    fn layout(&mut self, constraints: &BoxConstraints, _: &mut LayoutCtx, _: &State) -> Size {
         // Boldly unwrapping here. If you have not given constraints to a slider then we don't know how big it should be.
         Size::new(
             constraints.max_width().unwrap(),
@@ -106,13 +182,42 @@ impl<State: AppState> Widget<State> for Slider {
         )
     }
 
-    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, _: &State) {
+    fn accessibility(&self, state: &State) -> Option<AccessNode> {
+        Some(
+            AccessNode::new(Role::Slider)
+                .with_value(AccessValue::Number {
+                    value: self.value(state),
+                    min: self.min,
+                    max: self.max,
+                })
+                .with_action(AccessAction::SetValue)
+                .with_action(AccessAction::Increment)
+                .with_action(AccessAction::Decrement),
+        )
+    }
+
+    // Only the hover state and the halo are local, the value comes from whoever builds the slider.
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new((
+            std::mem::replace(&mut self.state, SliderState::Inactive),
+            std::mem::replace(&mut self.halo, Animated::new(0.0)),
+        )))
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        if let Ok(local_state) = local_state.downcast::<(SliderState, Animated<f32>)>() {
+            (self.state, self.halo) = *local_state
+        }
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
         let rect = ctx.rect();
+        let style = ctx.style::<SliderStyle>(theme);
 
         let mut fill_paint = Paint::default();
         fill_paint.set_anti_alias(true);
 
-        let rounding = 4.0;
+        let rounding = style.rounding;
         let gutter_height = rect.height() / 8.0;
         let thumb_size = gutter_height * 1.5;
         let active_thumb_size = thumb_size * 2.5;
@@ -123,7 +228,7 @@ impl<State: AppState> Widget<State> for Slider {
         let mut unfill_rect = Rect::from_wh(rect.width(), gutter_height);
         unfill_rect.offset(Point::new(0.0, rect.center_y() - unfill_rect.center_y()));
 
-        fill_paint.set_color(theme.slider.fill);
+        fill_paint.set_color(style.fill);
         fill_paint.set_stroke(true);
         canvas.draw_rounded_rect(&unfill_rect, rounding, rounding, &fill_paint);
 
@@ -131,21 +236,35 @@ impl<State: AppState> Widget<State> for Slider {
         fill_paint.set_stroke(false);
         canvas.draw_rounded_rect(&unfill_rect, rounding, rounding, &fill_paint);
 
+        // Derived from the value rather than the last mouse position so a rebuilt slider shows its value
+        let thumb_position = self.normalized(state) * rect.width();
+
         fill_paint.set_alpha_f(1.0);
-        let mut fill_rect = Rect::from_wh(self.last_position, gutter_height);
+        let mut fill_rect = Rect::from_wh(thumb_position, gutter_height);
         fill_rect.offset(Point::new(0.0, rect.center_y() - fill_rect.center_y()));
         canvas.draw_rounded_rect(&fill_rect, rounding, rounding, &fill_paint);
         canvas.draw_circle(
-            &Point::new(self.last_position, rect.center_y()),
+            &Point::new(thumb_position, rect.center_y()),
             thumb_size,
             &fill_paint,
         );
 
-        if let SliderState::Active = self.state {
-            fill_paint.set_alpha_f(0.25);
+        let now = ctx.now();
+        let active = if let SliderState::Active = self.state {
+            1.0
+        } else {
+            0.0
+        };
+        let halo = self.halo.animate_to(active, now);
+        if self.halo.is_animating(now) {
+            ctx.request_animation_frame()
+        }
+
+        if halo > 0.0 {
+            fill_paint.set_alpha_f(0.25 * halo);
             canvas.draw_circle(
-                &Point::new(self.last_position, rect.center_y()),
-                active_thumb_size,
+                &Point::new(thumb_position, rect.center_y()),
+                thumb_size + (active_thumb_size - thumb_size) * halo,
                 &fill_paint,
             );
         }
@@ -154,7 +273,7 @@ impl<State: AppState> Widget<State> for Slider {
     }
 }
 
-impl Default for Slider {
+impl<State: AppState> Default for Slider<State> {
     fn default() -> Self {
         Self::new(0., 1., 0., false)
     }
@@ -1,44 +1,642 @@
-use crate::canvas::{Color, Font};
-use std::collections::HashMap;
-#[derive(Default)]
+use crate::{
+    app::AppEvent,
+    canvas::Color,
+    widget::icon::{IconError, Icons},
+};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{de::Error as _, Deserialize, Deserializer};
+use std::{
+    any::Any,
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+use thiserror::Error;
+use winit::event_loop::EventLoopProxy;
+
+#[derive(Debug, Error)]
+pub enum ThemeError {
+    #[error("failed to read theme file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid TOML theme: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error("invalid JSON theme: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("invalid RON theme: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("unsupported theme file format: {0:?}, expected .toml, .json or .ron")]
+    UnsupportedFormat(PathBuf),
+    #[error("failed to watch theme file: {0}")]
+    Watch(#[from] notify::Error),
+}
+
+// Parses a CSS style hex color, "#RGB", "#RRGGBB" or "#RRGGBBAA"
+pub fn parse_color(value: &str) -> Option<Color> {
+    let hex = value.strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let digits: String = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => hex.to_string(),
+        _ => return None,
+    };
+
+    let value = u32::from_str_radix(&digits, 16).ok()?;
+    if digits.len() == 8 {
+        // Skia wants the alpha in the most significant byte
+        Some(Color::new(value.rotate_right(8)))
+    } else {
+        Some(Color::new(0xFF000000 | value))
+    }
+}
+
+fn deserialize_color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_color(&value).ok_or_else(|| {
+        D::Error::custom(format!(
+            "invalid color \"{}\", expected #RGB, #RRGGBB or #RRGGBBAA",
+            value
+        ))
+    })
+}
+
+// Every style struct falls back to its default for keys that are missing from a theme file,
+// so a theme only has to specify what it changes.
+#[derive(Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct FontStyle {
+    pub family: String,
+    pub size: f32,
+    // Tried in order for text the family has no glyphs for, or when it isn't installed
+    pub fallback: Vec<String>,
+    // Families widgets ask for by name, each with its own fallback chain
+    pub families: HashMap<String, Vec<String>>,
+}
+
+impl Default for FontStyle {
+    fn default() -> Self {
+        let families = [
+            (
+                "monospace",
+                vec![
+                    "Menlo",
+                    "Consolas",
+                    "DejaVu Sans Mono",
+                    "Liberation Mono",
+                    "monospace",
+                ],
+            ),
+            (
+                "serif",
+                vec![
+                    "Georgia",
+                    "Times New Roman",
+                    "DejaVu Serif",
+                    "Liberation Serif",
+                    "serif",
+                ],
+            ),
+        ];
+
+        Self {
+            family: "Arial".to_string(),
+            size: 14.0,
+            fallback: [
+                "Helvetica",
+                "Segoe UI",
+                "DejaVu Sans",
+                "Liberation Sans",
+                "sans-serif",
+            ]
+            .iter()
+            .map(|family| family.to_string())
+            .collect(),
+            families: families
+                .into_iter()
+                .map(|(name, chain)| {
+                    let chain = chain.into_iter().map(|family| family.to_string()).collect();
+                    (name.to_string(), chain)
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
 pub struct SliderThumbStyle {
+    #[serde(deserialize_with = "deserialize_color")]
     pub color: Color,
     pub size: f32,
 }
-#[derive(Default)]
+
+impl Default for SliderThumbStyle {
+    fn default() -> Self {
+        Self {
+            color: Color::new(0xFF73C8A6),
+            size: 0.125,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
 pub struct SliderStyle {
+    #[serde(deserialize_with = "deserialize_color")]
     pub background: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub fill: Color,
     pub thumb: SliderThumbStyle,
+    pub rounding: f32,
 }
 
-#[derive(Default)]
+impl Default for SliderStyle {
+    fn default() -> Self {
+        Self {
+            background: Color::new(0xFFFFFFFF),
+            fill: Color::new(0xFF766AC8),
+            thumb: SliderThumbStyle::default(),
+            rounding: 4.0,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
 pub struct TextButtonStyle {
+    #[serde(deserialize_with = "deserialize_color")]
     pub inactive: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub active: Color,
-    pub hoverd: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub hovered: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub text: Color,
-    pub font: Font,
     pub rounding: f32,
 }
 
 impl TextButtonStyle {
     pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Derives the inactive and hover colors from the color of a pressed button
+    pub fn from_primary(primary: Color, text: Color) -> Self {
+        Self {
+            inactive: primary.with_a(200),
+            active: primary,
+            hovered: primary.with_a(230),
+            text,
+            rounding: 4.0,
+        }
+    }
+}
+
+impl Default for TextButtonStyle {
+    fn default() -> Self {
+        Self::from_primary(Color::new(0xFF766AC8), Color::new(0xFFFFFFFF))
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct SwitchStyle {
+    #[serde(deserialize_with = "deserialize_color")]
+    pub fill: Color,
+    pub rounding: f32,
+}
+
+impl Default for SwitchStyle {
+    fn default() -> Self {
+        Self {
+            fill: Color::new(0xFF766AC8),
+            rounding: 4.0,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct CheckboxStyle {
+    // Of a checked box, and the border while hovered
+    #[serde(deserialize_with = "deserialize_color")]
+    pub fill: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub border: Color,
+    // The check mark
+    #[serde(deserialize_with = "deserialize_color")]
+    pub check: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub text: Color,
+    // The ring around the box while it has keyboard focus
+    #[serde(deserialize_with = "deserialize_color")]
+    pub focus: Color,
+    pub rounding: f32,
+}
+
+impl Default for CheckboxStyle {
+    fn default() -> Self {
+        Self {
+            fill: Color::new(0xFF766AC8),
+            border: Color::new(0xFF808080),
+            check: Color::new(0xFFFFFFFF),
+            text: Color::new(0xFF000000),
+            focus: Color::new(0x60766AC8),
+            rounding: 3.0,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct RadioStyle {
+    // The dot of the selected option, and the border while hovered
+    #[serde(deserialize_with = "deserialize_color")]
+    pub fill: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub border: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub text: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub focus: Color,
+}
+
+impl Default for RadioStyle {
+    fn default() -> Self {
+        Self {
+            fill: Color::new(0xFF766AC8),
+            border: Color::new(0xFF808080),
+            text: Color::new(0xFF000000),
+            focus: Color::new(0x60766AC8),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct TextBoxStyle {
+    #[serde(deserialize_with = "deserialize_color")]
+    pub background: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub border: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub selection: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub text: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub placeholder: Color,
+}
+
+impl Default for TextBoxStyle {
+    fn default() -> Self {
+        Self {
+            background: Color::new(0xFFFFFFFF),
+            border: Color::new(0xFF000000),
+            selection: Color::new(0xFF0000FF),
+            text: Color::new(0xFF000000),
+            placeholder: Color::new(0x80000000),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct MenuBarStyle {
+    #[serde(deserialize_with = "deserialize_color")]
+    pub background: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub text: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub disabled: Color,
+    // Behind the open menu's title and the item under the cursor
+    #[serde(deserialize_with = "deserialize_color")]
+    pub highlight: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub menu_background: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub border: Color,
+    pub rounding: f32,
+}
+
+impl Default for MenuBarStyle {
+    fn default() -> Self {
         Self {
+            background: Color::new(0xFFF2F2F2),
+            text: Color::new(0xFF000000),
+            disabled: Color::new(0x60000000),
+            highlight: Color::new(0x40766AC8),
+            menu_background: Color::new(0xFFFFFFFF),
+            border: Color::new(0xFFCCCCCC),
             rounding: 4.0,
-            ..Default::default()
         }
     }
 }
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct TooltipStyle {
+    #[serde(deserialize_with = "deserialize_color")]
+    pub background: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub text: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub border: Color,
+    pub font_size: f32,
+    pub padding: f32,
+    pub rounding: f32,
+}
+
+impl Default for TooltipStyle {
+    fn default() -> Self {
+        Self {
+            background: Color::new(0xFF333333),
+            text: Color::new(0xFFFFFFFF),
+            border: Color::new(0x00000000),
+            font_size: 12.0,
+            padding: 6.0,
+            rounding: 4.0,
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct LabelStyle {
+    // For text without a color of its own
+    #[serde(deserialize_with = "deserialize_color")]
+    pub text: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub link: Color,
+    #[serde(deserialize_with = "deserialize_color")]
+    pub selection: Color,
+}
+
+impl Default for LabelStyle {
+    fn default() -> Self {
+        Self {
+            text: Color::new(0xFF000000),
+            link: Color::new(0xFF766AC8),
+            selection: Color::new(0x60766AC8),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct ImageStyle {
+    // Fills the widget while the image is loading or when it failed to load
+    #[serde(deserialize_with = "deserialize_color")]
+    pub placeholder: Color,
+}
+
+impl Default for ImageStyle {
+    fn default() -> Self {
+        Self {
+            placeholder: Color::new(0xFFE0E0E0),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
+pub struct IconStyle {
+    // Of currentColor, and fills everything without a fill of its own
+    #[serde(deserialize_with = "deserialize_color")]
+    pub color: Color,
+}
+
+impl Default for IconStyle {
+    fn default() -> Self {
+        Self {
+            color: Color::new(0xFF333333),
+        }
+    }
+}
+
+// A style struct that belongs to a kind of widget. The key names the section of the theme it comes from.
+pub trait WidgetStyle: Clone + 'static {
+    const KEY: &'static str;
+
+    fn from_theme(theme: &Theme) -> &Self;
+}
+
+impl WidgetStyle for TextButtonStyle {
+    const KEY: &'static str = "button";
+
+    fn from_theme(theme: &Theme) -> &Self {
+        &theme.button
+    }
+}
+
+impl WidgetStyle for SliderStyle {
+    const KEY: &'static str = "slider";
+
+    fn from_theme(theme: &Theme) -> &Self {
+        &theme.slider
+    }
+}
+
+impl WidgetStyle for SwitchStyle {
+    const KEY: &'static str = "switch";
+
+    fn from_theme(theme: &Theme) -> &Self {
+        &theme.switch
+    }
+}
+
+impl WidgetStyle for CheckboxStyle {
+    const KEY: &'static str = "checkbox";
+
+    fn from_theme(theme: &Theme) -> &Self {
+        &theme.checkbox
+    }
+}
+
+impl WidgetStyle for RadioStyle {
+    const KEY: &'static str = "radio";
+
+    fn from_theme(theme: &Theme) -> &Self {
+        &theme.radio
+    }
+}
+
+impl WidgetStyle for TextBoxStyle {
+    const KEY: &'static str = "text_box";
+
+    fn from_theme(theme: &Theme) -> &Self {
+        &theme.text_box
+    }
+}
+
+impl WidgetStyle for MenuBarStyle {
+    const KEY: &'static str = "menu_bar";
+
+    fn from_theme(theme: &Theme) -> &Self {
+        &theme.menu_bar
+    }
+}
+
+impl WidgetStyle for LabelStyle {
+    const KEY: &'static str = "label";
+
+    fn from_theme(theme: &Theme) -> &Self {
+        &theme.label
+    }
+}
+
+impl WidgetStyle for ImageStyle {
+    const KEY: &'static str = "image";
+
+    fn from_theme(theme: &Theme) -> &Self {
+        &theme.image
+    }
+}
+
+impl WidgetStyle for IconStyle {
+    const KEY: &'static str = "icon";
+
+    fn from_theme(theme: &Theme) -> &Self {
+        &theme.icon
+    }
+}
+
+impl WidgetStyle for TooltipStyle {
+    const KEY: &'static str = "tooltip";
+
+    fn from_theme(theme: &Theme) -> &Self {
+        &theme.tooltip
+    }
+}
+
+type StyleOverride = Box<dyn Fn(&Theme, &mut dyn Any)>;
+
+// A named set of changes to widget styles. Every change gets the active theme,
+// so a class can derive its colors from the theme instead of hardcoding them.
+#[derive(Default)]
+pub struct StyleClass {
+    overrides: Vec<(&'static str, StyleOverride)>,
+}
+
+impl StyleClass {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with<S, F>(mut self, f: F) -> Self
+    where
+        S: WidgetStyle,
+        F: Fn(&Theme, &mut S) + 'static,
+    {
+        self.push(f);
+        self
+    }
+
+    pub(crate) fn push<S, F>(&mut self, f: F)
+    where
+        S: WidgetStyle,
+        F: Fn(&Theme, &mut S) + 'static,
+    {
+        self.overrides.push((
+            S::KEY,
+            Box::new(move |theme, style| {
+                if let Some(style) = style.downcast_mut::<S>() {
+                    f(theme, style)
+                }
+            }),
+        ))
+    }
+
+    fn applies_to<S: WidgetStyle>(&self) -> bool {
+        self.overrides.iter().any(|(key, _)| *key == S::KEY)
+    }
+
+    fn apply<S: WidgetStyle>(&self, theme: &Theme, style: &mut S) {
+        for (_, f) in self.overrides.iter().filter(|(key, _)| *key == S::KEY) {
+            f(theme, style)
+        }
+    }
+}
+
+// The classes and inline overrides attached to a single widget
 #[derive(Default)]
+pub struct StyleScope {
+    classes: Vec<String>,
+    overrides: StyleClass,
+}
+
+impl StyleScope {
+    pub fn add_class(&mut self, class: &str) {
+        self.classes.push(class.to_string())
+    }
+
+    pub fn add_override<S, F>(&mut self, f: F)
+    where
+        S: WidgetStyle,
+        F: Fn(&Theme, &mut S) + 'static,
+    {
+        self.overrides.push(f)
+    }
+
+    pub fn classes(&self) -> &[String] {
+        &self.classes
+    }
+
+    // Resolves the style for a widget: the theme default, then every class in the order
+    // they were added, then the inline overrides. Only clones the default when something changes it.
+    pub fn resolve<'t, S: WidgetStyle>(
+        &self,
+        theme: &'t Theme,
+        classes: &HashMap<String, StyleClass>,
+    ) -> Cow<'t, S> {
+        let mut style = Cow::Borrowed(S::from_theme(theme));
+        for class in self.classes.iter().filter_map(|name| classes.get(name)) {
+            if class.applies_to::<S>() {
+                class.apply(theme, style.to_mut());
+            }
+        }
+
+        if self.overrides.applies_to::<S>() {
+            self.overrides.apply(theme, style.to_mut());
+        }
+
+        style
+    }
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(default)]
 pub struct Theme {
+    #[serde(deserialize_with = "deserialize_color")]
     pub background: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub primary: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub secondary: Color,
+    #[serde(deserialize_with = "deserialize_color")]
     pub text: Color,
 
+    pub font: FontStyle,
+
     pub button: TextButtonStyle,
     pub slider: SliderStyle,
+    pub switch: SwitchStyle,
+    pub checkbox: CheckboxStyle,
+    pub radio: RadioStyle,
+    pub text_box: TextBoxStyle,
+    pub menu_bar: MenuBarStyle,
+    pub tooltip: TooltipStyle,
+    pub label: LabelStyle,
+    pub image: ImageStyle,
+    pub icon: IconStyle,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::default_light()
+    }
 }
 
 impl Theme {
@@ -48,15 +646,18 @@ impl Theme {
             primary: Color::new(0xFF766AC8),
             secondary: Color::new(0xFF73C8A6),
             text: Color::new(0xFFFFFFFF),
-            button: TextButtonStyle::new(),
-            slider: SliderStyle {
-                background: Color::new(0xFFFFFFFF),
-                fill: Color::new(0xFF766AC8),
-                thumb: SliderThumbStyle {
-                    color: Color::new(0xFF73C8A6),
-                    size: 0.125,
-                },
-            },
+            font: FontStyle::default(),
+            button: TextButtonStyle::default(),
+            slider: SliderStyle::default(),
+            switch: SwitchStyle::default(),
+            checkbox: CheckboxStyle::default(),
+            radio: RadioStyle::default(),
+            text_box: TextBoxStyle::default(),
+            menu_bar: MenuBarStyle::default(),
+            tooltip: TooltipStyle::default(),
+            label: LabelStyle::default(),
+            image: ImageStyle::default(),
+            icon: IconStyle::default(),
         }
     }
 
@@ -66,7 +667,8 @@ impl Theme {
             primary: Color::new(0xFF1E38A1),
             secondary: Color::new(0xFF73C8A6),
             text: Color::new(0xFFFFFFFF),
-            button: TextButtonStyle::new(),
+            font: FontStyle::default(),
+            button: TextButtonStyle::from_primary(Color::new(0xFF1E38A1), Color::new(0xFFFFFFFF)),
             slider: SliderStyle {
                 background: Color::new(0xFF1E1E1E),
                 fill: Color::new(0xFF1E38A1),
@@ -74,14 +676,166 @@ impl Theme {
                     color: Color::new(0xFF1E38A1),
                     size: 0.125,
                 },
+                rounding: 4.0,
+            },
+            switch: SwitchStyle {
+                fill: Color::new(0xFF1E38A1),
+                rounding: 4.0,
+            },
+            checkbox: CheckboxStyle {
+                fill: Color::new(0xFF1E38A1),
+                border: Color::new(0xFFAAAAAA),
+                check: Color::new(0xFFFFFFFF),
+                text: Color::new(0xFFFFFFFF),
+                focus: Color::new(0x801E38A1),
+                rounding: 3.0,
+            },
+            radio: RadioStyle {
+                fill: Color::new(0xFF8FA8FF),
+                border: Color::new(0xFFAAAAAA),
+                text: Color::new(0xFFFFFFFF),
+                focus: Color::new(0x801E38A1),
+            },
+            text_box: TextBoxStyle {
+                background: Color::new(0xFF1E1E1E),
+                border: Color::new(0xFF555555),
+                selection: Color::new(0xFF1E38A1),
+                text: Color::new(0xFFFFFFFF),
+                placeholder: Color::new(0x80FFFFFF),
+            },
+            menu_bar: MenuBarStyle {
+                background: Color::new(0xFF2B2B2B),
+                text: Color::new(0xFFFFFFFF),
+                disabled: Color::new(0x60FFFFFF),
+                highlight: Color::new(0xFF1E38A1),
+                menu_background: Color::new(0xFF1E1E1E),
+                border: Color::new(0xFF555555),
+                rounding: 4.0,
+            },
+            tooltip: TooltipStyle {
+                background: Color::new(0xFFE6E6E6),
+                text: Color::new(0xFF000000),
+                ..TooltipStyle::default()
+            },
+            label: LabelStyle {
+                text: Color::new(0xFFFFFFFF),
+                link: Color::new(0xFF8FA8FF),
+                selection: Color::new(0x801E38A1),
+            },
+            image: ImageStyle {
+                placeholder: Color::new(0xFF2B2B2B),
+            },
+            icon: IconStyle {
+                color: Color::new(0xFFFFFFFF),
             },
         }
     }
+
+    pub fn from_toml(source: &str) -> Result<Self, ThemeError> {
+        Ok(toml::from_str(source)?)
+    }
+
+    pub fn from_json(source: &str) -> Result<Self, ThemeError> {
+        Ok(serde_json::from_str(source)?)
+    }
+
+    pub fn from_ron(source: &str) -> Result<Self, ThemeError> {
+        Ok(ron::from_str(source)?)
+    }
+
+    // The format is picked by the file extension
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ThemeError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+
+        let parse = match extension.as_deref() {
+            Some("toml") => Self::from_toml,
+            Some("json") => Self::from_json,
+            Some("ron") => Self::from_ron,
+            _ => return Err(ThemeError::UnsupportedFormat(path.to_path_buf())),
+        };
+
+        parse(&std::fs::read_to_string(path)?)
+    }
+}
+
+// Watches the directories of theme files instead of the files themselves,
+// most editors save by replacing the file which would end a watch on the file.
+// Every event wakes the event loop through the proxy, so it can sleep while nothing changes.
+struct ThemeWatcher {
+    watcher: RecommendedWatcher,
+    events: mpsc::Receiver<notify::Result<notify::Event>>,
+    directories: HashSet<PathBuf>,
+    files: HashMap<PathBuf, String>,
+}
+
+impl ThemeWatcher {
+    fn new(proxy: Option<EventLoopProxy<AppEvent>>) -> Result<Self, ThemeError> {
+        let (tx, events) = mpsc::channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            if tx.send(event).is_ok() {
+                if let Some(proxy) = &proxy {
+                    let _ = proxy.send_event(AppEvent::Wake);
+                }
+            }
+        })?;
+        Ok(Self {
+            watcher,
+            events,
+            directories: HashSet::new(),
+            files: HashMap::new(),
+        })
+    }
+
+    fn watch(&mut self, name: &str, path: &Path) -> Result<(), ThemeError> {
+        if let Some(directory) = path.parent() {
+            if !self.directories.contains(directory) {
+                self.watcher.watch(directory, RecursiveMode::NonRecursive)?;
+                self.directories.insert(directory.to_path_buf());
+            }
+        }
+
+        self.files.insert(path.to_path_buf(), name.to_string());
+        Ok(())
+    }
+
+    // Names and paths of the watched themes that changed since the last call
+    fn changed(&self) -> Vec<(String, PathBuf)> {
+        let mut changed: Vec<(String, PathBuf)> = Vec::new();
+        while let Ok(event) = self.events.try_recv() {
+            let event = match event {
+                Ok(event) => event,
+                Err(_) => continue,
+            };
+
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                continue;
+            }
+
+            for path in event.paths {
+                let path = path.canonicalize().unwrap_or(path);
+                if let Some(name) = self.files.get(&path) {
+                    if !changed.iter().any(|(n, _)| n == name) {
+                        changed.push((name.clone(), path));
+                    }
+                }
+            }
+        }
+
+        changed
+    }
 }
 
-#[derive(Default)]
 pub struct StyleContext {
     themes: HashMap<String, Theme>,
+    classes: HashMap<String, StyleClass>,
+    icons: Icons,
+    fallback: Theme,
+    watcher: Option<ThemeWatcher>,
+    proxy: Option<EventLoopProxy<AppEvent>>,
 }
 
 impl StyleContext {
@@ -89,10 +843,108 @@ impl StyleContext {
         let mut themes = HashMap::new();
         themes.insert("light".to_string(), Theme::default_light());
         themes.insert("dark".to_string(), Theme::default_dark());
-        Self { themes }
+        Self {
+            themes,
+            classes: HashMap::new(),
+            icons: Icons::new(),
+            fallback: Theme::default(),
+            watcher: None,
+            proxy: None,
+        }
+    }
+
+    pub(crate) fn with_proxy(mut self, proxy: EventLoopProxy<AppEvent>) -> Self {
+        self.proxy = Some(proxy);
+        self
     }
 
     pub fn theme(&self, name: &str) -> Option<&Theme> {
         self.themes.get(name)
     }
+
+    // Like theme but never fails, windows asking for an unknown theme are painted with the default one
+    pub fn theme_or_default(&self, name: &str) -> &Theme {
+        self.themes.get(name).unwrap_or(&self.fallback)
+    }
+
+    pub fn theme_names(&self) -> impl Iterator<Item = &str> {
+        self.themes.keys().map(|name| name.as_str())
+    }
+
+    // Registers a theme, replacing any theme with the same name
+    pub fn register(&mut self, name: &str, theme: Theme) {
+        self.themes.insert(name.to_string(), theme);
+    }
+
+    // Classes are shared by all themes, attach them to widgets with WidgetExt::with_class
+    pub fn register_class(&mut self, name: &str, class: StyleClass) {
+        self.classes.insert(name.to_string(), class);
+    }
+
+    pub(crate) fn classes(&self) -> &HashMap<String, StyleClass> {
+        &self.classes
+    }
+
+    // Icons are shared by all themes and painted in the theme's icon color, show them with the Icon widget
+    pub fn register_icon(&mut self, name: &str, svg: &str) -> Result<(), IconError> {
+        self.icons.register(name, svg)
+    }
+
+    pub fn register_icon_file(
+        &mut self,
+        name: &str,
+        path: impl AsRef<Path>,
+    ) -> Result<(), IconError> {
+        self.icons.register_file(name, path)
+    }
+
+    pub fn icons(&self) -> &Icons {
+        &self.icons
+    }
+
+    pub fn load(&mut self, name: &str, path: impl AsRef<Path>) -> Result<(), ThemeError> {
+        let theme = Theme::from_file(path)?;
+        self.register(name, theme);
+        Ok(())
+    }
+
+    // Loads the theme and reloads it whenever the file changes on disk, meant for iterating on a theme during development
+    pub fn watch(&mut self, name: &str, path: impl AsRef<Path>) -> Result<(), ThemeError> {
+        let path = path.as_ref().canonicalize()?;
+        self.load(name, &path)?;
+
+        if self.watcher.is_none() {
+            self.watcher = Some(ThemeWatcher::new(self.proxy.clone())?);
+        }
+
+        if let Some(watcher) = self.watcher.as_mut() {
+            watcher.watch(name, &path)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn is_watching(&self) -> bool {
+        self.watcher.is_some()
+    }
+
+    // Reloads watched themes whose file changed. Returns the names of the reloaded themes,
+    // a theme that fails to parse keeps its previous version.
+    pub fn reload_changed(&mut self) -> Vec<Result<String, ThemeError>> {
+        let changed = match &self.watcher {
+            Some(watcher) => watcher.changed(),
+            None => return Vec::new(),
+        };
+
+        changed
+            .into_iter()
+            .map(|(name, path)| self.load(&name, &path).map(|_| name))
+            .collect()
+    }
+}
+
+impl Default for StyleContext {
+    fn default() -> Self {
+        Self::new()
+    }
 }
@@ -0,0 +1,115 @@
+use crate::{
+    app::AppState,
+    canvas::{Canvas2D, Rect, Size},
+    constraints::BoxConstraints,
+    widget::{
+        accessibility::AccessNode,
+        style::{StyleScope, WidgetStyle},
+        ChildSlot, Event, EventCtx, LayoutCtx, PaintCtx, Theme, Widget,
+    },
+};
+use std::any::Any;
+
+// Attaches style classes and inline overrides to a widget. Everything else is forwarded to the wrapped widget.
+pub struct Styled<W> {
+    scope: StyleScope,
+    widget: W,
+}
+
+impl<W> Styled<W> {
+    pub fn new(widget: W) -> Self {
+        Self {
+            scope: StyleScope::default(),
+            widget,
+        }
+    }
+
+    // Classes are applied in the order they are added, later classes win
+    pub fn with_class(mut self, class: &str) -> Self {
+        self.scope.add_class(class);
+        self
+    }
+
+    pub fn with_style<S, F>(mut self, f: F) -> Self
+    where
+        S: WidgetStyle,
+        F: Fn(&Theme, &mut S) + 'static,
+    {
+        self.scope.add_override(f);
+        self
+    }
+}
+
+impl<State: AppState, W: Widget<State>> Widget<State> for Styled<W> {
+    fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, state: &State) -> bool {
+        self.widget.event(event, ctx, state)
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, state: &State) -> Size {
+        self.widget.layout(constraints, ctx, state)
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
+        self.widget
+            .paint(theme, &ctx.with_scope(&self.scope), canvas, state)
+    }
+
+    fn paint_overlay(
+        &self,
+        theme: &Theme,
+        ctx: &PaintCtx,
+        canvas: &mut dyn Canvas2D,
+        state: &State,
+    ) {
+        self.widget
+            .paint_overlay(theme, &ctx.with_scope(&self.scope), canvas, state)
+    }
+
+    fn uid(&self) -> usize {
+        self.widget.uid()
+    }
+
+    fn flex(&self) -> f32 {
+        self.widget.flex()
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut ChildSlot<State>> {
+        self.widget.children_mut()
+    }
+
+    fn children(&self) -> Vec<&ChildSlot<State>> {
+        self.widget.children()
+    }
+
+    fn key(&self) -> Option<&str> {
+        self.widget.key()
+    }
+
+    fn type_name(&self) -> &'static str {
+        self.widget.type_name()
+    }
+
+    fn caret_rect(&self, size: &Size, state: &State) -> Option<Rect> {
+        self.widget.caret_rect(size, state)
+    }
+
+    fn accessibility(&self, state: &State) -> Option<AccessNode> {
+        self.widget.accessibility(state)
+    }
+
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        self.widget.take_local_state()
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        self.widget.restore_local_state(local_state)
+    }
+
+    fn take_layout_state(&mut self) -> Option<Box<dyn Any>> {
+        self.widget.take_layout_state()
+    }
+
+    fn restore_layout_state(&mut self, layout: Box<dyn Any>) -> bool {
+        self.widget.restore_layout_state(layout)
+    }
+}
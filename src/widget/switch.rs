@@ -1,29 +1,71 @@
 use crate::{
+    animation::{Animated, Spring, Transition},
     app::AppState,
     canvas::{Canvas2D, Paint, Point, Rect, Size},
     constraints::BoxConstraints,
-    widget::{style::Theme, Event, EventCtx, MouseEvent, PaintCtx, Widget},
+    lens::Binding,
+    widget::{
+        accessibility::{AccessAction, AccessNode, AccessValue, Role},
+        style::{SwitchStyle, Theme},
+        Event, EventCtx, MouseEvent, PaintCtx, Widget,
+    },
 };
 
 use super::LayoutCtx;
+use std::any::Any;
 
 enum SwitchState {
     Active,
     Inactive,
 }
 
-pub struct Switch {
+pub struct Switch<State: AppState> {
     value_changed: Option<Box<dyn Fn(bool)>>,
+    binding: Option<Binding<State, bool>>,
     active: bool,
     state: SwitchState,
+    // Position of the thumb, 0 is off and 1 is on
+    thumb: Animated<f32>,
+    // Size of the hover halo, 0 is hidden
+    halo: Animated<f32>,
 }
 
-impl Switch {
+fn thumb_animation() -> Animated<f32> {
+    Animated::new(0.0)
+        .with_transition(Transition::spring(Spring::smooth()))
+        .with_initial_jump()
+}
+
+impl<State: AppState> Switch<State> {
     pub fn new() -> Self {
         Self {
             value_changed: None,
+            binding: None,
             active: false,
             state: SwitchState::Inactive,
+            thumb: thumb_animation(),
+            halo: Animated::new(0.0),
+        }
+    }
+
+    // Shows the value from the state and publishes the message from on_change when the user toggles the switch.
+    pub fn bind<G, F>(self, get: G, on_change: F) -> Self
+    where
+        G: Fn(&State) -> &bool + 'static,
+        F: Fn(&bool) -> State::Message + 'static,
+    {
+        self.with_binding(Binding::new(get, on_change))
+    }
+
+    pub fn with_binding(mut self, binding: Binding<State, bool>) -> Self {
+        self.binding = Some(binding);
+        self
+    }
+
+    fn is_active(&self, state: &State) -> bool {
+        match &self.binding {
+            Some(binding) => *binding.get(state),
+            None => self.active,
         }
     }
 
@@ -39,10 +81,20 @@ impl Switch {
         self.value_changed = Some(Box::new(f));
         self
     }
+
+    fn toggle(&mut self, ctx: &mut EventCtx<State::Message>, state: &State) {
+        self.active = !self.is_active(state);
+        if let Some(l) = &self.value_changed {
+            (l)(self.active);
+        }
+        if let Some(binding) = &self.binding {
+            ctx.publish(binding.changed(&self.active))
+        }
+    }
 }
 
-impl<State: AppState> Widget<State> for Switch {
-    fn event(&mut self, event: &Event, _: &mut EventCtx<State::Message>, _: &State) -> bool {
+impl<State: AppState> Widget<State> for Switch<State> {
+    fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, state: &State) -> bool {
         match event {
             Event::Mouse(MouseEvent::MouseEnter(_)) => {
                 self.state = SwitchState::Active;
@@ -53,10 +105,11 @@ impl<State: AppState> Widget<State> for Switch {
                 true
             }
             Event::Mouse(MouseEvent::MouseDown(_)) => {
-                self.active = !self.active;
-                if let Some(l) = &self.value_changed {
-                    (l)(self.active);
-                }
+                self.toggle(ctx, state);
+                true
+            }
+            Event::Access(request) if request.action == AccessAction::Press => {
+                self.toggle(ctx, state);
                 true
             }
             Event::Mouse(MouseEvent::MouseUp(_)) => {
@@ -67,7 +120,7 @@ impl<State: AppState> Widget<State> for Switch {
         }
     }
 
-    fn layout(&mut self, constraints: &BoxConstraints, _ctx: &mut LayoutCtx, _: &State) -> Size {
+    fn layout(&mut self, constraints: &BoxConstraints, _: &mut LayoutCtx, _: &State) -> Size {
         // Boldly unwrapping here. If you have not given constraints to a switch then we don't know how big it should be.
         Size::new(
             constraints.max_width().unwrap(),
@@ -75,13 +128,39 @@ impl<State: AppState> Widget<State> for Switch {
         )
     }
 
-    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, _: &State) {
+    fn accessibility(&self, state: &State) -> Option<AccessNode> {
+        Some(
+            AccessNode::new(Role::Switch)
+                .with_value(AccessValue::Toggled(self.is_active(state)))
+                .with_action(AccessAction::Press),
+        )
+    }
+
+    // Only the hover state and running animations are local, whether the switch is on comes from whoever builds it.
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new((
+            std::mem::replace(&mut self.state, SwitchState::Inactive),
+            std::mem::replace(&mut self.thumb, thumb_animation()),
+            std::mem::replace(&mut self.halo, Animated::new(0.0)),
+        )))
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        if let Ok(local_state) =
+            local_state.downcast::<(SwitchState, Animated<f32>, Animated<f32>)>()
+        {
+            (self.state, self.thumb, self.halo) = *local_state
+        }
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
         let rect = ctx.rect();
+        let style = ctx.style::<SwitchStyle>(theme);
 
         let mut fill_paint = Paint::default();
         fill_paint.set_anti_alias(true);
 
-        let rounding = 4.0;
+        let rounding = style.rounding;
         let gutter_height = rect.height() / 4.0;
         let thumb_size = gutter_height * 1.25;
         let active_thumb_size = thumb_size * 1.5;
@@ -91,7 +170,7 @@ impl<State: AppState> Widget<State> for Switch {
         let mut unfill_rect = Rect::from_wh(rect.width(), gutter_height);
         unfill_rect.offset(Point::new(0.0, rect.center_y() - unfill_rect.center_y()));
 
-        fill_paint.set_color(theme.slider.fill);
+        fill_paint.set_color(style.fill);
         fill_paint.set_stroke(true);
         canvas.draw_rounded_rect(&unfill_rect, rounding, rounding, &fill_paint);
 
@@ -103,29 +182,39 @@ impl<State: AppState> Widget<State> for Switch {
         let mut fill_rect = Rect::from_wh(0.0, gutter_height);
         fill_rect.offset(Point::new(0.0, rect.center_y() - fill_rect.center_y()));
         canvas.draw_rounded_rect(&fill_rect, rounding, rounding, &fill_paint);
-        let thumb_position = if self.active {
-            rect.right() - thumb_size * 0.5
+
+        let now = ctx.now();
+        let on = if self.is_active(state) { 1.0 } else { 0.0 };
+        let hovered = if let SwitchState::Active = self.state {
+            1.0
         } else {
             0.0
         };
+        let thumb = self.thumb.animate_to(on, now);
+        let halo = self.halo.animate_to(hovered, now);
+        if self.thumb.is_animating(now) || self.halo.is_animating(now) {
+            ctx.request_animation_frame()
+        }
+
+        let thumb_position = thumb * (rect.right() - thumb_size * 0.5);
         canvas.draw_circle(
             &Point::new(thumb_position, rect.center_y()),
             thumb_size,
             &fill_paint,
         );
 
-        if let SwitchState::Active = self.state {
-            fill_paint.set_alpha_f(0.25);
+        if halo > 0.0 {
+            fill_paint.set_alpha_f(0.25 * halo);
             canvas.draw_circle(
                 &Point::new(thumb_position, rect.center_y()),
-                active_thumb_size,
+                thumb_size + (active_thumb_size - thumb_size) * halo,
                 &fill_paint,
             );
         }
     }
 }
 
-impl Default for Switch {
+impl<State: AppState> Default for Switch<State> {
     fn default() -> Self {
         Self::new()
     }
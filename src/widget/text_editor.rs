@@ -1,14 +1,20 @@
 use crate::{
-    app::AppState,
+    app::{AppState, Fonts},
     canvas::{
-        textlayout::{self, FontCollection, ParagraphBuilder, ParagraphStyle, TextStyle},
+        textlayout::{
+            self, Paragraph, ParagraphBuilder, ParagraphStyle, TextDecoration, TextStyle,
+        },
         Canvas2D, Color, Color4f, Paint, Point, Rect, Size,
     },
     constraints::BoxConstraints,
-    widget::{style::Theme, Event, EventCtx, KeyEvent, PaintCtx, Widget},
+    lens::Binding,
+    widget::{
+        accessibility::{AccessAction, AccessNode, AccessValue, ActionData, Role},
+        style::{TextBoxStyle, Theme},
+        Event, EventCtx, KeyEvent, PaintCtx, Widget,
+    },
 };
-use skia_safe::FontMgr;
-use std::ops::Range;
+use std::{any::Any, ops::Range};
 use winit::{
     event::{ElementState, VirtualKeyCode},
     window::CursorIcon,
@@ -16,28 +22,189 @@ use winit::{
 
 use super::{LayoutCtx, MouseEvent};
 
+// Commands a keyed TextBox handles, see WindowCtx::send_command
+pub enum TextBoxCommand {
+    SetText(String),
+    Clear,
+}
+
 #[derive(Default)]
 struct EditorState {
     text: String,
+    // Byte offset in the text
     caret_position: usize,
     selection: Range<usize>,
+    focused: bool,
+    // What the input method is composing, shown at the caret but not part of the text yet
+    preedit: String,
+    preedit_cursor: Option<(usize, usize)>,
+}
+
+fn previous_boundary(text: &str, index: usize) -> usize {
+    text[..index]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+// The caret is kept for the text it was moved in, the text it's shown in may be shorter or
+// have other characters at its position when the state changed in the meantime
+fn clamp_to_char_boundary(text: &str, caret: usize) -> usize {
+    let mut caret = caret.min(text.len());
+    while !text.is_char_boundary(caret) {
+        caret -= 1
+    }
+    caret
+}
+
+fn next_boundary(text: &str, index: usize) -> usize {
+    text[index..]
+        .chars()
+        .next()
+        .map_or(index, |c| index + c.len_utf8())
+}
+
+// A thin rect at the byte offset in the shown text. Skia counts text in utf-16 code units.
+fn caret_at(paragraph: &Paragraph, text: &str, index: usize) -> Rect {
+    let start = text[..previous_boundary(text, index)]
+        .encode_utf16()
+        .count();
+    let end = text[..index].encode_utf16().count();
+    let x = paragraph
+        .get_rects_for_range(
+            start..end,
+            textlayout::RectHeightStyle::Tight,
+            textlayout::RectWidthStyle::Tight,
+        )
+        .last()
+        .map_or(0.0, |textbox| textbox.rect.right);
+    Rect::from_xywh(x + 2.0, 0.0, 1.0, paragraph.height())
 }
 
-pub struct TextBox<State> {
+pub struct TextBox<State: AppState> {
     state: EditorState,
     placeholder: String,
     style: ParagraphStyle,
     on_commit: Option<Box<dyn Fn(&str, &State)>>,
+    binding: Option<Binding<State, str>>,
+    // Kept from the last layout, caret_rect has no context to get them from
+    fonts: Option<Fonts>,
 }
 
-impl<State> TextBox<State> {
+impl<State: AppState> TextBox<State> {
     pub fn new(placeholder: &str) -> Self {
         Self {
             state: EditorState::default(),
             placeholder: placeholder.to_string(),
             style: ParagraphStyle::new(),
             on_commit: None,
+            binding: None,
+            fonts: None,
+        }
+    }
+
+    // Shows the text from the state and publishes the message from on_change after every edit.
+    pub fn bind<G, F>(self, get: G, on_change: F) -> Self
+    where
+        G: Fn(&State) -> &str + 'static,
+        F: Fn(&str) -> State::Message + 'static,
+    {
+        self.with_binding(Binding::new(get, on_change))
+    }
+
+    pub fn with_binding(mut self, binding: Binding<State, str>) -> Self {
+        self.binding = Some(binding);
+        self
+    }
+
+    fn text<'a>(&'a self, state: &'a State) -> &'a str {
+        match &self.binding {
+            Some(binding) => binding.get(state),
+            None => &self.state.text,
+        }
+    }
+
+    // The state may have been changed by someone else since the last edit
+    fn sync_text(&mut self, state: &State) {
+        if let Some(binding) = &self.binding {
+            let text = binding.get(state);
+            if self.state.text != text {
+                self.state.text = text.to_string();
+                self.state.caret_position =
+                    clamp_to_char_boundary(&self.state.text, self.state.caret_position);
+            }
+        }
+    }
+
+    fn notify_text_changed(&self, ctx: &mut EventCtx<State::Message>) {
+        if let Some(binding) = &self.binding {
+            ctx.publish(binding.changed(&self.state.text))
+        }
+    }
+
+    fn insert(&mut self, text: &str, ctx: &mut EventCtx<State::Message>) {
+        self.state.text.insert_str(self.state.caret_position, text);
+        self.state.caret_position += text.len();
+        self.notify_text_changed(ctx);
+    }
+
+    fn shown_text(&self, text: &str) -> String {
+        let caret = clamp_to_char_boundary(text, self.state.caret_position);
+        [&text[..caret], &self.state.preedit, &text[caret..]].concat()
+    }
+
+    // The text as shown, with what the input method is composing at the caret.
+    // Returns the paragraph and where the caret is in the shown text.
+    fn paragraph(
+        &self,
+        text: &str,
+        width: f32,
+        text_color: Color,
+        placeholder_color: Color,
+    ) -> (Paragraph, usize) {
+        let fonts = self.fonts.clone().unwrap_or_default();
+        let mut paragraph_builder = ParagraphBuilder::new(&self.style, fonts.collection());
+        let mut ts = TextStyle::new();
+        ts.set_font_families(&fonts.families(None));
+        ts.set_font_size(18.0);
+
+        let preedit = &self.state.preedit;
+        let caret = clamp_to_char_boundary(text, self.state.caret_position);
+        if text.is_empty() && preedit.is_empty() {
+            ts.set_foreground_color(Paint::new(Color4f::from(placeholder_color), None));
+            paragraph_builder.push_style(&ts);
+            paragraph_builder.add_text(&self.placeholder);
+        } else {
+            ts.set_foreground_color(Paint::new(Color4f::from(text_color), None));
+            paragraph_builder.push_style(&ts);
+            paragraph_builder.add_text(&text[..caret]);
+
+            let mut preedit_style = ts.clone();
+            let decoration = preedit_style.decoration_mut();
+            decoration.ty = TextDecoration::UNDERLINE;
+            decoration.color = text_color;
+            decoration.thickness_multiplier = 1.0;
+            paragraph_builder.push_style(&preedit_style);
+            paragraph_builder.add_text(preedit);
+            paragraph_builder.pop();
+
+            paragraph_builder.add_text(&text[caret..]);
         }
+
+        let mut paragraph = paragraph_builder.build();
+        paragraph.layout(width - 4.0);
+        let cursor = match self.state.preedit_cursor {
+            Some((_, end)) if !preedit.is_empty() => end,
+            _ => preedit.len(),
+        };
+        (paragraph, caret + cursor)
+    }
+
+    fn set_text(&mut self, text: &str, ctx: &mut EventCtx<State::Message>) {
+        self.state.text = text.to_string();
+        self.state.caret_position = self.state.text.len();
+        self.state.selection = 0..0;
+        self.notify_text_changed(ctx);
     }
 
     pub fn on_commit<F>(mut self, f: F) -> Self
@@ -65,16 +232,36 @@ impl<State: AppState + 'static> Widget<State> for TextBox<State> {
                 event_ctx.change_cursor(CursorIcon::Default);
                 true
             }
+            Event::Mouse(MouseEvent::MouseDown(_)) => {
+                event_ctx.request_focus();
+                true
+            }
+            Event::Focus(focused) => {
+                self.state.focused = *focused;
+                self.state.preedit.clear();
+                true
+            }
+            // While the input method composes it handles the keys itself
+            Event::Key(KeyEvent::Input(_)) if !self.state.preedit.is_empty() => true,
             Event::Key(KeyEvent::Input(event)) => {
+                self.sync_text(state);
+                let caret = self.state.caret_position;
                 if let Some(keycode) = event.virtual_keycode {
                     if event.state == ElementState::Pressed {
                         match keycode {
-                            VirtualKeyCode::Left => self.state.caret_position -= 1,
-                            VirtualKeyCode::Right => self.state.caret_position += 1,
+                            VirtualKeyCode::Left => {
+                                self.state.caret_position =
+                                    previous_boundary(&self.state.text, caret)
+                            }
+                            VirtualKeyCode::Right => {
+                                self.state.caret_position = next_boundary(&self.state.text, caret)
+                            }
                             VirtualKeyCode::Back => {
-                                if self.state.caret_position > 0 {
-                                    self.state.text.remove(self.state.caret_position - 1);
-                                    self.state.caret_position -= 1;
+                                if caret > 0 {
+                                    let start = previous_boundary(&self.state.text, caret);
+                                    self.state.text.replace_range(start..caret, "");
+                                    self.state.caret_position = start;
+                                    self.notify_text_changed(event_ctx);
                                 }
                             }
                             VirtualKeyCode::Return => {
@@ -89,24 +276,59 @@ impl<State: AppState + 'static> Widget<State> for TextBox<State> {
 
                 true
             }
+            Event::Command(command) => match command.get::<TextBoxCommand>() {
+                Some(TextBoxCommand::SetText(text)) => {
+                    self.set_text(text, event_ctx);
+                    true
+                }
+                Some(TextBoxCommand::Clear) => {
+                    self.state.text.clear();
+                    self.state.caret_position = 0;
+                    self.state.selection = 0..0;
+                    self.notify_text_changed(event_ctx);
+                    true
+                }
+                None => false,
+            },
+            Event::Access(request) => match (request.action, &request.data) {
+                (AccessAction::SetValue, Some(ActionData::Value(text))) => {
+                    self.set_text(text, event_ctx);
+                    true
+                }
+                _ => false,
+            },
             Event::Key(KeyEvent::Char(char)) => {
-                if !char.is_ascii_control() {
-                    self.state.text.push(*char);
-                    self.state.caret_position += 1;
+                if !char.is_control() && self.state.preedit.is_empty() {
+                    self.sync_text(state);
+                    self.insert(char.encode_utf8(&mut [0; 4]), event_ctx);
                 }
 
                 true
             }
+            Event::Key(KeyEvent::Preedit { text, cursor }) => {
+                self.state.preedit = text.clone();
+                self.state.preedit_cursor = *cursor;
+                true
+            }
+            Event::Key(KeyEvent::Commit(text)) => {
+                self.sync_text(state);
+                self.state.preedit.clear();
+                self.insert(text, event_ctx);
+                true
+            }
 
             _ => false,
         }
     }
 
-    fn layout(&mut self, constraints: &BoxConstraints, _ctx: &mut LayoutCtx, _: &State) -> Size {
-        let mut font_collection = FontCollection::new();
-        font_collection.set_default_font_manager(FontMgr::new(), None);
-        let mut paragraph_builder = ParagraphBuilder::new(&self.style, font_collection);
+    // Sized by the placeholder, so the box doesn't change size while typing and the text doesn't have to be
+    // measured when the state changes
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, _: &State) -> Size {
+        let fonts = ctx.fonts();
+        self.fonts = Some(fonts.clone());
+        let mut paragraph_builder = ParagraphBuilder::new(&self.style, fonts.collection());
         let mut ts = TextStyle::new();
+        ts.set_font_families(&fonts.families(None));
         ts.set_font_size(18.0);
         ts.set_foreground_color(Paint::default());
         paragraph_builder.push_style(&ts);
@@ -116,26 +338,11 @@ impl<State: AppState + 'static> Widget<State> for TextBox<State> {
         Size::new(constraints.max_width().unwrap(), paragraph.height())
     }
 
-    fn paint(&self, _: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, _: &State) {
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
         let rect = ctx.rect();
-
-        let mut font_collection = FontCollection::new();
-        font_collection.set_default_font_manager(FontMgr::new(), None);
-        let mut paragraph_builder = ParagraphBuilder::new(&self.style, font_collection);
-        let mut ts = TextStyle::new();
-        ts.set_font_size(18.0);
-        if !self.state.text.is_empty() {
-            ts.set_foreground_color(Paint::default());
-            paragraph_builder.push_style(&ts);
-            paragraph_builder.add_text(&self.state.text);
-        } else {
-            ts.set_foreground_color(Paint::new(Color4f::new(0.0, 0.0, 0.0, 0.5), None));
-            paragraph_builder.push_style(&ts);
-            paragraph_builder.add_text(&self.placeholder);
-        }
-
-        let mut paragraph = paragraph_builder.build();
-        paragraph.layout(rect.width() - 4.0);
+        let style = ctx.style::<TextBoxStyle>(theme);
+        let text = self.text(state);
+        let (paragraph, caret) = self.paragraph(text, rect.width(), style.text, style.placeholder);
         let selection_boxes = paragraph.get_rects_for_range(
             self.state.selection.clone(),
             textlayout::RectHeightStyle::IncludeLineSpacingBottom,
@@ -148,19 +355,58 @@ impl<State: AppState + 'static> Widget<State> for TextBox<State> {
         }
 
         let mut border_paint = Paint::default();
-        border_paint.set_color(Color::from_rgb(255, 255, 255));
+        border_paint.set_color(style.background);
         canvas.draw_rect(&rect, &border_paint);
         if selected_rect.width() > 0.0 {
-            border_paint.set_color(Color::from_rgb(0, 0, 255));
+            border_paint.set_color(style.selection);
             canvas.draw_rect(&selected_rect, &border_paint);
         }
         border_paint.set_stroke(true);
-        border_paint.set_color(Color::from_rgb(0, 0, 0));
+        border_paint.set_color(style.border);
         canvas.draw_rect(&rect, &border_paint);
-        canvas.draw_paragraph(&Point::new(2.0, 0.0), &paragraph)
+        canvas.draw_paragraph(&Point::new(2.0, 0.0), &paragraph);
+
+        // The input method can hide the caret while composing
+        let caret_visible = self.state.preedit.is_empty() || self.state.preedit_cursor.is_some();
+        if self.state.focused && caret_visible {
+            let shown = self.shown_text(text);
+            let mut caret_paint = Paint::default();
+            caret_paint.set_color(style.text);
+            canvas.draw_rect(&caret_at(&paragraph, &shown, caret), &caret_paint);
+        }
+    }
+
+    fn caret_rect(&self, size: &Size, state: &State) -> Option<Rect> {
+        if !self.state.focused {
+            return None;
+        }
+
+        let text = self.text(state);
+        let (paragraph, caret) = self.paragraph(text, size.width, Color::BLACK, Color::BLACK);
+        Some(caret_at(&paragraph, &self.shown_text(text), caret))
     }
 
     fn flex(&self) -> f32 {
         0.0
     }
+
+    fn accessibility(&self, state: &State) -> Option<AccessNode> {
+        Some(
+            AccessNode::new(Role::TextInput)
+                .with_name(&self.placeholder)
+                .with_value(AccessValue::Text(self.text(state).to_string()))
+                .with_action(AccessAction::Focus)
+                .with_action(AccessAction::SetValue),
+        )
+    }
+
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new(std::mem::take(&mut self.state)))
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        if let Ok(state) = local_state.downcast::<EditorState>() {
+            self.state = *state
+        }
+    }
 }
@@ -0,0 +1,248 @@
+use crate::{
+    app::{AppState, TimerId},
+    canvas::{Canvas2D, Font, Paint, Point, Rect, Size, TextBlob},
+    constraints::BoxConstraints,
+    widget::{
+        style::{Theme, TooltipStyle},
+        ChildSlot, Event, EventCtx, MouseEvent, PaintCtx, Widget,
+    },
+    window::WindowEvent,
+};
+
+use super::LayoutCtx;
+use std::{any::Any, time::Duration};
+
+// How far below the cursor the tooltip appears
+const CURSOR_OFFSET: f32 = 20.0;
+
+enum TooltipContent<State> {
+    Text(String),
+    Widget(ChildSlot<State>),
+}
+
+#[derive(Default)]
+struct TooltipState {
+    hovering: bool,
+    // Where the tooltip shows up, in local coordinates. It stays put once it's shown.
+    cursor: Point,
+    timer: Option<TimerId>,
+    shown: bool,
+    // A click hides the tooltip until the mouse leaves the widget
+    dismissed: bool,
+}
+
+// Shows a popup near the cursor after the mouse rested on the child for a moment
+pub struct Tooltip<State> {
+    child: ChildSlot<State>,
+    content: TooltipContent<State>,
+    delay: Duration,
+    state: TooltipState,
+}
+
+impl<State: AppState> Tooltip<State> {
+    pub fn new(child: impl Widget<State> + 'static, text: &str) -> Self {
+        Self::with_content(child, TooltipContent::Text(text.to_string()))
+    }
+
+    // The widget is laid out without constraints, give it a size if it needs one
+    pub fn with_widget(
+        child: impl Widget<State> + 'static,
+        content: impl Widget<State> + 'static,
+    ) -> Self {
+        Self::with_content(child, TooltipContent::Widget(ChildSlot::new(content)))
+    }
+
+    fn with_content(child: impl Widget<State> + 'static, content: TooltipContent<State>) -> Self {
+        Self {
+            child: ChildSlot::new(child),
+            content,
+            delay: Duration::from_millis(500),
+            state: TooltipState::default(),
+        }
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn hide(&mut self, ctx: &mut EventCtx<State::Message>) {
+        if let Some(timer) = self.state.timer.take() {
+            ctx.cancel_timer(timer)
+        }
+        self.state.hovering = false;
+        self.state.shown = false;
+        self.state.dismissed = false;
+    }
+
+    fn popup_size(&self, style: &TooltipStyle, font: &Font) -> Size {
+        let content = match &self.content {
+            TooltipContent::Text(text) => {
+                Size::new(font.measure_str(text, None).0, style.font_size)
+            }
+            TooltipContent::Widget(content) => *content.size(),
+        };
+
+        Size::new(
+            content.width + 2.0 * style.padding,
+            content.height + 2.0 * style.padding,
+        )
+    }
+
+    // Below the cursor if it fits, above it otherwise, and always inside the window
+    fn popup_rect(&self, ctx: &PaintCtx, size: Size) -> Rect {
+        let origin = ctx.origin();
+        let window = ctx.window_size();
+        let cursor = origin + self.state.cursor;
+
+        let mut top = cursor.y + CURSOR_OFFSET;
+        if top + size.height > window.height {
+            top = cursor.y - size.height - CURSOR_OFFSET / 4.0
+        }
+        let top = top.min(window.height - size.height).max(0.0);
+        let left = cursor.x.min(window.width - size.width).max(0.0);
+
+        Rect::from_xywh(left - origin.x, top - origin.y, size.width, size.height)
+    }
+}
+
+fn contains(size: &Size, point: &Point) -> bool {
+    point.x >= 0.0 && point.x < size.width && point.y >= 0.0 && point.y < size.height
+}
+
+impl<State: AppState> Widget<State> for Tooltip<State> {
+    fn event(&mut self, event: &Event, ctx: &mut EventCtx<State::Message>, state: &State) -> bool {
+        match event {
+            // Enters of anything inside the child pass through here too
+            Event::Mouse(MouseEvent::MouseEnter(event)) => {
+                if !self.state.hovering {
+                    self.state.hovering = true;
+                    if !self.state.dismissed {
+                        self.state.timer = Some(ctx.start_timer(self.delay))
+                    }
+                }
+                if !self.state.shown {
+                    self.state.cursor = *event.local_position()
+                }
+            }
+            Event::Mouse(MouseEvent::MouseMove(event)) if !self.state.shown => {
+                self.state.cursor = *event.local_position()
+            }
+            // Every leave passes through here, only the ones that end outside the widget count
+            Event::Mouse(MouseEvent::MouseLeave(event))
+                if !contains(ctx.size(), event.local_position()) =>
+            {
+                self.hide(ctx)
+            }
+            Event::Mouse(MouseEvent::MouseDown(_)) => {
+                if let Some(timer) = self.state.timer.take() {
+                    ctx.cancel_timer(timer)
+                }
+                self.state.shown = false;
+                self.state.dismissed = true
+            }
+            Event::Timer(id) if self.state.timer == Some(*id) => {
+                self.state.timer = None;
+                self.state.shown = true
+            }
+            Event::Window(WindowEvent::CursorLeft | WindowEvent::Blurred) => self.hide(ctx),
+            _ => (),
+        }
+
+        let consumer = ctx.consumer();
+        self.child.event(event, ctx, state);
+
+        // Only takes the mouse when the child doesn't, a tooltip on a label still needs its enter and leave
+        matches!(event, Event::Mouse(MouseEvent::MouseMove(_))) && ctx.consumer() == consumer
+    }
+
+    fn layout(&mut self, constraints: &BoxConstraints, ctx: &mut LayoutCtx, state: &State) -> Size {
+        ctx.register_child(self.child.uid());
+        let size = self.child.layout(constraints, ctx, state);
+        self.child.set_size(&size);
+
+        // Not a child, it's neither hit tested nor part of the tree
+        if let TooltipContent::Widget(content) = &mut self.content {
+            content.invalidate_layout();
+            let mut content_ctx = LayoutCtx::new(ctx.fonts(), ctx.images());
+            let content_size = content.layout(&BoxConstraints::new(), &mut content_ctx, state);
+            content.set_size(&content_size);
+        }
+
+        size
+    }
+
+    fn paint(&self, theme: &Theme, ctx: &PaintCtx, canvas: &mut dyn Canvas2D, state: &State) {
+        self.child.paint(theme, ctx, canvas, state)
+    }
+
+    fn paint_overlay(
+        &self,
+        theme: &Theme,
+        ctx: &PaintCtx,
+        canvas: &mut dyn Canvas2D,
+        state: &State,
+    ) {
+        self.child.paint_overlay(theme, ctx, canvas, state);
+        if !self.state.shown {
+            return;
+        }
+
+        let style = ctx.style::<TooltipStyle>(theme);
+        let font = ctx.fonts().font(None, style.font_size);
+        let rect = self.popup_rect(ctx, self.popup_size(&style, &font));
+
+        let mut paint = Paint::default();
+        paint.set_anti_alias(true);
+        paint.set_color(style.background);
+        canvas.draw_rounded_rect(&rect, style.rounding, style.rounding, &paint);
+        if style.border.a() > 0 {
+            paint.set_color(style.border);
+            paint.set_stroke(true);
+            canvas.draw_rounded_rect(&rect, style.rounding, style.rounding, &paint);
+            paint.set_stroke(false);
+        }
+
+        match &self.content {
+            TooltipContent::Text(text) => {
+                let (_, metrics) = font.metrics();
+                let baseline = rect.center_y() - (metrics.ascent + metrics.descent) / 2.0;
+                if let Some(blob) = TextBlob::from_str(text, &font) {
+                    paint.set_color(style.text);
+                    canvas.draw_text_blob(
+                        &Point::new(rect.left + style.padding, baseline),
+                        &blob,
+                        &paint,
+                    )
+                }
+            }
+            TooltipContent::Widget(content) => {
+                canvas.save();
+                canvas.translate(&Point::new(
+                    rect.left + style.padding,
+                    rect.top + style.padding,
+                ));
+                content.paint(theme, ctx, canvas, state);
+                canvas.restore();
+            }
+        }
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut ChildSlot<State>> {
+        vec![&mut self.child]
+    }
+
+    fn children(&self) -> Vec<&ChildSlot<State>> {
+        vec![&self.child]
+    }
+
+    fn take_local_state(&mut self) -> Option<Box<dyn Any>> {
+        Some(Box::new(std::mem::take(&mut self.state)))
+    }
+
+    fn restore_local_state(&mut self, local_state: Box<dyn Any>) {
+        if let Ok(local_state) = local_state.downcast::<TooltipState>() {
+            self.state = *local_state
+        }
+    }
+}
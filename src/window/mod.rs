@@ -2,9 +2,11 @@ mod ui_window_delegate;
 mod window_delegate;
 mod window_event;
 mod window_registry;
+mod window_state;
 
 pub use ui_window_delegate::UiWindowDelegate;
 pub use window_delegate::WindowDelegate;
-pub use window_event::{MouseEvent, MouseEventType};
+pub use window_event::{MouseEvent, MouseEventType, WindowEvent};
 pub use window_registry::WindowRegistry;
+pub use window_state::{WindowState, WindowStateError, WindowStateStore};
 pub use winit::window::WindowId;
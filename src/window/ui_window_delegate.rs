@@ -1,13 +1,13 @@
 use crate::{
-    app::{App, AppState},
-    canvas::{skia_cpu_canvas::SkiaCanvas, Point},
+    app::{App, AppState, TimerId},
+    canvas::{skia_cpu_canvas::SkiaCanvas, Point, Rect},
     user_interface::UserInterface,
-    widget::Widget,
-    window::{MouseEvent, WindowDelegate},
+    widget::{accessibility::AccessibilityConsumer, query::WidgetTree, Command, Widget},
+    window::{MouseEvent, WindowDelegate, WindowEvent},
 };
 use std::{path::Path, rc::Rc};
 use wgpu::{Device, Queue};
-use winit::window::WindowId;
+use winit::{event::Ime, window::WindowId};
 
 struct UI<State: AppState> {
     canvas: SkiaCanvas,
@@ -20,6 +20,10 @@ pub struct UiWindowDelegate<State: AppState> {
     queue: Rc<Queue>,
     ui: Option<UI<State>>,
     builder: Box<dyn Fn(&State) -> Box<dyn Widget<State>>>,
+    theme: String,
+    accessibility: Option<Box<dyn AccessibilityConsumer>>,
+    // Known after the first resize, actions from assistive technology need it
+    window_id: Option<WindowId>,
 }
 
 impl<State: AppState + 'static> UiWindowDelegate<State> {
@@ -33,13 +37,48 @@ impl<State: AppState + 'static> UiWindowDelegate<State> {
             surface: None,
             ui: None,
             builder: Box::new(builder),
+            theme: "light".to_string(),
+            accessibility: None,
+            window_id: None,
         }
     }
 
-    fn render_ui(&mut self, state: &State) {
+    pub fn with_theme(mut self, theme: &str) -> Self {
+        self.theme = theme.to_string();
+        self
+    }
+
+    pub fn with_accessibility(mut self, consumer: Box<dyn AccessibilityConsumer>) -> Self {
+        self.accessibility = Some(consumer);
+        self
+    }
+
+    // Performs the actions that came in and hands the changes in the tree to the consumer
+    fn update_accessibility(&mut self, app: &App<State>, state: &State) {
+        if let (Some(ui), Some(consumer), Some(window_id)) = (
+            self.ui.as_mut(),
+            self.accessibility.as_mut(),
+            self.window_id,
+        ) {
+            for request in consumer.take_actions() {
+                ui.user_interface
+                    .access_action(app, state, window_id, &request)
+            }
+
+            if let Some(update) = ui.user_interface.accessibility_update(state) {
+                consumer.update(&update)
+            }
+        }
+    }
+
+    fn render_ui(&mut self, app: &App<State>, state: &State) {
         let size = self.ui.as_ref().unwrap().canvas.size;
         let pixels = if let Some(ui) = &mut self.ui {
-            ui.user_interface.paint(state, &mut ui.canvas);
+            // The theme may have been switched or reloaded with other fonts
+            ui.user_interface.update_fonts(app.style_ctx(), state);
+            ui.user_interface.update_images(state);
+            ui.user_interface
+                .paint(app.style_ctx(), app.frame_time(), state, &mut ui.canvas);
             ui.canvas.pixels()
         } else {
             None
@@ -184,6 +223,7 @@ impl<State: AppState + 'static> WindowDelegate<State> for UiWindowDelegate<State
         width: u32,
         height: u32,
     ) {
+        self.window_id = Some(window_id);
         if let Some(surface) = &self.surface {
             let config = wgpu::SurfaceConfiguration {
                 usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_DST,
@@ -208,14 +248,21 @@ impl<State: AppState + 'static> WindowDelegate<State> for UiWindowDelegate<State
             self.surface = Some(surface);
         }
 
-        let mut user_interface = UserInterface::new((self.builder)(state), "light");
-        user_interface.resize(state, width, height);
-        user_interface.resized(state, window_id);
+        if let Some(ui) = self.ui.as_mut() {
+            ui.canvas = SkiaCanvas::new(width as _, height as _);
+            ui.user_interface.resize(state, width, height);
+        } else {
+            let font = &app.style_ctx().theme_or_default(&self.theme).font;
+            let mut user_interface = UserInterface::new((self.builder)(state), &self.theme)
+                .with_fonts(app.fonts().with_style(font))
+                .with_images(app.images().clone());
+            user_interface.resize(state, width, height);
 
-        self.ui = Some(UI {
-            canvas: SkiaCanvas::new(width as _, height as _),
-            user_interface,
-        });
+            self.ui = Some(UI {
+                canvas: SkiaCanvas::new(width as _, height as _),
+                user_interface,
+            });
+        }
     }
 
     fn file_dropped(&mut self, state: &State, window_id: WindowId, path: &Path, x: f32, y: f32) {
@@ -232,13 +279,14 @@ impl<State: AppState + 'static> WindowDelegate<State> for UiWindowDelegate<State
         }
     }
 
-    fn draw(&mut self, _: &App<State>, state: &State) {
+    fn draw(&mut self, app: &App<State>, state: &State) {
+        self.update_accessibility(app, state);
         // draw user interface
-        self.render_ui(state)
+        self.render_ui(app, state)
     }
 
-    fn close_button_pressed(&mut self, _state: &State, _: WindowId) -> bool {
-        true
+    fn close_button_pressed(&mut self, state: &State, window_id: WindowId) -> bool {
+        state.can_close_window(window_id)
     }
 
     fn keyboard_event(
@@ -266,5 +314,96 @@ impl<State: AppState + 'static> WindowDelegate<State> for UiWindowDelegate<State
         }
     }
 
+    fn state_changed(&mut self, state: &State) {
+        self.rebuild(state)
+    }
+
+    fn set_theme(&mut self, theme: &str) {
+        self.theme = theme.to_string();
+        if let Some(ui) = self.ui.as_mut() {
+            ui.user_interface.set_theme(theme)
+        }
+    }
+
+    fn timer_fired(
+        &mut self,
+        app: &mut App<State>,
+        state: &State,
+        window_id: WindowId,
+        timer: TimerId,
+    ) {
+        if let Some(ui) = self.ui.as_mut() {
+            ui.user_interface.timer_fired(app, state, window_id, timer)
+        }
+    }
+
+    fn window_event(
+        &mut self,
+        app: &App<State>,
+        state: &State,
+        window_id: WindowId,
+        event: &WindowEvent,
+    ) {
+        if let Some(ui) = self.ui.as_mut() {
+            ui.user_interface.window_event(app, state, window_id, event)
+        }
+    }
+
+    fn set_builder(
+        &mut self,
+        builder: Box<dyn Fn(&State) -> Box<dyn Widget<State>>>,
+        state: &State,
+    ) {
+        self.builder = builder;
+        self.rebuild(state)
+    }
+
+    fn rebuild(&mut self, state: &State) {
+        if let Some(ui) = self.ui.as_mut() {
+            ui.user_interface.rebuild((self.builder)(state), state)
+        }
+    }
+
+    fn relayout(&mut self, state: &State) {
+        if let Some(ui) = self.ui.as_mut() {
+            ui.user_interface.relayout(state)
+        }
+    }
+
+    fn command(&mut self, app: &App<State>, state: &State, window_id: WindowId, command: &Command) {
+        if let Some(ui) = self.ui.as_mut() {
+            ui.user_interface.command(app, state, window_id, command)
+        }
+    }
+
+    fn focus_keys(&self) -> Vec<String> {
+        self.ui
+            .as_ref()
+            .map(|ui| ui.user_interface.focus_keys())
+            .unwrap_or_default()
+    }
+
+    fn ime_event(&mut self, app: &mut App<State>, state: &State, window_id: WindowId, event: &Ime) {
+        if let Some(ui) = self.ui.as_mut() {
+            ui.user_interface.ime_event(app, state, window_id, event)
+        }
+    }
+
+    fn caret_rect(&self, state: &State) -> Option<Rect> {
+        self.ui
+            .as_ref()
+            .and_then(|ui| ui.user_interface.caret_rect(state))
+    }
+
+    fn widget_tree(&self) -> Option<Rc<WidgetTree>> {
+        self.ui.as_ref().map(|ui| ui.user_interface.tree())
+    }
+
+    fn is_animating(&self) -> bool {
+        self.ui
+            .as_ref()
+            .map_or(false, |ui| ui.user_interface.is_animating())
+    }
+
     fn update(&mut self, _state: &State) {}
 }
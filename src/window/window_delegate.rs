@@ -1,6 +1,11 @@
-use crate::app::{App, AppState};
-use std::path::Path;
-use winit::window::WindowId;
+use super::WindowEvent;
+use crate::{
+    app::{App, AppState, TimerId},
+    canvas::Rect,
+    widget::{query::WidgetTree, Command, Widget},
+};
+use std::{path::Path, rc::Rc};
+use winit::{event::Ime, window::WindowId};
 
 pub trait WindowDelegate<State: AppState> {
     fn close_button_pressed(&mut self, state: &State, window_id: WindowId) -> bool;
@@ -74,5 +79,91 @@ pub trait WindowDelegate<State: AppState> {
 
     fn draw(&mut self, app: &App<State>, state: &State);
 
+    fn state_changed(&mut self, state: &State);
+
+    fn set_theme(&mut self, theme: &str) {
+        let _ = theme;
+    }
+
+    fn timer_fired(
+        &mut self,
+        app: &mut App<State>,
+        state: &State,
+        window_id: WindowId,
+        timer: TimerId,
+    ) {
+        let _ = app;
+        let _ = state;
+        let _ = window_id;
+        let _ = timer;
+    }
+
+    fn window_event(
+        &mut self,
+        app: &App<State>,
+        state: &State,
+        window_id: WindowId,
+        event: &WindowEvent,
+    ) {
+        let _ = app;
+        let _ = state;
+        let _ = window_id;
+        let _ = event;
+    }
+
+    // Replaces the builder of the widget tree, see WindowUpdate::SetRoot
+    fn set_builder(
+        &mut self,
+        builder: Box<dyn Fn(&State) -> Box<dyn Widget<State>>>,
+        state: &State,
+    ) {
+        let _ = builder;
+        let _ = state;
+    }
+
+    fn rebuild(&mut self, state: &State) {
+        let _ = state;
+    }
+
+    fn relayout(&mut self, state: &State) {
+        let _ = state;
+    }
+
+    fn command(&mut self, app: &App<State>, state: &State, window_id: WindowId, command: &Command) {
+        let _ = app;
+        let _ = state;
+        let _ = window_id;
+        let _ = command;
+    }
+
+    fn widget_tree(&self) -> Option<Rc<WidgetTree>> {
+        None
+    }
+
+    // Keys of the focused widget and its ancestors, innermost first. Shortcuts scoped to a widget
+    // only work while it's in there.
+    fn focus_keys(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn ime_event(&mut self, app: &mut App<State>, state: &State, window_id: WindowId, event: &Ime) {
+        let _ = app;
+        let _ = state;
+        let _ = window_id;
+        let _ = event;
+    }
+
+    // Where the caret of the focused text input is, in physical pixels. Input methods are only enabled
+    // while there is one.
+    fn caret_rect(&self, state: &State) -> Option<Rect> {
+        let _ = state;
+        None
+    }
+
+    // Windows that are animating are drawn continuously instead of only after events
+    fn is_animating(&self) -> bool {
+        false
+    }
+
     fn update(&mut self, state: &State);
 }
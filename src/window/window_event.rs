@@ -1,4 +1,18 @@
-use crate::canvas::Point;
+use crate::canvas::{Point, Size};
+
+// Things that happen to a window as a whole, positions and sizes are in physical pixels
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowEvent {
+    CloseRequested,
+    Focused,
+    Blurred,
+    Moved(Point),
+    Resized(Size),
+    Occluded(bool),
+    // The system switched between light and dark mode
+    ThemeChanged(winit::window::Theme),
+    CursorLeft,
+}
 
 #[derive(PartialEq, Eq, Hash)]
 pub enum MouseEventType {
@@ -1,43 +1,161 @@
-use super::{WindowDelegate, WindowId};
-use crate::app::{App, AppState};
-use std::{collections::HashMap, path::Path};
+use super::{WindowDelegate, WindowEvent, WindowId, WindowStateStore};
+use crate::{
+    app::{
+        App, AppError, AppEvent, AppState, TimerCommand, TimerId, WindowChange, WindowInfo,
+        WindowRequest, WindowUpdate,
+    },
+    canvas::{Rect, Size},
+};
+use std::{collections::HashMap, path::Path, sync::mpsc};
 use winit::{
+    dpi::{LogicalPosition, LogicalSize, PhysicalPosition, PhysicalSize},
     error::OsError,
+    event::Ime,
     event_loop::EventLoopWindowTarget,
-    window::{Window, WindowBuilder},
+    window::{Fullscreen, Window, WindowBuilder},
 };
 
-pub struct WindowRegistry<State: 'static> {
+pub struct WindowRegistry<State: AppState + 'static> {
     entries: HashMap<WindowId, Entry<State>>,
+    state_store: Option<WindowStateStore>,
+    // Cancels the timers widgets started in a window when it closes
+    timer_tx: Option<mpsc::Sender<TimerCommand<State::Message>>>,
+    // Reported to the state by the app, see take_errors
+    errors: Vec<AppError>,
 }
 
 pub struct Entry<State> {
     pub window: Window,
     delegate: Box<dyn WindowDelegate<State>>,
+    parent: Option<WindowId>,
+    modal: bool,
+    name: Option<String>,
+    // The caret the input method was last placed at, None while it's disabled
+    ime: Option<Rect>,
+}
+
+impl<State> Entry<State> {
+    pub fn apply(&self, change: WindowChange) {
+        let window = &self.window;
+        match change {
+            WindowChange::Title(title) => window.set_title(&title),
+            WindowChange::Size(width, height) => {
+                window.set_inner_size(LogicalSize::new(width, height))
+            }
+            WindowChange::Position(x, y) => window.set_outer_position(LogicalPosition::new(x, y)),
+            WindowChange::MinSize(size) => window
+                .set_min_inner_size(size.map(|(width, height)| LogicalSize::new(width, height))),
+            WindowChange::MaxSize(size) => window
+                .set_max_inner_size(size.map(|(width, height)| LogicalSize::new(width, height))),
+            WindowChange::Resizable(resizable) => window.set_resizable(resizable),
+            WindowChange::Fullscreen(fullscreen) => {
+                window.set_fullscreen(fullscreen.then_some(Fullscreen::Borderless(None)))
+            }
+            WindowChange::Maximized(maximized) => window.set_maximized(maximized),
+            WindowChange::Minimized(minimized) => window.set_minimized(minimized),
+            WindowChange::Decorations(decorations) => window.set_decorations(decorations),
+            WindowChange::AlwaysOnTop(always_on_top) => window.set_always_on_top(always_on_top),
+        }
+    }
+}
+
+impl<State: AppState> Entry<State> {
+    // Input methods follow the caret of the focused text input, and are disabled when there is none
+    fn update_ime(&mut self, state: &State) {
+        let caret = self.delegate.caret_rect(state);
+        if caret == self.ime {
+            return;
+        }
+
+        if caret.is_some() != self.ime.is_some() {
+            self.window.set_ime_allowed(caret.is_some())
+        }
+        if let Some(caret) = caret {
+            self.window
+                .set_ime_position(PhysicalPosition::new(caret.left, caret.bottom))
+        }
+        self.ime = caret
+    }
 }
 
 impl<State: AppState> WindowRegistry<State> {
     pub fn new() -> Self {
         Self {
             entries: HashMap::new(),
+            state_store: None,
+            timer_tx: None,
+            errors: Vec::new(),
         }
     }
 
+    pub(crate) fn set_timer_tx(&mut self, timer_tx: mpsc::Sender<TimerCommand<State::Message>>) {
+        self.timer_tx = Some(timer_tx)
+    }
+
     pub fn get_mut(&mut self, id: WindowId) -> Option<&mut Entry<State>> {
         self.entries.get_mut(&id)
     }
 
     pub fn create_window(
         &self,
-        target: &EventLoopWindowTarget<()>,
-        title: &str,
-        width: u32,
-        height: u32,
+        target: &EventLoopWindowTarget<AppEvent>,
+        request: &WindowRequest<State>,
     ) -> Result<Window, OsError> {
-        WindowBuilder::new()
-            .with_title(title)
-            .with_inner_size(winit::dpi::LogicalSize { width, height })
-            .build(target)
+        let mut builder = WindowBuilder::new()
+            .with_title(request.title.as_deref().unwrap_or("Untitled"))
+            .with_inner_size(LogicalSize::new(request.width, request.height))
+            .with_resizable(request.resizable)
+            .with_maximized(request.maximized)
+            .with_decorations(request.decorations)
+            .with_transparent(request.transparent)
+            .with_always_on_top(request.always_on_top);
+        if let Some((width, height)) = request.min_size {
+            builder = builder.with_min_inner_size(LogicalSize::new(width, height))
+        }
+        if let Some((width, height)) = request.max_size {
+            builder = builder.with_max_inner_size(LogicalSize::new(width, height))
+        }
+        if request.fullscreen {
+            builder = builder.with_fullscreen(Some(Fullscreen::Borderless(None)))
+        }
+
+        // A named window reopens where it was left
+        let mut restored_position = None;
+        if let Some(saved) = request
+            .name
+            .as_deref()
+            .and_then(|name| self.state_store.as_ref()?.get(name))
+        {
+            if let Some((width, height)) = saved.size {
+                builder = builder.with_inner_size(PhysicalSize::new(width, height))
+            }
+            builder = builder.with_maximized(saved.maximized);
+            // The position is only restored when the monitor it was on is still connected
+            let monitor_connected = saved.monitor.is_none()
+                || target
+                    .available_monitors()
+                    .any(|monitor| monitor.name() == saved.monitor);
+            if monitor_connected {
+                restored_position = saved.position
+            }
+        }
+
+        let parent = request.parent.and_then(|id| self.entries.get(&id));
+        if let Some((x, y)) = restored_position {
+            builder = builder.with_position(PhysicalPosition::new(x, y))
+        } else if let Some((x, y)) = request.position {
+            builder = builder.with_position(LogicalPosition::new(x, y))
+        } else if let Some(parent) = parent {
+            builder =
+                builder.with_position(centered_on(&parent.window, request.width, request.height))
+        }
+
+        let window = builder.build(target)?;
+        if request.minimized {
+            window.set_minimized(true)
+        }
+
+        Ok(window)
     }
 
     pub fn register_with_delegate(
@@ -45,7 +163,113 @@ impl<State: AppState> WindowRegistry<State> {
         window: Window,
         delegate: Box<dyn WindowDelegate<State>>,
     ) {
-        self.entries.insert(window.id(), Entry { window, delegate });
+        self.entries.insert(
+            window.id(),
+            Entry {
+                window,
+                delegate,
+                parent: None,
+                modal: false,
+                name: None,
+                ime: None,
+            },
+        );
+    }
+
+    // Opts in to remembering the position and size of named windows
+    pub fn set_state_store(&mut self, store: WindowStateStore) {
+        self.state_store = Some(store)
+    }
+
+    pub fn state_store(&self) -> Option<&WindowStateStore> {
+        self.state_store.as_ref()
+    }
+
+    // The stable name the state of the window is stored under
+    pub fn set_name(&mut self, id: WindowId, name: &str) {
+        if let Some(entry) = self.entries.get_mut(&id) {
+            entry.name = Some(name.to_string())
+        }
+    }
+
+    pub(crate) fn name(&self, id: &WindowId) -> Option<&str> {
+        self.entries.get(id).and_then(|entry| entry.name.as_deref())
+    }
+
+    pub(crate) fn focus_keys(&self, id: &WindowId) -> Vec<String> {
+        self.entries
+            .get(id)
+            .map(|entry| entry.delegate.focus_keys())
+            .unwrap_or_default()
+    }
+
+    fn record_state(&mut self, id: &WindowId) {
+        if let (Some(store), Some(entry)) = (self.state_store.as_mut(), self.entries.get(id)) {
+            if let Some(name) = &entry.name {
+                store.record(name, &entry.window)
+            }
+        }
+    }
+
+    fn save_state(&mut self) {
+        if let Some(store) = self.state_store.as_mut() {
+            if let Err(error) = store.save_if_needed() {
+                self.errors.push(AppError::SaveWindowState {
+                    path: store.path().to_path_buf(),
+                    error,
+                })
+            }
+        }
+    }
+
+    // Child windows close with their parent. A modal child also blocks the input of its parent.
+    pub fn set_parent(&mut self, child: WindowId, parent: WindowId, modal: bool) {
+        if let Some(entry) = self.entries.get_mut(&child) {
+            entry.parent = Some(parent);
+            entry.modal = modal;
+        }
+    }
+
+    pub fn parent(&self, id: WindowId) -> Option<WindowId> {
+        self.entries.get(&id).and_then(|entry| entry.parent)
+    }
+
+    // The modal child that currently blocks the window, if any
+    pub fn modal_child(&self, id: WindowId) -> Option<WindowId> {
+        self.entries
+            .iter()
+            .find(|(_, entry)| entry.modal && entry.parent == Some(id))
+            .map(|(child, _)| *child)
+    }
+
+    pub(crate) fn is_blocked(&self, id: &WindowId) -> bool {
+        self.modal_child(*id).is_some()
+    }
+
+    // Closes the window together with all of its child windows
+    pub fn close(&mut self, id: &WindowId) {
+        self.record_state(id);
+        let children: Vec<WindowId> = self
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.parent == Some(*id))
+            .map(|(child, _)| *child)
+            .collect();
+        for child in children {
+            self.close(&child)
+        }
+
+        if self.entries.remove(id).is_some() {
+            if let Some(timer_tx) = &self.timer_tx {
+                let _ = timer_tx.send(TimerCommand::CancelWindow(*id));
+            }
+        }
+        self.save_state();
+    }
+
+    // Failures since the last call, of work the registry does on its own like saving the window state
+    pub(crate) fn take_errors(&mut self) -> Vec<AppError> {
+        std::mem::take(&mut self.errors)
     }
 
     pub fn active_window_count(&self) -> usize {
@@ -56,6 +280,86 @@ impl<State: AppState> WindowRegistry<State> {
         for entry in self.entries.values_mut() {
             entry.delegate.update(state)
         }
+
+        // Moves and resizes come in bursts, they're written at most once per frame
+        self.save_state()
+    }
+
+    pub(crate) fn state_changed(&mut self, state: &State) {
+        for entry in self.entries.values_mut() {
+            entry.delegate.state_changed(state)
+        }
+    }
+
+    pub(crate) fn timer_fired(
+        &mut self,
+        app: &mut App<State>,
+        state: &State,
+        id: &WindowId,
+        timer: TimerId,
+    ) {
+        if let Some(entry) = self.entries.get_mut(id) {
+            entry.delegate.timer_fired(app, state, *id, timer)
+        }
+    }
+
+    pub(crate) fn window_event(
+        &mut self,
+        app: &App<State>,
+        state: &State,
+        id: &WindowId,
+        event: &WindowEvent,
+    ) {
+        if let Some(entry) = self.entries.get_mut(id) {
+            entry.delegate.window_event(app, state, *id, event)
+        }
+    }
+
+    pub(crate) fn update_window(
+        &mut self,
+        app: &App<State>,
+        state: &State,
+        id: &WindowId,
+        update: WindowUpdate<State>,
+    ) {
+        if let Some(entry) = self.entries.get_mut(id) {
+            match update {
+                WindowUpdate::SetRoot(builder) => entry.delegate.set_builder(builder, state),
+                WindowUpdate::Rebuild => entry.delegate.rebuild(state),
+                WindowUpdate::Relayout => entry.delegate.relayout(state),
+                WindowUpdate::Repaint => entry.window.request_redraw(),
+                WindowUpdate::Command(command) => entry.delegate.command(app, state, *id, &command),
+            }
+        }
+    }
+
+    pub(crate) fn window_infos(&self) -> HashMap<WindowId, WindowInfo> {
+        self.entries
+            .iter()
+            .map(|(id, entry)| {
+                let size = entry.window.inner_size();
+                let info = WindowInfo {
+                    id: *id,
+                    name: entry.name.clone(),
+                    size: Size::new(size.width as f32, size.height as f32),
+                    scale_factor: entry.window.scale_factor(),
+                    tree: entry.delegate.widget_tree().unwrap_or_default(),
+                };
+                (*id, info)
+            })
+            .collect()
+    }
+
+    pub(crate) fn is_animating(&self) -> bool {
+        self.entries
+            .values()
+            .any(|entry| entry.delegate.is_animating())
+    }
+
+    pub(crate) fn set_theme(&mut self, id: &WindowId, theme: &str) {
+        if let Some(entry) = self.entries.get_mut(id) {
+            entry.delegate.set_theme(theme)
+        }
     }
 
     pub(crate) fn window_resized(
@@ -70,6 +374,8 @@ impl<State: AppState> WindowRegistry<State> {
                 .delegate
                 .resized(&entry.window, app, state, *id, size.width, size.height)
         }
+
+        self.record_state(id)
     }
 
     pub(crate) fn character_received(
@@ -79,6 +385,10 @@ impl<State: AppState> WindowRegistry<State> {
         character: char,
         state: &State,
     ) {
+        if self.is_blocked(id) {
+            return;
+        }
+
         if let Some(entry) = self.entries.get_mut(id) {
             entry
                 .delegate
@@ -93,19 +403,37 @@ impl<State: AppState> WindowRegistry<State> {
         event: &winit::event::KeyboardInput,
         state: &State,
     ) {
+        if self.is_blocked(id) {
+            return;
+        }
+
         if let Some(entry) = self.entries.get_mut(id) {
             entry.delegate.keyboard_event(app, state, *id, event)
         }
     }
 
     pub(crate) fn close_button_pressed(&mut self, id: &WindowId, state: &State) {
+        if self.is_blocked(id) {
+            return;
+        }
+
         if let Some(entry) = self.entries.get_mut(id) {
             if entry.delegate.close_button_pressed(state, *id) {
-                self.entries.remove(id);
+                self.close(id);
             }
         }
     }
 
+    // A window that is blocked by a modal child hands the focus to that child
+    pub(crate) fn window_focused(&self, id: &WindowId) {
+        if let Some(entry) = self
+            .modal_child(*id)
+            .and_then(|child| self.entries.get(&child))
+        {
+            entry.window.focus_window()
+        }
+    }
+
     pub(crate) fn mouse_moved(
         &mut self,
         app: &mut App<State>,
@@ -113,6 +441,10 @@ impl<State: AppState> WindowRegistry<State> {
         id: &WindowId,
         position: &winit::dpi::PhysicalPosition<f64>,
     ) {
+        if self.is_blocked(id) {
+            return;
+        }
+
         if let Some(entry) = self.entries.get_mut(id) {
             entry
                 .delegate
@@ -128,6 +460,10 @@ impl<State: AppState> WindowRegistry<State> {
         position: &winit::dpi::PhysicalPosition<f64>,
         delta: &winit::dpi::PhysicalPosition<f64>,
     ) {
+        if self.is_blocked(id) {
+            return;
+        }
+
         if let Some(entry) = self.entries.get_mut(id) {
             entry.delegate.mouse_dragged(
                 app,
@@ -148,6 +484,10 @@ impl<State: AppState> WindowRegistry<State> {
         id: &winit::window::WindowId,
         position: &winit::dpi::PhysicalPosition<f64>,
     ) {
+        if self.is_blocked(id) {
+            return;
+        }
+
         if let Some(entry) = self.entries.get_mut(id) {
             entry
                 .delegate
@@ -162,6 +502,10 @@ impl<State: AppState> WindowRegistry<State> {
         id: &winit::window::WindowId,
         position: &winit::dpi::PhysicalPosition<f64>,
     ) {
+        if self.is_blocked(id) {
+            return;
+        }
+
         if let Some(entry) = self.entries.get_mut(id) {
             entry
                 .delegate
@@ -170,20 +514,38 @@ impl<State: AppState> WindowRegistry<State> {
     }
 
     pub(crate) fn window_moved(
-        &self,
-        _: &winit::window::WindowId,
+        &mut self,
+        id: &winit::window::WindowId,
         _: &winit::dpi::PhysicalPosition<i32>,
     ) {
+        self.record_state(id)
     }
 
     pub(crate) fn draw(&mut self, app: &App<State>, state: &State) {
         for entry in self.entries.values_mut() {
-            entry.delegate.draw(app, state)
+            entry.delegate.draw(app, state);
+            entry.update_ime(state)
+        }
+    }
+
+    pub(crate) fn ime_event(
+        &mut self,
+        id: &WindowId,
+        app: &mut App<State>,
+        event: &Ime,
+        state: &State,
+    ) {
+        if self.is_blocked(id) {
+            return;
+        }
+
+        if let Some(entry) = self.entries.get_mut(id) {
+            entry.delegate.ime_event(app, state, *id, event)
         }
     }
 
     pub(crate) fn window_destroyed(&mut self, id: &WindowId) {
-        self.entries.remove(id);
+        self.close(id);
     }
 
     pub(crate) fn file_dropped(
@@ -193,6 +555,10 @@ impl<State: AppState> WindowRegistry<State> {
         file: &Path,
         position: &winit::dpi::PhysicalPosition<f64>,
     ) {
+        if self.is_blocked(id) {
+            return;
+        }
+
         if let Some(entry) = self.entries.get_mut(id) {
             entry
                 .delegate
@@ -207,6 +573,10 @@ impl<State: AppState> WindowRegistry<State> {
         file: &Path,
         position: &winit::dpi::PhysicalPosition<f64>,
     ) {
+        if self.is_blocked(id) {
+            return;
+        }
+
         if let Some(entry) = self.entries.get_mut(id) {
             entry
                 .delegate
@@ -220,3 +590,13 @@ impl<State: AppState> Default for WindowRegistry<State> {
         Self::new()
     }
 }
+
+fn centered_on(parent: &Window, width: u32, height: u32) -> PhysicalPosition<i32> {
+    let origin = parent.outer_position().unwrap_or_default();
+    let parent_size = parent.outer_size();
+    let size = LogicalSize::new(width, height).to_physical::<i32>(parent.scale_factor());
+    PhysicalPosition::new(
+        origin.x + (parent_size.width as i32 - size.width) / 2,
+        origin.y + (parent_size.height as i32 - size.height) / 2,
+    )
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+use winit::window::Window;
+
+#[derive(Debug, Error)]
+pub enum WindowStateError {
+    #[error("failed to access window state file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid window state file: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the platform has no config directory")]
+    NoConfigDir,
+}
+
+// Where a window was and how big it was, in physical pixels. A window that has only been seen
+// maximized has neither, its normal size isn't known yet.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub position: Option<(i32, i32)>,
+    pub size: Option<(u32, u32)>,
+    pub maximized: bool,
+    pub monitor: Option<String>,
+}
+
+// Remembers the state of named windows across runs, see WindowRequest::with_name
+pub struct WindowStateStore {
+    path: PathBuf,
+    states: HashMap<String, WindowState>,
+    dirty: bool,
+}
+
+impl WindowStateStore {
+    // A missing file is an empty store, it's created on the first save
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, WindowStateError> {
+        let path = path.into();
+        let states = match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)?,
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(error) => return Err(error.into()),
+        };
+
+        Ok(Self {
+            path,
+            states,
+            dirty: false,
+        })
+    }
+
+    // Stores the state in window_state.json in a directory for the app in the user's config directory
+    pub fn in_config_dir(app_name: &str) -> Result<Self, WindowStateError> {
+        let directory = dirs::config_dir().ok_or(WindowStateError::NoConfigDir)?;
+        Self::load(directory.join(app_name).join("window_state.json"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self, name: &str) -> Option<&WindowState> {
+        self.states.get(name)
+    }
+
+    // A maximized window keeps the position and size it had before, so it restores to them
+    pub(crate) fn record(&mut self, name: &str, window: &Window) {
+        let maximized = window.is_maximized();
+        let previous = self.states.get(name);
+        let state = match previous {
+            Some(previous) if maximized => WindowState {
+                maximized,
+                ..previous.clone()
+            },
+            // Its size is the screen's, not the one to restore to
+            None if maximized => WindowState {
+                position: None,
+                size: None,
+                maximized,
+                monitor: window.current_monitor().and_then(|monitor| monitor.name()),
+            },
+            _ => {
+                let size = window.inner_size();
+                WindowState {
+                    position: window.outer_position().ok().map(|p| (p.x, p.y)),
+                    size: Some((size.width, size.height)),
+                    maximized,
+                    monitor: window.current_monitor().and_then(|monitor| monitor.name()),
+                }
+            }
+        };
+
+        if previous != Some(&state) {
+            self.states.insert(name.to_string(), state);
+            self.dirty = true
+        }
+    }
+
+    pub fn save(&mut self) -> Result<(), WindowStateError> {
+        if let Some(directory) = self.path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+
+        fs::write(&self.path, serde_json::to_string_pretty(&self.states)?)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    // A save that fails isn't retried until the state changes again, so the failure is reported once
+    pub(crate) fn save_if_needed(&mut self) -> Result<(), WindowStateError> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        self.dirty = false;
+        self.save()
+    }
+}